@@ -0,0 +1,136 @@
+//! Generates the instruction metadata tables (length, cycles, mnemonic, operands and
+//! flags) from ressources/instruction_set.json. The operations themselves are written
+//! by hand in src/gameboy/cpu/instructions.rs and src/gameboy/cpu/cb_instructions.rs.
+
+use serde_json::Value;
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+const INSTRUCTION_SET: &str = "ressources/instruction_set.json";
+
+fn flag_effect(value: &Value) -> &'static str {
+    match value.as_str() {
+        Some("-") => "FlagEffect::Unaffected",
+        Some("0") => "FlagEffect::Reset",
+        Some("1") => "FlagEffect::Set",
+        Some(_) => "FlagEffect::Affected",
+        None => panic!("invalid flag effect {}", value),
+    }
+}
+
+fn generate_operand(operand: &Value) -> String {
+    format!(
+        "Operand {{ name: {:?}, immediate: {}, bytes: {}, increment: {}, decrement: {} }}",
+        operand["name"].as_str().expect("operand without name"),
+        operand["immediate"].as_bool().unwrap_or(false),
+        operand["bytes"].as_u64().unwrap_or(0),
+        operand["increment"].as_bool().unwrap_or(false),
+        operand["decrement"].as_bool().unwrap_or(false),
+    )
+}
+
+/// Appends a 256 entries table, collecting the mnemonics met along the way
+fn generate_table(
+    output: &mut String,
+    table_name: &str,
+    instructions: &Value,
+    mnemonics: &mut Vec<String>,
+) {
+    writeln!(
+        output,
+        "pub const {}: [InstructionMetadata; 0x100] = [",
+        table_name
+    )
+    .unwrap();
+    for opcode in 0..0x100 {
+        let key = format!("0x{:02X}", opcode);
+        let instruction = &instructions[&key];
+        let mnemonic = instruction["mnemonic"]
+            .as_str()
+            .unwrap_or_else(|| panic!("{} {} has no mnemonic", table_name, key));
+        if !mnemonics.iter().any(|known| known == mnemonic) {
+            mnemonics.push(mnemonic.to_string());
+        }
+        let cycles: Vec<u64> = instruction["cycles"]
+            .as_array()
+            .unwrap_or_else(|| panic!("{} {} has no cycles", table_name, key))
+            .iter()
+            .map(|cycles| cycles.as_u64().unwrap())
+            .collect();
+        let operands: Vec<String> = instruction["operands"]
+            .as_array()
+            .unwrap_or_else(|| panic!("{} {} has no operands", table_name, key))
+            .iter()
+            .map(generate_operand)
+            .collect();
+        let flags = &instruction["flags"];
+
+        writeln!(output, "    InstructionMetadata {{").unwrap();
+        writeln!(output, "        opcode: {},", key).unwrap();
+        writeln!(output, "        mnemonic: InstructionCode::{},", mnemonic).unwrap();
+        writeln!(
+            output,
+            "        length: {},",
+            instruction["bytes"].as_u64().unwrap()
+        )
+        .unwrap();
+        // Conditional instructions have the cycles when branching first
+        writeln!(
+            output,
+            "        cycles: [{}, {}],",
+            cycles[0],
+            cycles[cycles.len() - 1]
+        )
+        .unwrap();
+        writeln!(output, "        operands: &[{}],", operands.join(", ")).unwrap();
+        writeln!(
+            output,
+            "        flags: FlagEffects {{ zero: {}, negative: {}, half_carry: {}, carry: {} }},",
+            flag_effect(&flags["Z"]),
+            flag_effect(&flags["N"]),
+            flag_effect(&flags["H"]),
+            flag_effect(&flags["C"]),
+        )
+        .unwrap();
+        writeln!(output, "    }},").unwrap();
+    }
+    writeln!(output, "];\n").unwrap();
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", INSTRUCTION_SET);
+    println!("cargo:rerun-if-changed=build.rs");
+
+    let content = fs::read_to_string(INSTRUCTION_SET).unwrap();
+    let instruction_set: Value = serde_json::from_str(&content).unwrap();
+
+    let mut tables = String::new();
+    let mut mnemonics = vec![];
+    generate_table(
+        &mut tables,
+        "INSTRUCTION_METADATA",
+        &instruction_set["unprefixed"],
+        &mut mnemonics,
+    );
+    generate_table(
+        &mut tables,
+        "CB_INSTRUCTION_METADATA",
+        &instruction_set["cbprefixed"],
+        &mut mnemonics,
+    );
+
+    let mut output = String::from("// Generated by build.rs from ") + INSTRUCTION_SET + "\n\n";
+    output += "#[allow(non_camel_case_types)]\n";
+    output += "#[derive(PartialEq, Debug, Clone, Copy)]\n";
+    output += "pub enum InstructionCode {\n";
+    for mnemonic in &mnemonics {
+        writeln!(output, "    {},", mnemonic).unwrap();
+    }
+    output += "}\n\n";
+    output += &tables;
+
+    let path = Path::new(&env::var("OUT_DIR").unwrap()).join("instruction_metadata.rs");
+    fs::write(path, output).unwrap();
+}
@@ -1,3 +1,6 @@
+use super::sgb::PACKET_SIZE;
+use std::cmp::Ordering;
+
 #[derive(Default)]
 pub struct Controller {
     up: bool,
@@ -13,6 +16,17 @@ pub struct Controller {
     button_keys_selected: bool,
 
     interrrupted: bool,
+
+    /// A Super Game Boy receives packets through P14 and P15
+    sgb: bool,
+    /// P14 and P15 as last written
+    select_lines: u8,
+    packet: [u8; PACKET_SIZE],
+    /// Bits of the packet received so far, None between packets
+    packet_bit: Option<usize>,
+    /// Joypads of the SGB multiplayer mode, the one read when P14 and P15 are high
+    player_count: u8,
+    player: u8,
 }
 
 impl Controller {
@@ -30,12 +44,24 @@ impl Controller {
             button_keys_selected: true,
             direction_keys_selected: true,
             interrrupted: false,
+
+            sgb: false,
+            select_lines: 0x30,
+            packet: [0; PACKET_SIZE],
+            packet_bit: None,
+            player_count: 1,
+            player: 0,
         }
     }
 }
 
 impl Controller {
     pub fn get_controller_status(&self) -> u8 {
+        // The SGB gives the joypad number instead, 0xf for the first one
+        if self.player_count > 1 && self.select_lines == 0x30 {
+            return 0x30 | (0x0f - self.player);
+        }
+
         let low_nibble_button = if self.button_keys_selected {
             ((self.start as u8) << 3)
                 | ((self.select as u8) << 2)
@@ -60,12 +86,80 @@ impl Controller {
             | (((!self.button_keys_selected) as u8) << 4)
     }
 
-    pub fn set_controller_status(&mut self, value: u8) {
+    /// Returns a packet once a Super Game Boy received all its bits
+    pub fn set_controller_status(&mut self, value: u8) -> Option<[u8; PACKET_SIZE]> {
         if (value >> 5) & 1 == 1 {
             self.button_keys_selected = true;
         } else {
             self.button_keys_selected = false;
         }
+
+        let previous = self.select_lines;
+        self.select_lines = value & 0x30;
+        if self.sgb {
+            self.receive_packet_bit(previous)
+        } else {
+            None
+        }
+    }
+
+    /// Select the keys read without sending a bit to the Super Game Boy
+    pub fn poke_controller_status(&mut self, value: u8) {
+        self.button_keys_selected = (value >> 5) & 1 == 1;
+        self.select_lines = value & 0x30;
+    }
+
+    /// Both P14 and P15 low start a packet, then each bit is a pulse of P14 low for a 0
+    /// or P15 low for a 1, with both high in between. 128 bits LSB first, then a 0.
+    // https://gbdev.io/pandocs/SGB_Command_Packet.html
+    fn receive_packet_bit(&mut self, previous: u8) -> Option<[u8; PACKET_SIZE]> {
+        match self.select_lines {
+            0x00 => {
+                self.packet = [0; PACKET_SIZE];
+                self.packet_bit = Some(0);
+                None
+            }
+            0x30 => {
+                match self.packet_bit {
+                    // End of the stop bit
+                    Some(bit) if bit > PACKET_SIZE * 8 => self.packet_bit = None,
+                    // P14 going high outside of packets selects the next joypad
+                    None if previous == 0x20 => {
+                        self.player = (self.player + 1) % self.player_count;
+                    }
+                    _ => (),
+                }
+                None
+            }
+            _ if previous != 0x30 => None,
+            select => {
+                let bit = self.packet_bit?;
+                let one = select == 0x10;
+                match bit.cmp(&(PACKET_SIZE * 8)) {
+                    Ordering::Less => {
+                        self.packet[bit / 8] |= (one as u8) << (bit % 8);
+                        self.packet_bit = Some(bit + 1);
+                        None
+                    }
+                    // The packet is dropped without a 0 stop bit
+                    Ordering::Equal => {
+                        self.packet_bit = Some(bit + 1);
+                        (!one).then_some(self.packet)
+                    }
+                    Ordering::Greater => None,
+                }
+            }
+        }
+    }
+
+    pub fn set_sgb(&mut self, enabled: bool) {
+        self.sgb = enabled;
+    }
+
+    /// MLT_REQ - 1, 2 or 4 joypads
+    pub fn set_player_count(&mut self, count: u8) {
+        self.player_count = count;
+        self.player = 0;
     }
 
     pub fn press_a(&mut self) {
@@ -153,6 +247,11 @@ impl Controller {
         self.right = false;
     }
 
+    /// Same as is_interrupted without clearing the request
+    pub fn has_interrupt(&self) -> bool {
+        self.interrrupted
+    }
+
     pub fn is_interrupted(&mut self) -> bool {
         let value = self.interrrupted;
         self.interrrupted = false;
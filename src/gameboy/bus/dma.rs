@@ -0,0 +1,87 @@
+// https://gbdev.io/pandocs/OAM_DMA_Transfer.html
+
+use crate::gameboy::ppu::OAM_SIZE;
+
+/// Clock cycles between two bytes copied, one M-cycle
+const BYTE_CYCLES: u8 = 4;
+
+/// The transfer starts one M-cycle after FF46 is written
+const START_DELAY: u8 = 4;
+
+/// Copy of OAM_SIZE bytes from DMA << 8 to OAM, one byte per M-cycle. While the
+/// transfer runs the CPU can only access HRAM and the I/O registers.
+pub struct Dma {
+    /// DMA - High byte of the source address, reads back the last value written
+    register: u8,
+    /// Source of a transfer about to start and the clock cycles before it does,
+    /// a running transfer goes on until then
+    pending: Option<(u16, u8)>,
+    source: u16,
+    /// Next byte copied, None when no transfer is running
+    offset: Option<usize>,
+    remaining_cycles: u8,
+}
+
+impl Default for Dma {
+    fn default() -> Dma {
+        Dma {
+            register: 0xff,
+            pending: None,
+            source: 0,
+            offset: None,
+            remaining_cycles: 0,
+        }
+    }
+}
+
+impl Dma {
+    pub fn get_register(&self) -> u8 {
+        self.register
+    }
+
+    pub fn start(&mut self, value: u8) {
+        self.register = value;
+        self.pending = Some(((value as u16) << 8, START_DELAY));
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.offset.is_some()
+    }
+
+    /// The CPU reads 0xff and its writes are dropped outside of HRAM and I/O
+    pub fn is_blocking(&self, address: u16) -> bool {
+        self.is_active() && address < 0xff00
+    }
+
+    /// Advance by one clock cycle, returns the source address and the OAM offset
+    /// of the byte to copy if any
+    pub fn tick(&mut self) -> Option<(u16, usize)> {
+        if let Some((source, delay)) = self.pending {
+            if delay > 1 {
+                self.pending = Some((source, delay - 1));
+            } else {
+                self.pending = None;
+                self.source = source;
+                self.offset = Some(0);
+                self.remaining_cycles = BYTE_CYCLES;
+                return None;
+            }
+        }
+
+        let offset = self.offset?;
+        self.remaining_cycles -= 1;
+        if self.remaining_cycles > 0 {
+            return None;
+        }
+        self.remaining_cycles = BYTE_CYCLES;
+        self.offset = (offset + 1 < OAM_SIZE).then_some(offset + 1);
+        // Sources past the work RAM read its echo
+        let source = self.source + offset as u16;
+        let source = if source >= 0xe000 {
+            source - 0x2000
+        } else {
+            source
+        };
+        Some((source, offset))
+    }
+}
@@ -0,0 +1,59 @@
+use super::MemoryBus;
+
+const MEMORY_SIZE: usize = 0x10000;
+
+/// 64 KiB of plain RAM without any I/O nor mapping, to test the CPU in isolation
+/// or run Game Boy code snippets.
+pub struct FlatBus {
+    memory: Vec<u8>,
+    cycles: u64,
+}
+
+impl Default for FlatBus {
+    fn default() -> FlatBus {
+        FlatBus::new()
+    }
+}
+
+impl FlatBus {
+    pub fn new() -> FlatBus {
+        FlatBus {
+            memory: vec![0; MEMORY_SIZE],
+            cycles: 0,
+        }
+    }
+
+    /// Copy data in memory starting at address, wrapping at the end of the address space
+    pub fn load(&mut self, address: u16, data: &[u8]) {
+        for (offset, value) in data.iter().enumerate() {
+            self.memory[address.wrapping_add(offset as u16) as usize] = *value;
+        }
+    }
+
+    /// Number of clock cycles elapsed
+    pub fn get_cycles(&self) -> u64 {
+        self.cycles
+    }
+}
+
+impl MemoryBus for FlatBus {
+    fn read_8(&mut self, address: u16) -> u8 {
+        self.memory[address as usize]
+    }
+
+    fn write_8(&mut self, address: u16, value: u8) {
+        self.memory[address as usize] = value;
+    }
+
+    fn peek(&self, address: u16) -> u8 {
+        self.memory[address as usize]
+    }
+
+    fn poke(&mut self, address: u16, value: u8) {
+        self.memory[address as usize] = value;
+    }
+
+    fn tick(&mut self) {
+        self.cycles += 1;
+    }
+}
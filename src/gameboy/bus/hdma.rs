@@ -0,0 +1,117 @@
+// https://gbdev.io/pandocs/CGB_Registers.html#lcd-vram-dma-transfers
+
+/// Bytes copied per block, a general purpose transfer copies all its blocks in a row and
+/// an HBlank transfer one block per HBlank
+const BLOCK_SIZE: u16 = 0x10;
+
+/// PPU clock cycles per byte copied, in both speeds
+const BYTE_CYCLES: u8 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Transfer {
+    GeneralPurpose,
+    HBlank,
+}
+
+/// CGB VRAM DMA, copy of blocks of 16 bytes from ROM or RAM to the VRAM bank selected
+/// in VBK. The CPU is halted while a block is copied.
+#[derive(Default)]
+pub struct Hdma {
+    /// HDMA1-2 - Source address, the lower 4 bits are ignored
+    source: u16,
+    /// HDMA3-4 - Destination offset in VRAM, the lower 4 bits are ignored
+    destination: u16,
+    /// Blocks left minus one, as read from HDMA5
+    remaining_blocks: u8,
+    /// None once done or cancelled
+    transfer: Option<Transfer>,
+    /// Bytes left of the block being copied
+    block_bytes: u16,
+    remaining_cycles: u8,
+}
+
+impl Hdma {
+    /// HDMA1-5, HDMA1-4 are write only
+    pub fn write_register(&mut self, address: u16, value: u8) {
+        match address {
+            0xff51 => self.source = (value as u16) << 8 | self.source & 0x00ff,
+            0xff52 => self.source = self.source & 0xff00 | (value & 0xf0) as u16,
+            0xff53 => self.destination = ((value & 0x1f) as u16) << 8 | self.destination & 0x00ff,
+            0xff54 => self.destination = self.destination & 0x1f00 | (value & 0xf0) as u16,
+            0xff55 => self.start(value),
+            _ => panic!("Not a VRAM DMA register address"),
+        }
+    }
+
+    /// HDMA5 - Blocks left minus one while a transfer runs, bit 7 is set once it is
+    /// over: 0xff when done, the blocks left when cancelled
+    pub fn get_status(&self) -> u8 {
+        match self.transfer {
+            Some(_) => self.remaining_blocks,
+            None => 0x80 | self.remaining_blocks,
+        }
+    }
+
+    /// Bit 7 selects an HBlank transfer instead of a general purpose one, writing it
+    /// clear while an HBlank transfer runs cancels it
+    fn start(&mut self, value: u8) {
+        if self.transfer == Some(Transfer::HBlank) && value & 0x80 == 0 {
+            self.transfer = None;
+            return;
+        }
+        self.remaining_blocks = value & 0x7f;
+        if value & 0x80 != 0 {
+            self.transfer = Some(Transfer::HBlank);
+        } else {
+            self.transfer = Some(Transfer::GeneralPurpose);
+            self.start_block();
+        }
+    }
+
+    /// The PPU entered HBlank on a visible line
+    pub fn hblank(&mut self) {
+        if self.transfer == Some(Transfer::HBlank) && self.block_bytes == 0 {
+            self.start_block();
+        }
+    }
+
+    fn start_block(&mut self) {
+        self.block_bytes = BLOCK_SIZE;
+        self.remaining_cycles = BYTE_CYCLES;
+    }
+
+    /// A block is being copied, the CPU waits for it
+    pub fn is_active(&self) -> bool {
+        self.block_bytes > 0
+    }
+
+    /// Advance by one PPU clock cycle, returns the source address and the VRAM offset
+    /// of the byte to copy if any
+    pub fn tick(&mut self) -> Option<(u16, usize)> {
+        if self.block_bytes == 0 {
+            return None;
+        }
+        self.remaining_cycles -= 1;
+        if self.remaining_cycles > 0 {
+            return None;
+        }
+        self.remaining_cycles = BYTE_CYCLES;
+        let copy = (self.source, self.destination as usize);
+        self.source = self.source.wrapping_add(1);
+        self.destination = (self.destination + 1) & 0x1fff;
+        self.block_bytes -= 1;
+        if self.block_bytes == 0 {
+            self.end_block();
+        }
+        Some(copy)
+    }
+
+    fn end_block(&mut self) {
+        self.remaining_blocks = self.remaining_blocks.wrapping_sub(1) & 0x7f;
+        if self.remaining_blocks == 0x7f {
+            self.transfer = None;
+        } else if self.transfer == Some(Transfer::GeneralPurpose) {
+            self.start_block();
+        }
+    }
+}
@@ -19,99 +19,535 @@ const BOOT_SEQUENCE: [u8; BOOT_SEQUENCE_SIZE] = [
 ];
 
 mod controller;
+mod dma;
+mod flat;
+mod hdma;
+pub mod printer;
+mod recording;
+pub mod serial;
+pub mod sgb;
 mod timer;
 
+pub use flat::FlatBus;
+pub use recording::{BusActivity, RecordingBus};
+
 use controller::Controller;
+use dma::Dma;
+use hdma::Hdma;
+use serial::Serial;
+use sgb::Sgb;
 use timer::Timer;
 
-use crate::gameboy::ppu::{Ppu, OAM_SIZE};
+use crate::gameboy::debugger::{WatchKind, Watchpoints};
+use crate::gameboy::palette;
+use crate::gameboy::ppu::{Model, Ppu};
 
 use super::cartridge::Cartridge;
-use std::cell::RefCell;
-use std::rc::Rc;
 
-const RAM_SIZE: usize = 0x2000;
+/// Work RAM is made of 4 KiB banks, bank 0 at c000-cfff and one of the others at
+/// d000-dfff: 2 banks on DMG, 8 on CGB
+const WRAM_BANK_SIZE: usize = 0x1000;
+const WRAM_BANK_COUNT: usize = 8;
 const HIRAM_SIZE: usize = 0x80;
 
-pub struct Bus<'a> {
-    ram: [u8; RAM_SIZE],
-    pub ppu: Ppu<'a>,
+/// Interrupt bits in IE and IF, by priority
+pub const INTERRUPT_VBLANK: u8 = 0x01;
+pub const INTERRUPT_STAT: u8 = 0x02;
+pub const INTERRUPT_TIMER: u8 = 0x04;
+pub const INTERRUPT_SERIAL: u8 = 0x08;
+pub const INTERRUPT_JOYPAD: u8 = 0x10;
+
+/// Name of the memory area of an address, see the mapping of Bus::read_8
+pub fn region_name(address: u16) -> &'static str {
+    match address {
+        0x0000..=0x3fff => "ROM0",
+        0x4000..=0x7fff => "ROMX",
+        0x8000..=0x9fff => "VRAM",
+        0xa000..=0xbfff => "SRAM",
+        0xc000..=0xdfff => "WRAM",
+        0xe000..=0xfdff => "ECHO",
+        0xfe00..=0xfe9f => "OAM",
+        0xfea0..=0xfeff => "----",
+        0xff00..=0xff7f => "I/O",
+        0xff80..=0xfffe => "HRAM",
+        0xffff => "IE",
+    }
+}
+
+/// Banks mapped in the switchable areas, see Bus::peek_banked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryBanks {
+    /// ROM bank at 4000-7fff
+    pub rom: usize,
+    /// Cartridge RAM bank at a000-bfff
+    pub ram: usize,
+    /// VRAM bank at 8000-9fff, only bank 0 on DMG
+    pub vram: usize,
+    /// Work RAM bank at d000-dfff, only bank 1 on DMG and 1-7 on CGB
+    pub wram: usize,
+}
+
+/// Memory map as seen by the CPU
+pub trait MemoryBus {
+    fn read_8(&mut self, address: u16) -> u8;
+
+    fn write_8(&mut self, address: u16, value: u8);
+
+    /// Value the CPU would read at address, for debugging tools: no side effects,
+    /// no watchpoints and no access restrictions
+    fn peek(&self, address: u16) -> u8;
+
+    /// Store value at address for debugging tools: no watchpoints nor access restrictions,
+    /// ROM and RAM are modified in place instead of being seen by the cartridge controller
+    fn poke(&mut self, address: u16, value: u8);
+
+    /// Advance everything but the CPU by one clock cycle
+    fn tick(&mut self);
+
+    fn read_16(&mut self, address: u16) -> u16 {
+        (self.read_8(address + 1) as u16) << 8 | (self.read_8(address) as u16)
+    }
+
+    fn write_16(&mut self, address: u16, value: u16) {
+        self.write_8(address + 1, (value >> 8) as u8);
+        self.write_8(address, (value & 0xff) as u8);
+    }
+
+    /// Interrupts both requested in IF and enabled in IE
+    fn get_pending_interrupts(&mut self) -> u8 {
+        self.read_8(0xffff) & self.read_8(0xff0f) & 0x1f
+    }
+
+    /// Clear the IF bit of the interrupt being serviced
+    fn acknowledge_interrupt(&mut self, interrupt: u8) {
+        let value = self.read_8(0xff0f) & !interrupt;
+        self.write_8(0xff0f, value);
+    }
+
+    /// ROM bank mapped at 4000-7fff
+    fn get_rom_bank(&self) -> u16 {
+        1
+    }
+
+    /// Called on STOP, DIV is reset without a write seen by watchpoints or recorded
+    fn reset_divider(&mut self) {
+        self.poke(0xff04, 0);
+    }
+
+    /// Called on STOP, switch between normal and double speed if requested in KEY1.
+    /// Returns false when no switch was armed, which is always the case on DMG.
+    fn switch_speed(&mut self) -> bool {
+        false
+    }
+
+    /// True while a transfer halts the CPU, which keeps ticking the bus without executing
+    fn is_cpu_halted(&self) -> bool {
+        false
+    }
+}
+
+pub struct Bus {
+    ram: [u8; WRAM_BANK_COUNT * WRAM_BANK_SIZE],
+    /// SVBK - Work RAM bank at d000-dfff, 0 selects bank 1, CGB only
+    wram_bank: u8,
+    /// KEY0 - CGB mode (0x80 or 0xc0) or DMG compatibility mode (0x04), written by the
+    /// boot ROM from the cartridge header then locked
+    key0: u8,
+    pub ppu: Ppu,
     cartridge: Cartridge,
     hiram: [u8; HIRAM_SIZE],
     interrupt_enabled: u8,
-    boot_rom_enabled: u8,
+    interrupt_flag: u8,
+    /// BANK - Bit 0 unmaps the boot ROM, it can't be mapped again
+    boot_rom_register: u8,
     pub controller: Controller,
     pub timer: Timer,
+    pub serial: Serial,
+    dma: Dma,
+    hdma: Hdma,
+    /// Super Game Boy, receiving packets through P1
+    sgb: Option<Sgb>,
+    /// KEY1 - Bit 7: the CPU and the timer run at twice the speed of the PPU, CGB only
+    double_speed: bool,
+    /// KEY1 - Bit 0: STOP switches speed
+    speed_switch_armed: bool,
+    /// Every other clock cycle is skipped by the PPU in double speed
+    odd_cycle: bool,
+    /// Checked on every read_8 and write_8
+    pub watchpoints: Watchpoints,
 }
 
-impl Bus<'_> {
-    pub fn new(sdl_context: Rc<RefCell<sdl2::Sdl>>) -> Bus<'static> {
+impl Default for Bus {
+    fn default() -> Bus {
+        Bus::new()
+    }
+}
+
+impl Bus {
+    pub fn new() -> Bus {
         Bus {
-            ppu: Ppu::new(sdl_context),
-            ram: [(); RAM_SIZE].map(|_| 0),
+            ppu: Ppu::new(),
+            ram: [(); WRAM_BANK_COUNT * WRAM_BANK_SIZE].map(|_| 0),
+            wram_bank: 0,
+            key0: 0,
             cartridge: Cartridge::new(),
             hiram: [0; HIRAM_SIZE],
             interrupt_enabled: 0,
-            boot_rom_enabled: 1,
+            interrupt_flag: 0,
+            boot_rom_register: 0,
             controller: Controller::new(),
             timer: Timer::default(),
+            serial: Serial::default(),
+            dma: Dma::default(),
+            hdma: Hdma::default(),
+            sgb: None,
+            double_speed: false,
+            speed_switch_armed: false,
+            odd_cycle: false,
+            watchpoints: Watchpoints::default(),
         }
     }
 
+    /// CGB cartridges run in CGB mode, the others on DMG
     pub fn load_cartridge(&mut self, cartridge: Cartridge) {
+        if cartridge.is_cgb() {
+            self.set_model(Model::Cgb);
+            self.key0 = cartridge.get_cgb_flag();
+        } else {
+            self.set_model(Model::Dmg);
+        }
         self.cartridge = cartridge;
     }
 
+    pub fn get_model(&self) -> Model {
+        self.ppu.get_model()
+    }
+
+    pub fn set_model(&mut self, model: Model) {
+        self.ppu.set_model(model);
+        self.key0 = match model {
+            Model::Dmg => 0,
+            Model::Cgb => 0x80,
+            Model::DmgCompatibility => 0x04,
+        };
+        if model != Model::Cgb {
+            self.wram_bank = 0;
+            self.double_speed = false;
+            self.speed_switch_armed = false;
+        }
+    }
+
+    /// Run as a Super Game Boy, for cartridges with SGB functions
+    pub fn enable_sgb(&mut self) {
+        self.sgb = Some(Sgb::new());
+        self.controller.set_sgb(true);
+    }
+
+    pub fn get_sgb(&self) -> Option<&Sgb> {
+        self.sgb.as_ref()
+    }
+
+    pub fn is_double_speed(&self) -> bool {
+        self.double_speed
+    }
+
+    /// KEY1
+    fn get_speed_register(&self) -> u8 {
+        (self.double_speed as u8) << 7 | 0x7e | self.speed_switch_armed as u8
+    }
+
+    /// Start from the state the CGB boot ROM leaves the I/O registers in, there is
+    /// no CGB boot ROM to run
+    pub fn skip_cgb_boot_rom(&mut self) {
+        self.boot_rom_register = 0x01;
+        self.ppu.reset_palettes();
+        if self.get_model() == Model::DmgCompatibility {
+            self.ppu.set_colorization(&palette::get_colorization(
+                self.cartridge.get_title_bytes(),
+                self.cartridge.has_nintendo_licensee(),
+            ));
+        }
+        self.ppu.write_registers(0xff47, 0xfc);
+        self.ppu.write_registers(0xff40, 0x91);
+    }
+
+    fn is_boot_rom_mapped(&self) -> bool {
+        self.boot_rom_register & 0x1 == 0
+    }
+
     pub fn load_boot_rom(&mut self) {
         for i in 0..BOOT_SEQUENCE_SIZE {
             self.ram[i] = BOOT_SEQUENCE[i];
         }
     }
 
-    /// Mapping:
-    /// 0000-3fff : bank0
-    /// 4000-7fff : bankn
-    /// 8000-9fff : vram
-    /// a000-bfff : cartridge ram
-    /// c000-dfff : working ram
-    /// e000-fdff : working ram mirror
-    /// fe00-fe9f : OAM
-    /// fea0-feff : prohibited, unusable
-    /// ff00-ff7f : I/O
-    /// ff80-fffe : hiram
-    /// ffff-ffff : interrupt enable register
-    pub fn read_8(&mut self, address: u16) -> u8 {
+    /// Banks currently mapped
+    pub fn get_banks(&self) -> MemoryBanks {
+        MemoryBanks {
+            rom: self.cartridge.get_active_bank(),
+            ram: self.cartridge.get_active_ram(),
+            vram: self.ppu.get_vram_bank(),
+            wram: (self.wram_bank as usize).max(1),
+        }
+    }
+
+    /// Same as peek with the given banks mapped instead of the current ones.
+    /// Banks that don't exist read 0xff.
+    pub fn peek_banked(&self, address: u16, banks: MemoryBanks) -> u8 {
         match address {
             0x0000..=0x3fff => {
-                if self.boot_rom_enabled > 0 && address < 0x100 {
+                if self.is_boot_rom_mapped() && address < 0x100 {
                     BOOT_SEQUENCE[address as usize]
                 } else {
-                    self.cartridge.read_bank0(address as usize)
+                    self.cartridge.read_rom(0, address as usize)
                 }
             }
-            0x4000..=0x7fff => self.cartridge.read_active_bank((address - 0x4000) as usize),
-            0x8000..=0x9fff => self.ppu.read_vram((address - 0x8000) as usize),
-            0xa000..=0xbfff => self.cartridge.read_active_ram((address - 0xa000) as usize),
-            0xc000..=0xdfff => self.ram[(address - 0xc000) as usize],
-            0xe000..=0xfdff => self.ram[(address - 0xe000) as usize],
-            0xfe00..=0xfe9f => self.ppu.read_oam((address - 0xfe00) as usize),
+            0x4000..=0x7fff => self
+                .cartridge
+                .read_rom(banks.rom, (address - 0x4000) as usize),
+            0x8000..=0x9fff if banks.vram < self.get_vram_bank_count() => {
+                self.ppu.peek_vram(banks.vram, (address - 0x8000) as usize)
+            }
+            0x8000..=0x9fff => 0xff,
+            0xa000..=0xbfff => self
+                .cartridge
+                .read_ram(banks.ram, (address - 0xa000) as usize),
+            0xc000..=0xfdff => self
+                .get_wram_offset(address, banks.wram)
+                .map_or(0xff, |offset| self.ram[offset]),
+            0xfe00..=0xfe9f => self.ppu.peek_oam((address - 0xfe00) as usize),
             0xff00 => self.controller.get_controller_status(), // joypad
-            0xff01..=0xff02 => 0,                              // serial transfer
+            0xff01 => self.serial.get_data(),                  // serial transfer
+            0xff02 => self.serial.get_control(),               // serial transfer
             0xff04 => self.timer.get_div(),                    // time and divider
             0xff05 => self.timer.get_tima(),                   // time and divider
             0xff06 => self.timer.get_tma(),                    // time and divider
             0xff07 => self.timer.get_tac(),                    // time and divider
+            0xff0f => self.peek_interrupt_flag(),              // request interrupt
             0xff10..=0xff26 => 0,                              // audio
             0xff30..=0xff3f => 0,                              // wave pattern
-            0xff40..=0xff4b => self.ppu.read_registers(address), // lcd
-            0xff50 => self.boot_rom_enabled,
+            0xff40..=0xff45 => self.ppu.read_registers(address), // lcd
+            0xff46 => self.dma.get_register(),                 // DMA
+            0xff47..=0xff4b => self.ppu.read_registers(address), // lcd
+            0xff4c if self.is_cgb_hardware() => self.key0,
+            0xff4d if self.get_model() == Model::Cgb => self.get_speed_register(), // speed switch
+            0xff4f if self.get_model() == Model::Cgb => self.ppu.read_registers(address), // VRAM bank
+            0xff50 => 0xfe | self.boot_rom_register,
+            0xff51..=0xff54 if self.get_model() == Model::Cgb => 0xff, // VRAM DMA
+            0xff55 if self.get_model() == Model::Cgb => self.hdma.get_status(), // VRAM DMA
+            0xff68..=0xff6c if self.is_cgb_hardware() => self.ppu.read_registers(address), // CGB palettes
+            0xff70 if self.get_model() == Model::Cgb => 0xf8 | self.wram_bank, // WRAM bank
             0xff80..=0xfffe => self.hiram[(address - 0xff80) as usize],
             0xffff => self.interrupt_enabled,
             _ => 0,
         }
     }
 
-    pub fn write_8(&mut self, address: u16, value: u8) {
+    /// Same as poke with the given banks mapped instead of the current ones.
+    /// Writes to banks that don't exist and to read only registers are ignored, DMA
+    /// isn't started and IF is set as is. Registers are stored without their write
+    /// action: no serial transfer, SGB packet bit, DIV reset or STAT interrupt. The LCD
+    /// can't be turned on or off, such LCDC writes are ignored.
+    pub fn poke_banked(&mut self, address: u16, value: u8, banks: MemoryBanks) {
+        match address {
+            0x0000..=0x3fff => self.cartridge.poke_rom(0, address as usize, value),
+            0x4000..=0x7fff => {
+                self.cartridge
+                    .poke_rom(banks.rom, (address - 0x4000) as usize, value)
+            }
+            0x8000..=0x9fff if banks.vram < self.get_vram_bank_count() => {
+                self.ppu
+                    .poke_vram(banks.vram, (address - 0x8000) as usize, value)
+            }
+            0x8000..=0x9fff => (),
+            0xa000..=0xbfff => {
+                self.cartridge
+                    .poke_ram(banks.ram, (address - 0xa000) as usize, value)
+            }
+            0xc000..=0xfdff => {
+                if let Some(offset) = self.get_wram_offset(address, banks.wram) {
+                    self.ram[offset] = value;
+                }
+            }
+            0xfe00..=0xfe9f => self.ppu.poke_oam((address - 0xfe00) as usize, value),
+            0xff00 => self.controller.poke_controller_status(value),
+            0xff02 => self.serial.poke_control(value),
+            0xff04 => self.timer.poke_div(value),
+            0xff05 => self.timer.poke_tima(value),
+            0xff07 => self.timer.poke_tac(value),
+            0xff0f => self.interrupt_flag = value & 0x1f,
+            0xff40 | 0xff41 | 0xff45 => self.ppu.poke_registers(address, value),
+            0xff44 | 0xff46 | 0xff55 => (),
+            0xff4c if self.is_cgb_hardware() => self.key0 = value,
+            0xff69 | 0xff6b if self.is_cgb_hardware() => self.ppu.poke_registers(address, value),
+            0xff80..=0xfffe => self.hiram[(address - 0xff80) as usize] = value,
+            _ => self.write_register(address, value),
+        }
+    }
+
+    /// I/O registers and IE, IF and DMA are handled by write_8
+    fn write_register(&mut self, address: u16, value: u8) {
+        match address {
+            0xff00 => {
+                // joypad
+                if let Some(packet) = self.controller.set_controller_status(value) {
+                    if let Some(sgb) = &mut self.sgb {
+                        sgb.receive_packet(&packet);
+                        self.controller.set_player_count(sgb.get_player_count());
+                    }
+                }
+            }
+            0xff01 => self.serial.set_data(value), // serial transfer
+            0xff02 => self.serial.set_control(value), // serial transfer
+            0xff04 => self.timer.set_div(value),   // time and divider
+            0xff05 => self.timer.set_tima(value),  // time and divider
+            0xff06 => self.timer.set_tma(value),   // time and divider
+            0xff07 => self.timer.set_tac(value),   // time and divider
+            0xff10..=0xff26 => (),                 // audio
+            0xff30..=0xff3f => (),                 // wave pattern
+            0xff40..=0xff45 => self.ppu.write_registers(address, value), // lcd
+            0xff47..=0xff4b => self.ppu.write_registers(address, value), // lcd
+            0xff4c if self.is_cgb_hardware() && self.is_boot_rom_mapped() => {
+                // DMG compatibility mode
+                let model = if value & 0x0c == 0x04 {
+                    Model::DmgCompatibility
+                } else {
+                    Model::Cgb
+                };
+                self.set_model(model);
+                self.key0 = value;
+            }
+            0xff4d if self.get_model() == Model::Cgb => self.speed_switch_armed = value & 0x1 != 0, // speed switch
+            0xff4f if self.get_model() == Model::Cgb => self.ppu.write_registers(address, value), // VRAM bank
+            0xff50 => self.boot_rom_register |= value & 0x1,
+            0xff51..=0xff54 if self.get_model() == Model::Cgb => {
+                self.hdma.write_register(address, value) // VRAM DMA
+            }
+            0xff68..=0xff6c if self.is_cgb_hardware() => self.ppu.write_registers(address, value), // CGB palettes
+            0xff70 if self.get_model() == Model::Cgb => self.wram_bank = value & 0x7, // WRAM bank
+            0xffff => self.interrupt_enabled = value,
+            _ => (), // Handle most read only and should not happen cases
+        }
+    }
+
+    /// ROM banks of the cartridge, bank 0 included
+    pub fn get_rom_bank_count(&self) -> usize {
+        self.cartridge.get_rom_bank_count()
+    }
+
+    pub fn get_ram_bank_count(&self) -> usize {
+        self.cartridge.get_ram_bank_count()
+    }
+
+    /// One VRAM bank on DMG, two on CGB
+    pub fn get_vram_bank_count(&self) -> usize {
+        self.ppu.get_vram_bank_count()
+    }
+
+    /// Work RAM banks, bank 0 included
+    pub fn get_wram_bank_count(&self) -> usize {
+        match self.get_model() {
+            Model::Cgb => WRAM_BANK_COUNT,
+            _ => 2,
+        }
+    }
+
+    /// CGB mode or DMG compatibility mode
+    fn is_cgb_hardware(&self) -> bool {
+        self.get_model() != Model::Dmg
+    }
+
+    /// Offset in the work RAM of an address in c000-fdff, echo included,
+    /// None if the bank mapped at d000-dfff doesn't exist
+    fn get_wram_offset(&self, address: u16, bank: usize) -> Option<usize> {
+        let offset = (address as usize - 0xc000) % (2 * WRAM_BANK_SIZE);
+        match offset {
+            0x0000..=0x0fff => Some(offset),
+            _ if (1..self.get_wram_bank_count()).contains(&bank) => {
+                Some(bank * WRAM_BANK_SIZE + offset - WRAM_BANK_SIZE)
+            }
+            _ => None,
+        }
+    }
+
+    /// IF with the interrupts requested since it was last updated, without gathering them
+    fn peek_interrupt_flag(&self) -> u8 {
+        self.interrupt_flag | self.get_requested_interrupts() | 0xe0
+    }
+
+    /// Interrupts requested by the peripherals since IF was last updated
+    fn get_requested_interrupts(&self) -> u8 {
+        let mut interrupts = 0;
+        if self.ppu.has_vblank_interrupt() {
+            interrupts |= INTERRUPT_VBLANK;
+        }
+        if self.ppu.has_stat_interrupt() {
+            interrupts |= INTERRUPT_STAT;
+        }
+        if self.timer.has_interrupt() {
+            interrupts |= INTERRUPT_TIMER;
+        }
+        if self.serial.has_interrupt() {
+            interrupts |= INTERRUPT_SERIAL;
+        }
+        if self.controller.has_interrupt() {
+            interrupts |= INTERRUPT_JOYPAD;
+        }
+        interrupts
+    }
+
+    /// Gather the interrupts requested by the peripherals in IF
+    fn update_interrupt_flag(&mut self) {
+        if self.ppu.is_vblank_interrupted() {
+            self.interrupt_flag |= INTERRUPT_VBLANK;
+        }
+        if self.ppu.is_stat_interrupted() {
+            self.interrupt_flag |= INTERRUPT_STAT;
+        }
+        if self.timer.is_interrupted() {
+            self.interrupt_flag |= INTERRUPT_TIMER;
+        }
+        if self.serial.is_interrupted() {
+            self.interrupt_flag |= INTERRUPT_SERIAL;
+        }
+        if self.controller.is_interrupted() {
+            self.interrupt_flag |= INTERRUPT_JOYPAD;
+        }
+    }
+}
+
+impl MemoryBus for Bus {
+    /// Mapping:
+    /// 0000-3fff : bank0
+    /// 4000-7fff : bankn
+    /// 8000-9fff : vram
+    /// a000-bfff : cartridge ram
+    /// c000-dfff : working ram
+    /// e000-fdff : working ram mirror
+    /// fe00-fe9f : OAM
+    /// fea0-feff : prohibited, unusable
+    /// ff00-ff7f : I/O
+    /// ff80-fffe : hiram
+    /// ffff-ffff : interrupt enable register
+    fn read_8(&mut self, address: u16) -> u8 {
+        if address == 0xff0f {
+            self.update_interrupt_flag();
+        }
+        let value = match address {
+            _ if self.dma.is_blocking(address) => 0xff,
+            0x8000..=0x9fff => self.ppu.read_vram((address - 0x8000) as usize),
+            0xfe00..=0xfe9f => self.ppu.read_oam((address - 0xfe00) as usize),
+            _ => self.peek(address),
+        };
+        self.watchpoints.check(address, WatchKind::Read, value);
+        value
+    }
+
+    fn write_8(&mut self, address: u16, value: u8) {
+        self.watchpoints.check(address, WatchKind::Write, value);
+        if self.dma.is_blocking(address) {
+            return;
+        }
         match address {
             0x0000..=0x3fff => self.cartridge.write_bank0(address as usize, value),
             0x4000..=0x7fff => self
@@ -121,60 +557,89 @@ impl Bus<'_> {
             0xa000..=0xbfff => self
                 .cartridge
                 .write_active_ram((address - 0xa000) as usize, value),
-            0xc000..=0xdfff => self.ram[(address - 0xc000) as usize] = value,
-            0xe000..=0xfdff => self.ram[(address - 0xe000) as usize] = value,
             0xfe00..=0xfe9f => self.ppu.write_oam((address - 0xfe00) as usize, value),
-            0xff00 => self.controller.set_controller_status(value), // joypad
-            0xff01..=0xff02 => (),                                  // serial transfer
-            0xff04 => self.timer.set_div(value),                    // time and divider
-            0xff05 => self.timer.set_tima(value),                   // time and divider
-            0xff06 => self.timer.set_tma(value),                    // time and divider
-            0xff07 => self.timer.set_tac(value),                    // time and divider
-            0xff0f => (),                                           // request interrupt
-            0xff10..=0xff26 => (),                                  // audio
-            0xff30..=0xff3f => (),                                  // wave pattern
-            0xff40..=0xff45 => self.ppu.write_registers(address, value), // lcd
-            0xff46 => {
-                let mut data = [0_u8; OAM_SIZE];
-                for i in 0..0x100 {
-                    data[i] = self.read_8((address << 8) + i as u16);
-                }
-                self.ppu.set_oam(data)
-            } // Start DMA transfer
-            0xff47..=0xff4b => self.ppu.write_registers(address, value), // lcd
-            0xff50 => self.boot_rom_enabled = value,
-            0xff80..=0xfffe => self.hiram[(address - 0xff80) as usize] = value,
-            0xffff => self.interrupt_enabled = value,
-            _ => (), // Handle most read only and should not happen cases
+            0xff0f => {
+                self.update_interrupt_flag();
+                self.interrupt_flag = value & 0x1f
+            } // request interrupt
+            0xff46 => self.dma.start(value), // Start DMA transfer
+            0xff55 if self.get_model() == Model::Cgb => self.hdma.write_register(address, value), // Start VRAM DMA transfer
+            0xff44 => (), // LY is read only
+            0xff00..=0xff7f | 0xffff => self.write_register(address, value),
+            _ => self.poke(address, value),
         }
     }
 
-    pub fn read_16(&mut self, address: u16) -> u16 {
-        (self.read_8(address + 1) as u16) << 8 | (self.read_8(address) as u16)
+    /// Interrupts requested since IF was last read are included without being gathered
+    fn peek(&self, address: u16) -> u8 {
+        self.peek_banked(address, self.get_banks())
     }
 
-    pub fn write_16(&mut self, address: u16, value: u16) {
-        self.write_8(address + 1, (value >> 8) as u8);
-        self.write_8(address, (value & 0xff) as u8);
+    fn poke(&mut self, address: u16, value: u8) {
+        self.poke_banked(address, value, self.get_banks())
     }
 
-    pub fn is_vblank_interrup_enabled(&mut self) -> bool {
-        self.interrupt_enabled & 1 == 1
+    /// Registers are read directly so that polling them is not seen by watchpoints
+    fn get_pending_interrupts(&mut self) -> u8 {
+        self.update_interrupt_flag();
+        self.interrupt_enabled & self.interrupt_flag & 0x1f
     }
 
-    pub fn is_stat_interrup_enabled(&mut self) -> bool {
-        (self.interrupt_enabled >> 1) & 1 == 1
+    fn acknowledge_interrupt(&mut self, interrupt: u8) {
+        self.update_interrupt_flag();
+        self.interrupt_flag &= !interrupt;
     }
 
-    pub fn is_time_interrup_enabled(&mut self) -> bool {
-        (self.interrupt_enabled >> 2) & 1 == 1
+    fn get_rom_bank(&self) -> u16 {
+        self.cartridge.get_active_bank() as u16
     }
 
-    pub fn is_serial_interrup_enabled(&mut self) -> bool {
-        (self.interrupt_enabled >> 3) & 1 == 1
+    /// Like a DIV write, TIMA can be incremented on the falling edge
+    fn reset_divider(&mut self) {
+        self.timer.set_div(0);
     }
 
-    pub fn is_joypad_interrup_enabled(&mut self) -> bool {
-        (self.interrupt_enabled >> 4) & 1 == 1
+    /// The speed changes when armed in KEY1, CGB only
+    fn switch_speed(&mut self) -> bool {
+        if !self.speed_switch_armed {
+            return false;
+        }
+        self.speed_switch_armed = false;
+        self.double_speed = !self.double_speed;
+        true
+    }
+
+    /// The CPU waits for VRAM DMA blocks
+    fn is_cpu_halted(&self) -> bool {
+        self.hdma.is_active()
+    }
+
+    /// In double speed the timer, the serial port and OAM DMA follow the CPU while the
+    /// PPU and VRAM DMA skip every other clock cycle
+    fn tick(&mut self) {
+        self.timer.tick();
+        self.serial.tick();
+        if let Some((source, offset)) = self.dma.tick() {
+            let value = self.peek(source);
+            self.ppu.poke_oam(offset, value);
+        }
+
+        self.odd_cycle = !self.odd_cycle;
+        if self.double_speed && self.odd_cycle {
+            return;
+        }
+        self.ppu.next();
+        if self.ppu.is_hblank_started() {
+            self.hdma.hblank();
+        }
+        if self.ppu.is_vblank_started() {
+            if let Some(sgb) = &mut self.sgb {
+                sgb.update_frame(self.ppu.get_frame_buffer());
+            }
+        }
+        if let Some((source, offset)) = self.hdma.tick() {
+            let value = self.peek(source);
+            self.ppu.poke_vram(self.ppu.get_vram_bank(), offset, value);
+        }
     }
 }
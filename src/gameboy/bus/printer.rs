@@ -0,0 +1,323 @@
+// https://gbdev.io/pandocs/Gameboy_Printer.html
+// https://www.mikrocontroller.net/attachment/34801/gb-printer.txt
+
+use super::serial::SerialDevice;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const MAGIC_BYTE_1: u8 = 0x88;
+const MAGIC_BYTE_2: u8 = 0x33;
+
+const COMMAND_INIT: u8 = 0x01;
+const COMMAND_PRINT: u8 = 0x02;
+const COMMAND_DATA: u8 = 0x04;
+const COMMAND_STATUS: u8 = 0x0f;
+
+/// Value sent back on the first byte following the checksum
+const KEEP_ALIVE: u8 = 0x81;
+
+/// Bit 0 - Checksum error
+/// Bit 1 - Printer busy
+/// Bit 2 - Image data full
+/// Bit 3 - Unprocessed data
+/// Bit 4 - Packet error
+/// Bit 5 - Paper jam
+/// Bit 6 - Other error
+/// Bit 7 - Battery too low
+const STATUS_CHECKSUM_ERROR: u8 = 0x01;
+const STATUS_BUSY: u8 = 0x02;
+const STATUS_IMAGE_DATA_FULL: u8 = 0x04;
+const STATUS_UNPROCESSED_DATA: u8 = 0x08;
+
+/// The printer RAM holds up to 9 data packets of 2 rows of 20 tiles
+const BUFFER_SIZE: usize = 0x2280;
+const DATA_PACKET_SIZE: usize = 0x280;
+
+/// Number of status requests answered busy after a print command
+const PRINT_DURATION: u8 = 4;
+
+pub const PRINT_WIDTH: usize = 160;
+const TILES_PER_ROW: usize = PRINT_WIDTH / 8;
+const TILE_SIZE: usize = 16;
+
+/// Printed shades, from white to black
+const SHADES: [u8; 4] = [0xff, 0xaa, 0x55, 0x00];
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum PacketState {
+    MagicByte1,
+    MagicByte2,
+    Command,
+    Compression,
+    LengthLow,
+    LengthHigh,
+    Data,
+    ChecksumLow,
+    ChecksumHigh,
+    KeepAlive,
+    Status,
+}
+
+pub struct Printer {
+    output_dir: PathBuf,
+    state: PacketState,
+    command: u8,
+    compression: bool,
+    length: u16,
+    data: Vec<u8>,
+    checksum: u16,
+    received_checksum: u16,
+    status: u8,
+    busy_remaining: u8,
+    /// Decompressed 2bpp tile data waiting to be printed
+    buffer: Vec<u8>,
+    /// Shades (0-3) of the page being printed, PRINT_WIDTH pixels per line
+    page: Vec<u8>,
+    printed_pages: u32,
+}
+
+impl Printer {
+    pub fn new(output_dir: PathBuf) -> Printer {
+        Printer {
+            output_dir,
+            state: PacketState::MagicByte1,
+            command: 0,
+            compression: false,
+            length: 0,
+            data: vec![],
+            checksum: 0,
+            received_checksum: 0,
+            status: 0,
+            busy_remaining: 0,
+            buffer: vec![],
+            page: vec![],
+            printed_pages: 0,
+        }
+    }
+
+    fn receive(&mut self, value: u8) -> u8 {
+        let mut response = 0x00;
+        self.state = match self.state {
+            PacketState::MagicByte1 if value == MAGIC_BYTE_1 => PacketState::MagicByte2,
+            PacketState::MagicByte1 => PacketState::MagicByte1,
+            PacketState::MagicByte2 if value == MAGIC_BYTE_2 => PacketState::Command,
+            PacketState::MagicByte2 => PacketState::MagicByte1,
+            PacketState::Command => {
+                self.command = value;
+                self.checksum = value as u16;
+                PacketState::Compression
+            }
+            PacketState::Compression => {
+                self.compression = value & 0x1 == 0x1;
+                self.checksum = self.checksum.wrapping_add(value as u16);
+                PacketState::LengthLow
+            }
+            PacketState::LengthLow => {
+                self.length = value as u16;
+                self.checksum = self.checksum.wrapping_add(value as u16);
+                PacketState::LengthHigh
+            }
+            PacketState::LengthHigh => {
+                self.length |= (value as u16) << 8;
+                self.checksum = self.checksum.wrapping_add(value as u16);
+                self.data.clear();
+                if self.length == 0 {
+                    PacketState::ChecksumLow
+                } else {
+                    PacketState::Data
+                }
+            }
+            PacketState::Data => {
+                self.data.push(value);
+                self.checksum = self.checksum.wrapping_add(value as u16);
+                if self.data.len() == self.length as usize {
+                    PacketState::ChecksumLow
+                } else {
+                    PacketState::Data
+                }
+            }
+            PacketState::ChecksumLow => {
+                self.received_checksum = value as u16;
+                PacketState::ChecksumHigh
+            }
+            PacketState::ChecksumHigh => {
+                self.received_checksum |= (value as u16) << 8;
+                PacketState::KeepAlive
+            }
+            PacketState::KeepAlive => {
+                response = KEEP_ALIVE;
+                self.execute();
+                PacketState::Status
+            }
+            PacketState::Status => {
+                response = self.status;
+                PacketState::MagicByte1
+            }
+        };
+        response
+    }
+
+    fn execute(&mut self) {
+        if self.checksum != self.received_checksum {
+            self.status |= STATUS_CHECKSUM_ERROR;
+            return;
+        }
+        self.status &= !STATUS_CHECKSUM_ERROR;
+
+        match self.command {
+            COMMAND_INIT => {
+                self.buffer.clear();
+                self.status = 0;
+                self.busy_remaining = 0;
+            }
+            COMMAND_PRINT => self.print(),
+            COMMAND_DATA => self.load_data(),
+            COMMAND_STATUS if self.busy_remaining > 0 => {
+                self.busy_remaining -= 1;
+                if self.busy_remaining == 0 {
+                    self.status &= !STATUS_BUSY;
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// An empty data packet only marks the end of the transfer
+    fn load_data(&mut self) {
+        let data = if self.compression {
+            Printer::decompress(&self.data)
+        } else {
+            self.data.clone()
+        };
+        let available = BUFFER_SIZE - self.buffer.len();
+        self.buffer
+            .extend_from_slice(&data[..data.len().min(available)]);
+
+        if !self.buffer.is_empty() {
+            self.status |= STATUS_UNPROCESSED_DATA;
+        }
+        if self.buffer.len() >= DATA_PACKET_SIZE {
+            self.status |= STATUS_IMAGE_DATA_FULL;
+        }
+    }
+
+    /// A control byte with bit 7 set repeats the next byte (control & 0x7f) + 2 times,
+    /// otherwise the next (control + 1) bytes are copied as is.
+    fn decompress(data: &[u8]) -> Vec<u8> {
+        let mut output = vec![];
+        let mut i = 0;
+        while i < data.len() {
+            let control = data[i];
+            i += 1;
+            if control & 0x80 == 0x80 {
+                let length = (control & 0x7f) as usize + 2;
+                if let Some(&value) = data.get(i) {
+                    output.extend(std::iter::repeat_n(value, length));
+                }
+                i += 1;
+            } else {
+                let length = control as usize + 1;
+                let end = (i + length).min(data.len());
+                output.extend_from_slice(&data[i..end]);
+                i = end;
+            }
+        }
+        output
+    }
+
+    /// Print command data:
+    /// Byte 0 - Number of sheets (0 only feeds paper)
+    /// Byte 1 - Margins, upper nibble before printing, lower nibble after printing
+    /// Byte 2 - Palette, 2 bits per color, 0 being equivalent to 0xe4
+    /// Byte 3 - Exposure, ignored as we do not burn paper
+    fn print(&mut self) {
+        if self.data.len() < 4 {
+            return;
+        }
+        let sheets = self.data[0];
+        let margin_after = self.data[1] & 0x0f;
+        let palette = match self.data[2] {
+            0 => 0xe4,
+            palette => palette,
+        };
+
+        if sheets > 0 {
+            self.render_buffer(palette);
+        }
+        self.buffer.clear();
+        self.status =
+            (self.status & !(STATUS_UNPROCESSED_DATA | STATUS_IMAGE_DATA_FULL)) | STATUS_BUSY;
+        self.busy_remaining = PRINT_DURATION;
+
+        // Games print long images in several strips without margins in between,
+        // the page is only complete once the paper is fed after printing.
+        if margin_after > 0 {
+            self.save_page();
+        }
+    }
+
+    fn render_buffer(&mut self, palette: u8) {
+        let tile_rows = self.buffer.len() / (TILES_PER_ROW * TILE_SIZE);
+        for tile_row in 0..tile_rows {
+            for line in 0..8 {
+                for x in 0..PRINT_WIDTH {
+                    let tile = tile_row * TILES_PER_ROW + x / 8;
+                    let address = tile * TILE_SIZE + line * 2;
+                    let low = self.buffer[address];
+                    let high = self.buffer[address + 1];
+                    let bit = 7 - (x % 8);
+                    let color = (((high >> bit) & 0x1) << 1) | ((low >> bit) & 0x1);
+                    self.page.push((palette >> (color * 2)) & 0x3);
+                }
+            }
+        }
+    }
+
+    fn save_page(&mut self) {
+        if self.page.is_empty() {
+            return;
+        }
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+        let path = self
+            .output_dir
+            .join(format!("print_{}_{}.png", timestamp, self.printed_pages));
+        match self.write_png(&path) {
+            Ok(()) => println!("Printed {}", path.display()),
+            Err(error) => println!("Unable to save print {}: {}", path.display(), error),
+        }
+        self.printed_pages += 1;
+        self.page.clear();
+    }
+
+    fn write_png(&self, path: &Path) -> Result<(), png::EncodingError> {
+        let file = File::create(path)?;
+        let height = (self.page.len() / PRINT_WIDTH) as u32;
+        let mut encoder = png::Encoder::new(BufWriter::new(file), PRINT_WIDTH as u32, height);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::Eight);
+        let pixels: Vec<u8> = self
+            .page
+            .iter()
+            .map(|&shade| SHADES[shade as usize])
+            .collect();
+        encoder.write_header()?.write_image_data(&pixels)
+    }
+}
+
+impl SerialDevice for Printer {
+    fn exchange(&mut self, value: u8) -> u8 {
+        self.receive(value)
+    }
+}
+
+impl Drop for Printer {
+    /// Do not lose a page still in the printer when the emulator is closed
+    fn drop(&mut self) {
+        self.save_page();
+    }
+}
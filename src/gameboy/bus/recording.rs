@@ -0,0 +1,100 @@
+use super::MemoryBus;
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum BusActivity {
+    Read {
+        address: u16,
+        value: u8,
+    },
+    Write {
+        address: u16,
+        value: u8,
+    },
+    /// M-cycle ticked without memory access
+    Idle,
+}
+
+/// Wraps any bus to keep track of the memory accesses done through it, one entry per
+/// M-cycle. Interrupt handling, peek and poke are forwarded without being recorded.
+pub struct RecordingBus<B: MemoryBus> {
+    pub inner: B,
+    pub activity: Vec<BusActivity>,
+    /// Clock cycles ticked since the beginning of the current M-cycle
+    ticks: u8,
+    /// The last activity is the Idle placeholder of the current M-cycle
+    idle_cycle: bool,
+}
+
+impl<B: MemoryBus> RecordingBus<B> {
+    pub fn new(inner: B) -> RecordingBus<B> {
+        RecordingBus {
+            inner,
+            activity: vec![],
+            ticks: 0,
+            idle_cycle: false,
+        }
+    }
+
+    /// The access takes the place of the Idle entry of the M-cycle it happens in
+    fn record(&mut self, access: BusActivity) {
+        if self.idle_cycle {
+            self.activity.pop();
+            self.idle_cycle = false;
+        }
+        self.activity.push(access);
+    }
+}
+
+impl<B: MemoryBus> MemoryBus for RecordingBus<B> {
+    fn read_8(&mut self, address: u16) -> u8 {
+        let value = self.inner.read_8(address);
+        self.record(BusActivity::Read { address, value });
+        value
+    }
+
+    fn write_8(&mut self, address: u16, value: u8) {
+        self.inner.write_8(address, value);
+        self.record(BusActivity::Write { address, value });
+    }
+
+    fn peek(&self, address: u16) -> u8 {
+        self.inner.peek(address)
+    }
+
+    fn poke(&mut self, address: u16, value: u8) {
+        self.inner.poke(address, value);
+    }
+
+    fn tick(&mut self) {
+        self.inner.tick();
+        if self.ticks == 0 {
+            self.activity.push(BusActivity::Idle);
+            self.idle_cycle = true;
+        }
+        self.ticks = (self.ticks + 1) % 4;
+    }
+
+    fn get_pending_interrupts(&mut self) -> u8 {
+        self.inner.get_pending_interrupts()
+    }
+
+    fn acknowledge_interrupt(&mut self, interrupt: u8) {
+        self.inner.acknowledge_interrupt(interrupt);
+    }
+
+    fn get_rom_bank(&self) -> u16 {
+        self.inner.get_rom_bank()
+    }
+
+    fn reset_divider(&mut self) {
+        self.inner.reset_divider();
+    }
+
+    fn switch_speed(&mut self) -> bool {
+        self.inner.switch_speed()
+    }
+
+    fn is_cpu_halted(&self) -> bool {
+        self.inner.is_cpu_halted()
+    }
+}
@@ -0,0 +1,84 @@
+/// Number of clock ticks needed to shift a full byte at 8192 Hz (internal clock)
+const TRANSFER_CYCLES: u16 = 4096;
+
+/// Anything that can be plugged in the link port.
+///
+/// The Game Boy always provides the clock in our implementation, so a device
+/// only has to answer the byte it receives with the byte it shifts back.
+pub trait SerialDevice {
+    fn exchange(&mut self, value: u8) -> u8;
+}
+
+#[derive(Default)]
+pub struct Serial {
+    /// SB - Serial transfer data
+    data: u8,
+    /// SC - Serial transfer control
+    /// Bit 7 - Transfer Start Flag (0=No transfer, 1=Start)
+    /// Bit 0 - Shift Clock (0=External Clock, 1=Internal Clock 8192Hz)
+    control: u8,
+    remaining_cycles: u16,
+    device: Option<Box<dyn SerialDevice>>,
+    interrupted: bool,
+}
+
+impl Serial {
+    pub fn connect(&mut self, device: Box<dyn SerialDevice>) {
+        self.device = Some(device);
+    }
+
+    pub fn tick(&mut self) {
+        if self.remaining_cycles == 0 {
+            return;
+        }
+        self.remaining_cycles -= 1;
+        if self.remaining_cycles == 0 {
+            // Without a device on the other end, the line is pulled up
+            self.data = match &mut self.device {
+                Some(device) => device.exchange(self.data),
+                None => 0xff,
+            };
+            self.control &= 0x7f;
+            self.interrupted = true;
+        }
+    }
+
+    pub fn get_data(&self) -> u8 {
+        self.data
+    }
+
+    pub fn set_data(&mut self, value: u8) {
+        self.data = value;
+    }
+
+    pub fn get_control(&self) -> u8 {
+        self.control | 0x7e
+    }
+
+    pub fn set_control(&mut self, value: u8) {
+        self.control = value & 0x81;
+        // With an external clock, the transfer waits for the other side to
+        // drive the clock, which never happens as we are always the master.
+        if self.control == 0x81 {
+            self.remaining_cycles = TRANSFER_CYCLES;
+        } else {
+            self.remaining_cycles = 0;
+        }
+    }
+
+    /// Set the control bits without starting or cancelling a transfer
+    pub fn poke_control(&mut self, value: u8) {
+        self.control = value & 0x81;
+    }
+
+    /// Same as is_interrupted without clearing the request
+    pub fn has_interrupt(&self) -> bool {
+        self.interrupted
+    }
+
+    pub fn is_interrupted(&mut self) -> bool {
+        let value = self.interrupted;
+        self.interrupted = false;
+        value
+    }
+}
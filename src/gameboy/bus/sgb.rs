@@ -0,0 +1,445 @@
+//! Super Game Boy: command packets sent by the game through P1, the four palettes
+//! colorizing the screen and the 256x224 border around it.
+// https://gbdev.io/pandocs/SGB_Functions.html
+
+use crate::gameboy::ppu::{SCREEN_HEIGHT, SCREEN_WIDTH};
+
+/// Packets are 16 bytes, the first byte of a command gives its code and its number of
+/// packets (1-7)
+pub const PACKET_SIZE: usize = 16;
+
+pub const BORDER_WIDTH: usize = 256;
+pub const BORDER_HEIGHT: usize = 224;
+/// Top left corner of the game screen in the border
+pub const SCREEN_LEFT: usize = 48;
+pub const SCREEN_TOP: usize = 40;
+
+/// The attribute map gives a palette to each 8x8 cell of the screen
+const CELLS_X: usize = SCREEN_WIDTH / 8;
+const CELLS_Y: usize = SCREEN_HEIGHT / 8;
+
+/// Data of CHR_TRN, PCT_TRN and PAL_TRN: the first 256 tiles shown on screen
+const TRANSFER_SIZE: usize = 0x1000;
+const TRANSFER_TILE_SIZE: usize = 16;
+
+const SYSTEM_PALETTE_COUNT: usize = 512;
+/// SNES tiles of 8x8 pixels, 4 bits per pixel
+const BORDER_TILE_SIZE: usize = 32;
+const BORDER_TILE_COUNT: usize = 256;
+/// 32x28 tiles of the map sent by PCT_TRN
+const BORDER_MAP_WIDTH: usize = BORDER_WIDTH / 8;
+const BORDER_MAP_HEIGHT: usize = BORDER_HEIGHT / 8;
+/// SNES palettes 4-7 of 16 colors, after the map in the PCT_TRN data
+const BORDER_PALETTES_OFFSET: usize = 0x800;
+const BORDER_PALETTE_COUNT: usize = 4;
+
+const BLACK: u16 = 0x0000;
+/// Palette of the SGB until the game sets its own
+const DEFAULT_PALETTE: [u16; 4] = [0x67bf, 0x265b, 0x10b5, 0x2866];
+
+const PAL01: u8 = 0x00;
+const PAL23: u8 = 0x01;
+const PAL03: u8 = 0x02;
+const PAL12: u8 = 0x03;
+const ATTR_BLK: u8 = 0x04;
+const ATTR_LIN: u8 = 0x05;
+const ATTR_DIV: u8 = 0x06;
+const ATTR_CHR: u8 = 0x07;
+const PAL_SET: u8 = 0x0a;
+const PAL_TRN: u8 = 0x0b;
+const MLT_REQ: u8 = 0x11;
+const CHR_TRN: u8 = 0x13;
+const PCT_TRN: u8 = 0x14;
+const MASK_EN: u8 = 0x17;
+
+/// MASK_EN - What is shown instead of the game screen, while the game prepares it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mask {
+    Cancel,
+    /// The last frame stays on screen
+    Freeze,
+    Black,
+    /// Color 0 of the palettes
+    Color0,
+}
+
+/// VRAM transfer done with the next frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Transfer {
+    /// CHR_TRN - Half of the border tiles, 0x00-0x7f or 0x80-0xff
+    BorderTiles(usize),
+    /// PCT_TRN - Border map and palettes
+    Border,
+    /// PAL_TRN - System palettes, picked with PAL_SET
+    Palettes,
+}
+
+pub struct Sgb {
+    /// Packets received of a command of several packets
+    command: Vec<u8>,
+    /// Color 0 is the same in all palettes
+    palettes: [[u16; 4]; 4],
+    system_palettes: Vec<[u16; 4]>,
+    /// Palette of each 8x8 cell of the screen
+    attributes: [u8; CELLS_X * CELLS_Y],
+    mask: Mask,
+    transfer: Option<Transfer>,
+    border_tiles: Vec<u8>,
+    /// Tile number, palette and flips of the border tiles
+    border_map: Vec<u16>,
+    border_palettes: [[u16; 16]; BORDER_PALETTE_COUNT],
+    /// MLT_REQ - 1, 2 or 4 joypads
+    player_count: u8,
+    /// 15-bit colors of the game screen
+    colors: Vec<u16>,
+}
+
+impl Default for Sgb {
+    fn default() -> Sgb {
+        Sgb::new()
+    }
+}
+
+impl Sgb {
+    pub fn new() -> Sgb {
+        Sgb {
+            command: vec![],
+            palettes: [DEFAULT_PALETTE; 4],
+            system_palettes: vec![[BLACK; 4]; SYSTEM_PALETTE_COUNT],
+            attributes: [0; CELLS_X * CELLS_Y],
+            mask: Mask::Cancel,
+            transfer: None,
+            border_tiles: vec![0; BORDER_TILE_COUNT * BORDER_TILE_SIZE],
+            border_map: vec![0; BORDER_MAP_WIDTH * BORDER_MAP_HEIGHT],
+            border_palettes: [[BLACK; 16]; BORDER_PALETTE_COUNT],
+            player_count: 1,
+            colors: vec![DEFAULT_PALETTE[0]; SCREEN_WIDTH * SCREEN_HEIGHT],
+        }
+    }
+
+    pub fn get_player_count(&self) -> u8 {
+        self.player_count
+    }
+
+    pub fn get_mask(&self) -> Mask {
+        self.mask
+    }
+
+    pub fn get_palettes(&self) -> &[[u16; 4]; 4] {
+        &self.palettes
+    }
+
+    /// 15-bit colors of the game screen, SCREEN_WIDTH pixels per line
+    pub fn get_colors(&self) -> &[u16] {
+        &self.colors
+    }
+
+    /// 15-bit colors of the whole picture, the game screen at SCREEN_LEFT, SCREEN_TOP in the
+    /// border, BORDER_WIDTH pixels per line
+    pub fn get_border_frame(&self) -> Vec<u16> {
+        let mut frame = vec![self.palettes[0][0]; BORDER_WIDTH * BORDER_HEIGHT];
+        for y in 0..SCREEN_HEIGHT {
+            let offset = (SCREEN_TOP + y) * BORDER_WIDTH + SCREEN_LEFT;
+            frame[offset..offset + SCREEN_WIDTH]
+                .copy_from_slice(&self.colors[y * SCREEN_WIDTH..(y + 1) * SCREEN_WIDTH]);
+        }
+        // Color 0 of the border tiles is transparent
+        for y in 0..BORDER_HEIGHT {
+            for x in 0..BORDER_WIDTH {
+                if let Some(color) = self.get_border_pixel(x, y) {
+                    frame[y * BORDER_WIDTH + x] = color;
+                }
+            }
+        }
+        frame
+    }
+
+    fn get_border_pixel(&self, x: usize, y: usize) -> Option<u16> {
+        let entry = self.border_map[y / 8 * BORDER_MAP_WIDTH + x / 8];
+        let tile = (entry & 0xff) as usize;
+        let palette = (entry >> 10 & 0x3) as usize;
+        let column = if entry & 0x4000 != 0 {
+            x % 8
+        } else {
+            7 - x % 8
+        };
+        let row = if entry & 0x8000 != 0 {
+            7 - y % 8
+        } else {
+            y % 8
+        };
+
+        // Bit planes 0 and 1 interleaved by row, then planes 2 and 3
+        let data = &self.border_tiles[tile * BORDER_TILE_SIZE..(tile + 1) * BORDER_TILE_SIZE];
+        let color = [
+            data[row * 2],
+            data[row * 2 + 1],
+            data[16 + row * 2],
+            data[17 + row * 2],
+        ]
+        .iter()
+        .enumerate()
+        .fold(0, |color, (plane, byte)| {
+            color | (byte >> column & 0x1) << plane
+        });
+        match color {
+            0 => None,
+            _ => Some(self.border_palettes[palette][color as usize]),
+        }
+    }
+
+    /// Keep a packet received through P1, the command runs once all its packets arrived
+    pub fn receive_packet(&mut self, packet: &[u8; PACKET_SIZE]) {
+        if self.command.is_empty() && packet[0] & 0x7 == 0 {
+            return;
+        }
+        self.command.extend_from_slice(packet);
+        let packet_count = (self.command[0] & 0x7) as usize;
+        if self.command.len() == packet_count * PACKET_SIZE {
+            let command = std::mem::take(&mut self.command);
+            self.execute(&command);
+        }
+    }
+
+    fn execute(&mut self, data: &[u8]) {
+        match data[0] >> 3 {
+            PAL01 => self.set_palettes(0, 1, data),
+            PAL23 => self.set_palettes(2, 3, data),
+            PAL03 => self.set_palettes(0, 3, data),
+            PAL12 => self.set_palettes(1, 2, data),
+            ATTR_BLK => self.set_block_attributes(data),
+            ATTR_LIN => self.set_line_attributes(data),
+            ATTR_DIV => self.set_division_attributes(data),
+            ATTR_CHR => self.set_cell_attributes(data),
+            PAL_SET => self.set_system_palettes(data),
+            PAL_TRN => self.transfer = Some(Transfer::Palettes),
+            MLT_REQ => {
+                self.player_count = match data[1] & 0x3 {
+                    1 => 2,
+                    3 => 4,
+                    _ => 1,
+                }
+            }
+            CHR_TRN => self.transfer = Some(Transfer::BorderTiles((data[1] & 0x1) as usize)),
+            PCT_TRN => self.transfer = Some(Transfer::Border),
+            MASK_EN => {
+                self.mask = match data[1] & 0x3 {
+                    0 => Mask::Cancel,
+                    1 => Mask::Freeze,
+                    2 => Mask::Black,
+                    _ => Mask::Color0,
+                }
+            }
+            // Sound, attribute files and SNES code are not supported
+            _ => (),
+        }
+    }
+
+    /// PALxy - Color 0 shared by all palettes, then colors 1-3 of both palettes
+    fn set_palettes(&mut self, first: usize, second: usize, data: &[u8]) {
+        let color = |index: usize| u16::from_le_bytes([data[1 + index * 2], data[2 + index * 2]]);
+        self.set_color_0(color(0));
+        for i in 1..4 {
+            self.palettes[first][i] = color(i);
+            self.palettes[second][i] = color(i + 3);
+        }
+    }
+
+    fn set_color_0(&mut self, color: u16) {
+        for palette in self.palettes.iter_mut() {
+            palette[0] = color;
+        }
+    }
+
+    fn set_attribute(&mut self, x: usize, y: usize, palette: u8) {
+        if x < CELLS_X && y < CELLS_Y {
+            self.attributes[y * CELLS_X + x] = palette & 0x3;
+        }
+    }
+
+    /// ATTR_BLK - Rectangles of cells, with palettes for the cells inside, on the edge and
+    /// outside of each
+    fn set_block_attributes(&mut self, data: &[u8]) {
+        let count = data[1] as usize;
+        for block in data[2..].chunks_exact(6).take(count) {
+            let mut control = block[0] & 0x7;
+            let (inside, mut edge, outside) =
+                (block[1] & 0x3, block[1] >> 2 & 0x3, block[1] >> 4 & 0x3);
+            // The edge goes with the inside or the outside when only one of them is set
+            match control {
+                0x1 => {
+                    control |= 0x2;
+                    edge = inside;
+                }
+                0x4 => {
+                    control |= 0x2;
+                    edge = outside;
+                }
+                _ => (),
+            }
+            let (left, top, right, bottom) = (
+                block[2] as usize,
+                block[3] as usize,
+                block[4] as usize,
+                block[5] as usize,
+            );
+            for y in 0..CELLS_Y {
+                for x in 0..CELLS_X {
+                    let within = (left..=right).contains(&x) && (top..=bottom).contains(&y);
+                    let on_edge = within && (x == left || x == right || y == top || y == bottom);
+                    let (selected, palette) = if on_edge {
+                        (control & 0x2, edge)
+                    } else if within {
+                        (control & 0x1, inside)
+                    } else {
+                        (control & 0x4, outside)
+                    };
+                    if selected != 0 {
+                        self.set_attribute(x, y, palette);
+                    }
+                }
+            }
+        }
+    }
+
+    /// ATTR_LIN - Whole rows or columns of cells
+    fn set_line_attributes(&mut self, data: &[u8]) {
+        let count = data[1] as usize;
+        for line in data[2..].iter().take(count) {
+            let (number, palette) = ((line & 0x1f) as usize, line >> 5 & 0x3);
+            if line & 0x80 != 0 {
+                for x in 0..CELLS_X {
+                    self.set_attribute(x, number, palette);
+                }
+            } else {
+                for y in 0..CELLS_Y {
+                    self.set_attribute(number, y, palette);
+                }
+            }
+        }
+    }
+
+    /// ATTR_DIV - The screen split in two by a row or column of cells
+    fn set_division_attributes(&mut self, data: &[u8]) {
+        let (after, before, on_line) = (data[1] & 0x3, data[1] >> 2 & 0x3, data[1] >> 4 & 0x3);
+        let horizontal = data[1] & 0x40 != 0;
+        let line = data[2] as usize;
+        for y in 0..CELLS_Y {
+            for x in 0..CELLS_X {
+                let position = if horizontal { y } else { x };
+                let palette = match position.cmp(&line) {
+                    std::cmp::Ordering::Less => before,
+                    std::cmp::Ordering::Equal => on_line,
+                    std::cmp::Ordering::Greater => after,
+                };
+                self.set_attribute(x, y, palette);
+            }
+        }
+    }
+
+    /// ATTR_CHR - Cells one by one from a starting cell, 4 per byte from bit 7, left to
+    /// right or top to bottom
+    fn set_cell_attributes(&mut self, data: &[u8]) {
+        let (mut x, mut y) = (data[1] as usize, data[2] as usize);
+        let count = (data[3] as usize) | (data[4] as usize) << 8;
+        let vertical = data[5] & 0x1 != 0;
+        for index in 0..count.min(CELLS_X * CELLS_Y) {
+            let Some(byte) = data.get(6 + index / 4) else {
+                break;
+            };
+            self.set_attribute(x, y, byte >> (6 - index % 4 * 2));
+            if vertical {
+                y += 1;
+                if y == CELLS_Y {
+                    y = 0;
+                    x = (x + 1) % CELLS_X;
+                }
+            } else {
+                x += 1;
+                if x == CELLS_X {
+                    x = 0;
+                    y = (y + 1) % CELLS_Y;
+                }
+            }
+        }
+    }
+
+    /// PAL_SET - The 4 palettes from the system palettes sent with PAL_TRN, the color 0
+    /// of the first one is shared
+    fn set_system_palettes(&mut self, data: &[u8]) {
+        for palette in 0..4 {
+            let index = u16::from_le_bytes([data[1 + palette * 2], data[2 + palette * 2]]);
+            self.palettes[palette] = self.system_palettes[index as usize % SYSTEM_PALETTE_COUNT];
+        }
+        self.set_color_0(self.palettes[0][0]);
+        if data[9] & 0x40 != 0 {
+            self.mask = Mask::Cancel;
+        }
+    }
+
+    /// A frame is complete, run the pending VRAM transfer then colorize it
+    pub fn update_frame(&mut self, frame_buffer: &[u8]) {
+        if let Some(transfer) = self.transfer.take() {
+            self.receive_transfer(transfer, &get_transfer_data(frame_buffer));
+        }
+        match self.mask {
+            Mask::Cancel => {
+                for (offset, shade) in frame_buffer.iter().enumerate() {
+                    let (x, y) = (offset % SCREEN_WIDTH, offset / SCREEN_WIDTH);
+                    let palette = self.attributes[y / 8 * CELLS_X + x / 8] as usize;
+                    self.colors[offset] = self.palettes[palette][*shade as usize];
+                }
+            }
+            Mask::Freeze => (),
+            Mask::Black => self.colors.fill(BLACK),
+            Mask::Color0 => self.colors.fill(self.palettes[0][0]),
+        }
+    }
+
+    fn receive_transfer(&mut self, transfer: Transfer, data: &[u8]) {
+        let colors = data
+            .chunks_exact(2)
+            .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]));
+        match transfer {
+            Transfer::BorderTiles(half) => {
+                let start = half * TRANSFER_SIZE;
+                self.border_tiles[start..start + TRANSFER_SIZE].copy_from_slice(data);
+            }
+            Transfer::Border => {
+                for (entry, value) in self.border_map.iter_mut().zip(colors.clone()) {
+                    *entry = value;
+                }
+                let palettes = colors.skip(BORDER_PALETTES_OFFSET / 2);
+                for (index, color) in palettes.take(BORDER_PALETTE_COUNT * 16).enumerate() {
+                    self.border_palettes[index / 16][index % 16] = color;
+                }
+            }
+            Transfer::Palettes => {
+                for (index, color) in colors.enumerate() {
+                    self.system_palettes[index / 4][index % 4] = color;
+                }
+            }
+        }
+    }
+}
+
+/// The SGB reads transfers from the LCD output: 256 tiles laid out 20 per row on screen,
+/// encoded back with 2 bits per pixel
+fn get_transfer_data(frame_buffer: &[u8]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(TRANSFER_SIZE);
+    for tile in 0..TRANSFER_SIZE / TRANSFER_TILE_SIZE {
+        let (left, top) = (tile % CELLS_X * 8, tile / CELLS_X * 8);
+        for row in 0..8 {
+            let offset = (top + row) * SCREEN_WIDTH + left;
+            let pixels = &frame_buffer[offset..offset + 8];
+            let plane = |bit: u8| {
+                pixels
+                    .iter()
+                    .fold(0, |byte, shade| byte << 1 | (shade >> bit & 0x1))
+            };
+            data.push(plane(0));
+            data.push(plane(1));
+        }
+    }
+    data
+}
@@ -1,3 +1,13 @@
+// https://gbdev.io/pandocs/Timer_Obscure_Behaviour.html
+
+/// Clock cycles between two TIMA increments for each TAC clock mode
+const CLOCK_SELECT: InputClockSelect = [1024, 16, 64, 256];
+
+/// After an overflow TIMA reads 0 for one M-cycle before being reloaded with TMA
+const RELOAD_DELAY: u8 = 4;
+
+type InputClockSelect = [u16; 4];
+
 #[derive(Default)]
 pub struct Timer {
     divider_register: u16,
@@ -5,11 +15,45 @@ pub struct Timer {
     time_modulo: u8,
     time_enable: bool,
     clock_mode: u8,
+    /// Clock cycles left before TIMA is reloaded, 0 when no overflow is pending
+    reload_remaining_cycles: u8,
+    interrupted: bool,
 }
 
 impl Timer {
     pub fn tick(&mut self) {
+        if self.reload_remaining_cycles > 0 {
+            self.reload_remaining_cycles -= 1;
+            if self.reload_remaining_cycles == 0 {
+                self.time_counter = self.time_modulo;
+                self.interrupted = true;
+            }
+        }
+
+        let input = self.get_timer_input();
         self.divider_register = self.divider_register.wrapping_add(1);
+        self.check_falling_edge(input);
+    }
+
+    /// TIMA is incremented when the divider bit selected by TAC goes from 1 to 0
+    fn get_timer_input(&self) -> bool {
+        let bit = CLOCK_SELECT[self.clock_mode as usize] / 2;
+        self.time_enable && self.divider_register & bit != 0
+    }
+
+    /// Writes to DIV and TAC also move the selected bit, and can increment TIMA
+    fn check_falling_edge(&mut self, previous_input: bool) {
+        if previous_input && !self.get_timer_input() {
+            self.increment_tima();
+        }
+    }
+
+    fn increment_tima(&mut self) {
+        let (value, overflow) = self.time_counter.overflowing_add(1);
+        self.time_counter = value;
+        if overflow {
+            self.reload_remaining_cycles = RELOAD_DELAY;
+        }
     }
 
     pub fn get_div(&self) -> u8 {
@@ -17,14 +61,30 @@ impl Timer {
     }
 
     pub fn set_div(&mut self, _value: u8) {
+        let input = self.get_timer_input();
         self.divider_register = 0;
+        self.check_falling_edge(input);
+    }
+
+    /// Set the upper byte of the divider as read in DIV, TIMA is left alone
+    pub fn poke_div(&mut self, value: u8) {
+        self.divider_register = (value as u16) << 8;
     }
 
     pub fn get_tima(&self) -> u8 {
         self.time_counter
     }
 
-    pub fn set_tima(&mut self, _value: u8) {}
+    /// Writing TIMA while the reload is pending cancels it
+    pub fn set_tima(&mut self, value: u8) {
+        self.time_counter = value;
+        self.reload_remaining_cycles = 0;
+    }
+
+    /// Set TIMA without cancelling a pending reload
+    pub fn poke_tima(&mut self, value: u8) {
+        self.time_counter = value;
+    }
 
     pub fn get_tma(&self) -> u8 {
         self.time_modulo
@@ -35,19 +95,30 @@ impl Timer {
     }
 
     pub fn get_tac(&self) -> u8 {
-        self.time_modulo
+        0xf8 | (self.time_enable as u8) << 2 | self.clock_mode
     }
 
     pub fn set_tac(&mut self, value: u8) {
+        let input = self.get_timer_input();
         self.time_enable = value >> 2 & 0x1 == 0x1;
         self.clock_mode = value & 0x3;
+        self.check_falling_edge(input);
     }
 
-    pub fn is_interrupted(&mut self) -> bool {
-        false
+    /// Set TAC without incrementing TIMA on a falling edge
+    pub fn poke_tac(&mut self, value: u8) {
+        self.time_enable = value >> 2 & 0x1 == 0x1;
+        self.clock_mode = value & 0x3;
     }
-}
 
-const CLOCK_SELECT: InputClockSelect = [1024, 16, 64, 256];
+    /// Same as is_interrupted without clearing the request
+    pub fn has_interrupt(&self) -> bool {
+        self.interrupted
+    }
 
-type InputClockSelect = [u16; 4];
+    pub fn is_interrupted(&mut self) -> bool {
+        let value = self.interrupted;
+        self.interrupted = false;
+        value
+    }
+}
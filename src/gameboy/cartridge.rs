@@ -8,6 +8,13 @@ const RAM_BANK_SIZE: usize = 0x4000;
 
 const TITLE_START_ADDRESS: usize = 0x0134;
 const TITLE_END_ADDRESS: usize = 0x0143;
+/// Last byte of the title on older cartridges, 0x80 or 0xc0 for CGB cartridges
+const CGB_FLAG_ADDRESS: usize = 0x0143;
+/// Two ASCII characters, used when the old licensee code is 0x33
+const NEW_LICENSEE_CODE_ADDRESS: usize = 0x0144;
+/// 0x03 for cartridges with SGB functions
+const SGB_FLAG_ADDRESS: usize = 0x0146;
+const OLD_LICENSEE_CODE_ADDRESS: usize = 0x014b;
 
 const TYPE_ADDRESS: usize = 0x0147;
 const ROM_SIZE_ADDRESS: usize = 0x0148;
@@ -92,7 +99,12 @@ impl Cartridge {
 
         let mut c = Cartridge::new();
 
-        let s = buffer[TITLE_START_ADDRESS..=TITLE_END_ADDRESS].to_vec();
+        let title_end = if buffer[CGB_FLAG_ADDRESS] & 0x80 != 0 {
+            CGB_FLAG_ADDRESS - 1
+        } else {
+            TITLE_END_ADDRESS
+        };
+        let s = buffer[TITLE_START_ADDRESS..=title_end].to_vec();
         c.title = String::from_utf8_lossy(&s).to_string();
 
         for i in 0..ROM_BANK_SIZE {
@@ -276,22 +288,90 @@ impl Cartridge {
         c
     }
 
-    pub fn read_bank0(&self, address: usize) -> u8 {
-        self.bank_0[address]
-    }
     pub fn write_bank0(&mut self, address: usize, value: u8) {
         // Switch banks
     }
 
-    pub fn read_active_bank(&self, address: usize) -> u8 {
-        self.bank_n[self.active_bank - 1][address]
+    /// CGB flag of the header, byte 0x143
+    pub fn get_cgb_flag(&self) -> u8 {
+        self.bank_0[CGB_FLAG_ADDRESS]
+    }
+
+    /// CGB enhanced (0x80) or CGB only (0xc0) cartridge
+    pub fn is_cgb(&self) -> bool {
+        self.get_cgb_flag() & 0x80 != 0
+    }
+
+    /// The 16 bytes of the title area, CGB flag included
+    pub fn get_title_bytes(&self) -> &[u8] {
+        &self.bank_0[TITLE_START_ADDRESS..=TITLE_END_ADDRESS]
+    }
+
+    /// Licensee code 01, old or new
+    pub fn has_nintendo_licensee(&self) -> bool {
+        match self.bank_0[OLD_LICENSEE_CODE_ADDRESS] {
+            0x01 => true,
+            0x33 => &self.bank_0[NEW_LICENSEE_CODE_ADDRESS..NEW_LICENSEE_CODE_ADDRESS + 2] == b"01",
+            _ => false,
+        }
+    }
+
+    /// The SGB ignores the packets of cartridges without the SGB flag or the new licensee code
+    pub fn supports_sgb(&self) -> bool {
+        self.bank_0[SGB_FLAG_ADDRESS] == 0x03 && self.bank_0[OLD_LICENSEE_CODE_ADDRESS] == 0x33
+    }
+
+    /// Selecting bank 0 maps bank 1
+    pub fn get_active_bank(&self) -> usize {
+        self.active_bank.max(1)
     }
+
     pub fn write_active_bank(&mut self, address: usize, value: u8) {
         // ?
     }
 
-    pub fn read_active_ram(&self, address: usize) -> u8 {
-        self.ram_n[self.active_ram][address]
+    /// Byte of any ROM bank, 0xff past the end of the ROM
+    pub fn read_rom(&self, bank: usize, address: usize) -> u8 {
+        match bank {
+            0 => self.bank_0[address],
+            _ => self.bank_n.get(bank - 1).map_or(0xff, |bank| bank[address]),
+        }
+    }
+
+    /// Byte of any RAM bank, 0xff if there is no such bank
+    pub fn read_ram(&self, bank: usize, address: usize) -> u8 {
+        self.ram_n.get(bank).map_or(0xff, |bank| bank[address])
+    }
+
+    /// Modify a byte of any ROM bank in place, ignored past the end of the ROM
+    pub fn poke_rom(&mut self, bank: usize, address: usize, value: u8) {
+        match bank {
+            0 => self.bank_0[address] = value,
+            _ => {
+                if let Some(bank) = self.bank_n.get_mut(bank - 1) {
+                    bank[address] = value;
+                }
+            }
+        }
+    }
+
+    /// Modify a byte of any RAM bank, ignored if there is no such bank
+    pub fn poke_ram(&mut self, bank: usize, address: usize, value: u8) {
+        if let Some(bank) = self.ram_n.get_mut(bank) {
+            bank[address] = value;
+        }
+    }
+
+    pub fn get_active_ram(&self) -> usize {
+        self.active_ram
+    }
+
+    pub fn get_rom_bank_count(&self) -> usize {
+        self.bank_n.len() + 1
+    }
+
+    pub fn get_ram_bank_count(&self) -> usize {
+        self.ram_n.len()
     }
 
     pub fn write_active_ram(&mut self, address: usize, value: u8) {
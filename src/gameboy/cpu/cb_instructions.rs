@@ -1,12 +1,9 @@
-use super::instructions::{Instruction, InstructionCode};
+use super::instructions::Instruction;
 use super::registers::RegisterNames;
 
 pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     Instruction {
         opcode: 0x00,
-        name: InstructionCode::RLC,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.rlc(RegisterNames::B);
             0
@@ -14,9 +11,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x01,
-        name: InstructionCode::RLC,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.rlc(RegisterNames::C);
             0
@@ -24,9 +18,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x02,
-        name: InstructionCode::RLC,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.rlc(RegisterNames::D);
             0
@@ -34,9 +25,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x03,
-        name: InstructionCode::RLC,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.rlc(RegisterNames::E);
             0
@@ -44,9 +32,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x04,
-        name: InstructionCode::RLC,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.rlc(RegisterNames::H);
             0
@@ -54,9 +39,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x05,
-        name: InstructionCode::RLC,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.rlc(RegisterNames::L);
             0
@@ -64,9 +46,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x06,
-        name: InstructionCode::RLC,
-        length: 2,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.rlc(RegisterNames::IndirectHL);
             0
@@ -74,9 +53,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x07,
-        name: InstructionCode::RLC,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.rlc(RegisterNames::A);
             0
@@ -84,9 +60,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x08,
-        name: InstructionCode::RRC,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.rrc(RegisterNames::B);
             0
@@ -94,9 +67,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x09,
-        name: InstructionCode::RRC,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.rrc(RegisterNames::C);
             0
@@ -104,9 +74,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x0A,
-        name: InstructionCode::RRC,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.rrc(RegisterNames::D);
             0
@@ -114,9 +81,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x0B,
-        name: InstructionCode::RRC,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.rrc(RegisterNames::E);
             0
@@ -124,9 +88,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x0C,
-        name: InstructionCode::RRC,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.rrc(RegisterNames::H);
             0
@@ -134,9 +95,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x0D,
-        name: InstructionCode::RRC,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.rrc(RegisterNames::L);
             0
@@ -144,9 +102,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x0E,
-        name: InstructionCode::RRC,
-        length: 2,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.rrc(RegisterNames::IndirectHL);
             0
@@ -154,9 +109,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x0F,
-        name: InstructionCode::RRC,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.rrc(RegisterNames::A);
             0
@@ -164,9 +116,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x10,
-        name: InstructionCode::RL,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.rl(RegisterNames::B);
             0
@@ -174,9 +123,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x11,
-        name: InstructionCode::RL,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.rl(RegisterNames::C);
             0
@@ -184,9 +130,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x12,
-        name: InstructionCode::RL,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.rl(RegisterNames::D);
             0
@@ -194,9 +137,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x13,
-        name: InstructionCode::RL,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.rl(RegisterNames::E);
             0
@@ -204,9 +144,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x14,
-        name: InstructionCode::RL,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.rl(RegisterNames::H);
             0
@@ -214,9 +151,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x15,
-        name: InstructionCode::RL,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.rl(RegisterNames::L);
             0
@@ -224,9 +158,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x16,
-        name: InstructionCode::RL,
-        length: 2,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.rl(RegisterNames::IndirectHL);
             0
@@ -234,9 +165,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x17,
-        name: InstructionCode::RL,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.rl(RegisterNames::A);
             0
@@ -244,9 +172,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x18,
-        name: InstructionCode::RR,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.rr(RegisterNames::B);
             0
@@ -254,9 +179,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x19,
-        name: InstructionCode::RR,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.rr(RegisterNames::C);
             0
@@ -264,9 +186,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x1A,
-        name: InstructionCode::RR,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.rr(RegisterNames::D);
             0
@@ -274,9 +193,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x1B,
-        name: InstructionCode::RR,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.rr(RegisterNames::E);
             0
@@ -284,9 +200,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x1C,
-        name: InstructionCode::RR,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.rr(RegisterNames::H);
             0
@@ -294,9 +207,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x1D,
-        name: InstructionCode::RR,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.rr(RegisterNames::L);
             0
@@ -304,9 +214,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x1E,
-        name: InstructionCode::RR,
-        length: 2,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.rr(RegisterNames::IndirectHL);
             0
@@ -314,9 +221,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x1F,
-        name: InstructionCode::RR,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.rr(RegisterNames::A);
             0
@@ -324,9 +228,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x20,
-        name: InstructionCode::SLA,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.sla(RegisterNames::B);
             0
@@ -334,9 +235,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x21,
-        name: InstructionCode::SLA,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.sla(RegisterNames::C);
             0
@@ -344,9 +242,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x22,
-        name: InstructionCode::SLA,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.sla(RegisterNames::D);
             0
@@ -354,9 +249,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x23,
-        name: InstructionCode::SLA,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.sla(RegisterNames::E);
             0
@@ -364,9 +256,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x24,
-        name: InstructionCode::SLA,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.sla(RegisterNames::H);
             0
@@ -374,9 +263,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x25,
-        name: InstructionCode::SLA,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.sla(RegisterNames::L);
             0
@@ -384,9 +270,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x26,
-        name: InstructionCode::SLA,
-        length: 2,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.sla(RegisterNames::IndirectHL);
             0
@@ -394,9 +277,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x27,
-        name: InstructionCode::SLA,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.sla(RegisterNames::A);
             0
@@ -404,9 +284,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x28,
-        name: InstructionCode::SRA,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.sra(RegisterNames::B);
             0
@@ -414,9 +291,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x29,
-        name: InstructionCode::SRA,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.sra(RegisterNames::C);
             0
@@ -424,9 +298,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x2A,
-        name: InstructionCode::SRA,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.sra(RegisterNames::D);
             0
@@ -434,9 +305,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x2B,
-        name: InstructionCode::SRA,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.sra(RegisterNames::E);
             0
@@ -444,9 +312,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x2C,
-        name: InstructionCode::SRA,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.sra(RegisterNames::H);
             0
@@ -454,9 +319,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x2D,
-        name: InstructionCode::SRA,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.sra(RegisterNames::L);
             0
@@ -464,9 +326,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x2E,
-        name: InstructionCode::SRA,
-        length: 2,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.sra(RegisterNames::IndirectHL);
             0
@@ -474,9 +333,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x2F,
-        name: InstructionCode::SRA,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.sra(RegisterNames::A);
             0
@@ -484,9 +340,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x30,
-        name: InstructionCode::SWAP,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.swap(RegisterNames::B);
             0
@@ -494,9 +347,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x31,
-        name: InstructionCode::SWAP,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.swap(RegisterNames::C);
             0
@@ -504,9 +354,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x32,
-        name: InstructionCode::SWAP,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.swap(RegisterNames::D);
             0
@@ -514,9 +361,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x33,
-        name: InstructionCode::SWAP,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.swap(RegisterNames::E);
             0
@@ -524,9 +368,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x34,
-        name: InstructionCode::SWAP,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.swap(RegisterNames::H);
             0
@@ -534,9 +375,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x35,
-        name: InstructionCode::SWAP,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.swap(RegisterNames::L);
             0
@@ -544,9 +382,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x36,
-        name: InstructionCode::SWAP,
-        length: 2,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.swap(RegisterNames::IndirectHL);
             0
@@ -554,9 +389,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x37,
-        name: InstructionCode::SWAP,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.swap(RegisterNames::A);
             0
@@ -564,9 +396,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x38,
-        name: InstructionCode::SRL,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.srl(RegisterNames::B);
             0
@@ -574,9 +403,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x39,
-        name: InstructionCode::SRL,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.srl(RegisterNames::C);
             0
@@ -584,9 +410,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x3A,
-        name: InstructionCode::SRL,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.srl(RegisterNames::D);
             0
@@ -594,9 +417,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x3B,
-        name: InstructionCode::SRL,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.srl(RegisterNames::E);
             0
@@ -604,9 +424,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x3C,
-        name: InstructionCode::SRL,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.srl(RegisterNames::H);
             0
@@ -614,9 +431,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x3D,
-        name: InstructionCode::SRL,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.srl(RegisterNames::L);
             0
@@ -624,9 +438,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x3E,
-        name: InstructionCode::SRL,
-        length: 2,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.srl(RegisterNames::IndirectHL);
             0
@@ -634,9 +445,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x3F,
-        name: InstructionCode::SRL,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.srl(RegisterNames::A);
             0
@@ -644,9 +452,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x40,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::B, 0);
             0
@@ -654,9 +459,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x41,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::C, 0);
             0
@@ -664,9 +466,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x42,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::D, 0);
             0
@@ -674,9 +473,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x43,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::E, 0);
             0
@@ -684,9 +480,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x44,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::H, 0);
             0
@@ -694,9 +487,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x45,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::L, 0);
             0
@@ -704,9 +494,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x46,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [12, 12],
         operation: |cpu| {
             cpu.bit(RegisterNames::IndirectHL, 0);
             0
@@ -714,9 +501,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x47,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::A, 0);
             0
@@ -724,9 +508,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x48,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::B, 1);
             0
@@ -734,9 +515,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x49,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::C, 1);
             0
@@ -744,9 +522,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x4A,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::D, 1);
             0
@@ -754,9 +529,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x4B,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::E, 1);
             0
@@ -764,9 +536,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x4C,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::H, 1);
             0
@@ -774,9 +543,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x4D,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::L, 1);
             0
@@ -784,9 +550,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x4E,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [12, 12],
         operation: |cpu| {
             cpu.bit(RegisterNames::IndirectHL, 1);
             0
@@ -794,9 +557,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x4F,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::A, 1);
             0
@@ -804,9 +564,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x50,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::B, 2);
             0
@@ -814,9 +571,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x51,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::C, 2);
             0
@@ -824,9 +578,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x52,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::D, 2);
             0
@@ -834,9 +585,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x53,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::E, 2);
             0
@@ -844,9 +592,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x54,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::H, 2);
             0
@@ -854,9 +599,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x55,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::L, 2);
             0
@@ -864,9 +606,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x56,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [12, 12],
         operation: |cpu| {
             cpu.bit(RegisterNames::IndirectHL, 2);
             0
@@ -874,9 +613,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x57,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::A, 2);
             0
@@ -884,9 +620,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x58,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::B, 3);
             0
@@ -894,9 +627,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x59,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::C, 3);
             0
@@ -904,9 +634,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x5A,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::D, 3);
             0
@@ -914,9 +641,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x5B,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::E, 3);
             0
@@ -924,9 +648,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x5C,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::H, 3);
             0
@@ -934,9 +655,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x5D,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::L, 3);
             0
@@ -944,9 +662,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x5E,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [12, 12],
         operation: |cpu| {
             cpu.bit(RegisterNames::IndirectHL, 3);
             0
@@ -954,9 +669,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x5F,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::A, 3);
             0
@@ -964,9 +676,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x60,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::B, 4);
             0
@@ -974,9 +683,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x61,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::C, 4);
             0
@@ -984,9 +690,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x62,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::D, 4);
             0
@@ -994,9 +697,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x63,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::E, 4);
             0
@@ -1004,9 +704,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x64,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::H, 4);
             0
@@ -1014,9 +711,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x65,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::L, 4);
             0
@@ -1024,9 +718,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x66,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [12, 12],
         operation: |cpu| {
             cpu.bit(RegisterNames::IndirectHL, 4);
             0
@@ -1034,9 +725,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x67,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::A, 4);
             0
@@ -1044,9 +732,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x68,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::B, 5);
             0
@@ -1054,9 +739,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x69,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::C, 5);
             0
@@ -1064,9 +746,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x6A,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::D, 5);
             0
@@ -1074,9 +753,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x6B,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::E, 5);
             0
@@ -1084,9 +760,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x6C,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::H, 5);
             0
@@ -1094,9 +767,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x6D,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::L, 5);
             0
@@ -1104,9 +774,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x6E,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [12, 12],
         operation: |cpu| {
             cpu.bit(RegisterNames::IndirectHL, 5);
             0
@@ -1114,9 +781,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x6F,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::A, 5);
             0
@@ -1124,9 +788,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x70,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::B, 6);
             0
@@ -1134,9 +795,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x71,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::C, 6);
             0
@@ -1144,9 +802,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x72,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::D, 6);
             0
@@ -1154,9 +809,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x73,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::E, 6);
             0
@@ -1164,9 +816,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x74,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::H, 6);
             0
@@ -1174,9 +823,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x75,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::L, 6);
             0
@@ -1184,9 +830,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x76,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [12, 12],
         operation: |cpu| {
             cpu.bit(RegisterNames::IndirectHL, 6);
             0
@@ -1194,9 +837,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x77,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::A, 6);
             0
@@ -1204,9 +844,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x78,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::B, 7);
             0
@@ -1214,9 +851,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x79,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::C, 7);
             0
@@ -1224,9 +858,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x7A,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::D, 7);
             0
@@ -1234,9 +865,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x7B,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::E, 7);
             0
@@ -1244,9 +872,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x7C,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::H, 7);
             0
@@ -1254,9 +879,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x7D,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::L, 7);
             0
@@ -1264,9 +886,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x7E,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [12, 12],
         operation: |cpu| {
             cpu.bit(RegisterNames::IndirectHL, 7);
             0
@@ -1274,9 +893,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x7F,
-        name: InstructionCode::BIT,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.bit(RegisterNames::A, 7);
             0
@@ -1284,9 +900,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x80,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::B, 0);
             0
@@ -1294,9 +907,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x81,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::C, 0);
             0
@@ -1304,9 +914,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x82,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::D, 0);
             0
@@ -1314,9 +921,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x83,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::E, 0);
             0
@@ -1324,9 +928,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x84,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::H, 0);
             0
@@ -1334,9 +935,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x85,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::L, 0);
             0
@@ -1344,9 +942,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x86,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.res(RegisterNames::IndirectHL, 0);
             0
@@ -1354,9 +949,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x87,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::A, 0);
             0
@@ -1364,9 +956,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x88,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::B, 1);
             0
@@ -1374,9 +963,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x89,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::C, 1);
             0
@@ -1384,9 +970,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x8A,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::D, 1);
             0
@@ -1394,9 +977,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x8B,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::E, 1);
             0
@@ -1404,9 +984,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x8C,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::H, 1);
             0
@@ -1414,9 +991,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x8D,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::L, 1);
             0
@@ -1424,9 +998,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x8E,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.res(RegisterNames::IndirectHL, 1);
             0
@@ -1434,9 +1005,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x8F,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::A, 1);
             0
@@ -1444,9 +1012,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x90,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::B, 2);
             0
@@ -1454,9 +1019,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x91,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::C, 2);
             0
@@ -1464,9 +1026,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x92,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::D, 2);
             0
@@ -1474,9 +1033,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x93,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::E, 2);
             0
@@ -1484,9 +1040,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x94,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::H, 2);
             0
@@ -1494,9 +1047,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x95,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::L, 2);
             0
@@ -1504,9 +1054,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x96,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.res(RegisterNames::IndirectHL, 2);
             0
@@ -1514,9 +1061,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x97,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::A, 2);
             0
@@ -1524,9 +1068,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x98,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::B, 3);
             0
@@ -1534,9 +1075,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x99,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::C, 3);
             0
@@ -1544,9 +1082,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x9A,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::D, 3);
             0
@@ -1554,9 +1089,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x9B,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::E, 3);
             0
@@ -1564,9 +1096,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x9C,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::H, 3);
             0
@@ -1574,9 +1103,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x9D,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::L, 3);
             0
@@ -1584,9 +1110,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x9E,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.res(RegisterNames::IndirectHL, 3);
             0
@@ -1594,9 +1117,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x9F,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::A, 3);
             0
@@ -1604,9 +1124,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xA0,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::B, 4);
             0
@@ -1614,9 +1131,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xA1,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::C, 4);
             0
@@ -1624,9 +1138,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xA2,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::D, 4);
             0
@@ -1634,9 +1145,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xA3,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::E, 4);
             0
@@ -1644,9 +1152,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xA4,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::H, 4);
             0
@@ -1654,9 +1159,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xA5,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::L, 4);
             0
@@ -1664,9 +1166,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xA6,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.res(RegisterNames::IndirectHL, 4);
             0
@@ -1674,9 +1173,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xA7,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::A, 4);
             0
@@ -1684,9 +1180,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xA8,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::B, 5);
             0
@@ -1694,9 +1187,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xA9,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::C, 5);
             0
@@ -1704,9 +1194,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xAA,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::D, 5);
             0
@@ -1714,9 +1201,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xAB,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::E, 5);
             0
@@ -1724,9 +1208,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xAC,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::H, 5);
             0
@@ -1734,9 +1215,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xAD,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::L, 5);
             0
@@ -1744,9 +1222,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xAE,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.res(RegisterNames::IndirectHL, 5);
             0
@@ -1754,9 +1229,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xAF,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::A, 5);
             0
@@ -1764,9 +1236,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xB0,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::B, 6);
             0
@@ -1774,9 +1243,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xB1,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::C, 6);
             0
@@ -1784,9 +1250,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xB2,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::D, 6);
             0
@@ -1794,9 +1257,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xB3,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::E, 6);
             0
@@ -1804,9 +1264,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xB4,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::H, 6);
             0
@@ -1814,9 +1271,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xB5,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::L, 6);
             0
@@ -1824,9 +1278,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xB6,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.res(RegisterNames::IndirectHL, 6);
             0
@@ -1834,9 +1285,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xB7,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::A, 6);
             0
@@ -1844,9 +1292,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xB8,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::B, 7);
             0
@@ -1854,9 +1299,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xB9,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::C, 7);
             0
@@ -1864,9 +1306,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xBA,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::D, 7);
             0
@@ -1874,9 +1313,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xBB,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::E, 7);
             0
@@ -1884,9 +1320,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xBC,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::H, 7);
             0
@@ -1894,9 +1327,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xBD,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::L, 7);
             0
@@ -1904,9 +1334,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xBE,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.res(RegisterNames::IndirectHL, 7);
             0
@@ -1914,9 +1341,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xBF,
-        name: InstructionCode::RES,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.res(RegisterNames::A, 7);
             0
@@ -1924,9 +1348,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xC0,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::B, 0);
             0
@@ -1934,9 +1355,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xC1,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::C, 0);
             0
@@ -1944,9 +1362,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xC2,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::D, 0);
             0
@@ -1954,9 +1369,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xC3,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::E, 0);
             0
@@ -1964,9 +1376,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xC4,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::H, 0);
             0
@@ -1974,9 +1383,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xC5,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::L, 0);
             0
@@ -1984,9 +1390,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xC6,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.set(RegisterNames::IndirectHL, 0);
             0
@@ -1994,9 +1397,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xC7,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::A, 0);
             0
@@ -2004,9 +1404,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xC8,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::B, 1);
             0
@@ -2014,9 +1411,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xC9,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::C, 1);
             0
@@ -2024,9 +1418,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xCA,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::D, 1);
             0
@@ -2034,9 +1425,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xCB,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::E, 1);
             0
@@ -2044,9 +1432,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xCC,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::H, 1);
             0
@@ -2054,9 +1439,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xCD,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::L, 1);
             0
@@ -2064,9 +1446,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xCE,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.set(RegisterNames::IndirectHL, 1);
             0
@@ -2074,9 +1453,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xCF,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::A, 1);
             0
@@ -2084,9 +1460,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xD0,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::B, 2);
             0
@@ -2094,9 +1467,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xD1,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::C, 2);
             0
@@ -2104,9 +1474,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xD2,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::D, 2);
             0
@@ -2114,9 +1481,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xD3,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::E, 2);
             0
@@ -2124,9 +1488,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xD4,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::H, 2);
             0
@@ -2134,9 +1495,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xD5,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::L, 2);
             0
@@ -2144,9 +1502,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xD6,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.set(RegisterNames::IndirectHL, 2);
             0
@@ -2154,9 +1509,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xD7,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::A, 2);
             0
@@ -2164,9 +1516,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xD8,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::B, 3);
             0
@@ -2174,9 +1523,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xD9,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::C, 3);
             0
@@ -2184,9 +1530,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xDA,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::D, 3);
             0
@@ -2194,9 +1537,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xDB,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::E, 3);
             0
@@ -2204,9 +1544,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xDC,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::H, 3);
             0
@@ -2214,9 +1551,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xDD,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::L, 3);
             0
@@ -2224,9 +1558,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xDE,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.set(RegisterNames::IndirectHL, 3);
             0
@@ -2234,9 +1565,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xDF,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::A, 3);
             0
@@ -2244,9 +1572,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xE0,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::B, 4);
             0
@@ -2254,9 +1579,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xE1,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::C, 4);
             0
@@ -2264,9 +1586,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xE2,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::D, 4);
             0
@@ -2274,9 +1593,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xE3,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::E, 4);
             0
@@ -2284,9 +1600,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xE4,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::H, 4);
             0
@@ -2294,9 +1607,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xE5,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::L, 4);
             0
@@ -2304,9 +1614,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xE6,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.set(RegisterNames::IndirectHL, 4);
             0
@@ -2314,9 +1621,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xE7,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::A, 4);
             0
@@ -2324,9 +1628,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xE8,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::B, 5);
             0
@@ -2334,9 +1635,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xE9,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::C, 5);
             0
@@ -2344,9 +1642,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xEA,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::D, 5);
             0
@@ -2354,9 +1649,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xEB,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::E, 5);
             0
@@ -2364,9 +1656,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xEC,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::H, 5);
             0
@@ -2374,9 +1663,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xED,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::L, 5);
             0
@@ -2384,9 +1670,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xEE,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.set(RegisterNames::IndirectHL, 5);
             0
@@ -2394,9 +1677,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xEF,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::A, 5);
             0
@@ -2404,9 +1684,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xF0,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::B, 6);
             0
@@ -2414,9 +1691,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xF1,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::C, 6);
             0
@@ -2424,9 +1698,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xF2,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::D, 6);
             0
@@ -2434,9 +1705,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xF3,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::E, 6);
             0
@@ -2444,9 +1712,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xF4,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::H, 6);
             0
@@ -2454,9 +1719,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xF5,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::L, 6);
             0
@@ -2464,9 +1726,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xF6,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.set(RegisterNames::IndirectHL, 6);
             0
@@ -2474,9 +1733,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xF7,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::A, 6);
             0
@@ -2484,9 +1740,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xF8,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::B, 7);
             0
@@ -2494,9 +1747,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xF9,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::C, 7);
             0
@@ -2504,9 +1754,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xFA,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::D, 7);
             0
@@ -2514,9 +1761,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xFB,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::E, 7);
             0
@@ -2524,9 +1768,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xFC,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::H, 7);
             0
@@ -2534,9 +1775,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xFD,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::L, 7);
             0
@@ -2544,9 +1782,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xFE,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.set(RegisterNames::IndirectHL, 7);
             0
@@ -2554,9 +1789,6 @@ pub const CB_INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xFF,
-        name: InstructionCode::SET,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set(RegisterNames::A, 7);
             0
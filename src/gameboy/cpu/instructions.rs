@@ -1,102 +1,23 @@
 use super::cb_instructions::CB_INSTRUCTION_TABLE;
 use super::registers::{RegisterNames, RegisterNames16b};
+use crate::gameboy::bus::MemoryBus;
 use crate::gameboy::cpu::Cpu;
-use std::fmt;
 
+/// Length, cycles and mnemonic of the opcode are generated in super::metadata.
+/// The operation returns which of the two cycle counts applies, 1 when a
+/// conditional instruction does not branch.
 pub struct Instruction {
     pub opcode: u8,
-    pub length: u16,
-    pub cycles: [u32; 2],
-    pub name: InstructionCode,
-    pub operation: fn(cpu: &mut Cpu) -> usize,
-}
-
-impl fmt::Display for Instruction {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.name)
-    }
-}
-
-#[allow(non_camel_case_types)]
-#[derive(PartialEq, Debug)]
-pub enum InstructionCode {
-    NOP,
-    LD,
-    INC,
-    DEC,
-    RLCA,
-    ADD,
-    RRCA,
-    STOP,
-    RLA,
-    JR,
-    RRA,
-    DAA,
-    CPL,
-    SCF,
-    CCF,
-    HALT,
-    ADC,
-    SUB,
-    SBC,
-    AND,
-    XOR,
-    OR,
-    CP,
-    RET,
-    POP,
-    JP,
-    CALL,
-    PUSH,
-    RST,
-    PREFIX,
-    ILLEGAL_D3,
-    RETI,
-    ILLEGAL_DB,
-    ILLEGAL_DD,
-    LDH,
-    ILLEGAL_E3,
-    ILLEGAL_E4,
-    ILLEGAL_EB,
-    ILLEGAL_EC,
-    ILLEGAL_ED,
-    DI,
-    ILLEGAL_F4,
-    EI,
-    ILLEGAL_FC,
-    ILLEGAL_FD,
-    RLC,
-    RRC,
-    RL,
-    RR,
-    SLA,
-    SRA,
-    SWAP,
-    SRL,
-    BIT,
-    RES,
-    SET,
-}
-
-impl fmt::Display for InstructionCode {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", self)
-    }
+    pub operation: fn(cpu: &mut Cpu<dyn MemoryBus>) -> usize,
 }
 
 pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     Instruction {
         opcode: 0x00,
-        name: InstructionCode::NOP,
-        length: 1,
-        cycles: [4, 4],
         operation: |_| 0,
     },
     Instruction {
         opcode: 0x01,
-        name: InstructionCode::LD,
-        length: 3,
-        cycles: [12, 12],
         operation: |cpu| {
             let value = cpu.get_immediate_16();
             cpu.registers.set_bc(value);
@@ -105,9 +26,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x02,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set_value_at(cpu.registers.get_bc(), cpu.registers.a);
             0
@@ -115,19 +33,14 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x03,
-        name: InstructionCode::INC,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
+            cpu.tick();
             cpu.registers.inc_bc();
             0
         },
     },
     Instruction {
         opcode: 0x04,
-        name: InstructionCode::INC,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.inc(RegisterNames::B);
             0
@@ -135,9 +48,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x05,
-        name: InstructionCode::DEC,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.dec(RegisterNames::B);
             0
@@ -145,9 +55,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x06,
-        name: InstructionCode::LD,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.registers.b = cpu.get_immediate();
             0
@@ -155,9 +62,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x07,
-        name: InstructionCode::RLCA,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.rlc(RegisterNames::A);
             cpu.flags.zero = false;
@@ -166,9 +70,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x08,
-        name: InstructionCode::LD,
-        length: 3,
-        cycles: [20, 20],
         operation: |cpu| {
             let address = cpu.get_immediate_16();
             cpu.set_value_16_at(address, cpu.registers.sp);
@@ -177,9 +78,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x09,
-        name: InstructionCode::ADD,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.add_16b_register_to_hl(RegisterNames16b::BC);
             0
@@ -187,9 +85,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x0A,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.registers.a = cpu.get_value_at(cpu.registers.get_bc());
             0
@@ -197,19 +92,14 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x0B,
-        name: InstructionCode::DEC,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
+            cpu.tick();
             cpu.registers.dec_bc();
             0
         },
     },
     Instruction {
         opcode: 0x0C,
-        name: InstructionCode::INC,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.inc(RegisterNames::C);
             0
@@ -217,9 +107,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x0D,
-        name: InstructionCode::DEC,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.dec(RegisterNames::C);
             0
@@ -227,9 +114,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x0E,
-        name: InstructionCode::LD,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.registers.c = cpu.get_immediate();
             0
@@ -237,9 +121,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x0F,
-        name: InstructionCode::RRCA,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.rrc(RegisterNames::A);
             cpu.flags.zero = false;
@@ -248,16 +129,13 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x10,
-        name: InstructionCode::STOP,
-        length: 2,
-        cycles: [4, 4],
-        operation: |_cpu| 0,
+        operation: |cpu| {
+            cpu.stop();
+            0
+        },
     },
     Instruction {
         opcode: 0x11,
-        name: InstructionCode::LD,
-        length: 3,
-        cycles: [12, 12],
         operation: |cpu| {
             let value = cpu.get_immediate_16();
             cpu.registers.set_de(value);
@@ -266,9 +144,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x12,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set_value_at(cpu.registers.get_de(), cpu.registers.a);
             0
@@ -276,19 +151,14 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x13,
-        name: InstructionCode::INC,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
+            cpu.tick();
             cpu.registers.inc_de();
             0
         },
     },
     Instruction {
         opcode: 0x14,
-        name: InstructionCode::INC,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.inc(RegisterNames::D);
             0
@@ -296,9 +166,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x15,
-        name: InstructionCode::DEC,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.dec(RegisterNames::D);
             0
@@ -306,9 +173,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x16,
-        name: InstructionCode::LD,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.registers.d = cpu.get_immediate();
             0
@@ -316,9 +180,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x17,
-        name: InstructionCode::RLA,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.rl(RegisterNames::A);
             cpu.flags.zero = false;
@@ -327,20 +188,14 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x18,
-        name: InstructionCode::JR,
-        length: 2,
-        cycles: [12, 12],
         operation: |cpu| {
             let signed_value = cpu.get_immediate() as i8;
-            cpu.registers.pc = cpu.registers.pc.wrapping_add(signed_value as u16);
+            cpu.jump(cpu.registers.pc.wrapping_add(signed_value as u16));
             0
         },
     },
     Instruction {
         opcode: 0x19,
-        name: InstructionCode::ADD,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.add_16b_register_to_hl(RegisterNames16b::DE);
             0
@@ -348,9 +203,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x1A,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.registers.a = cpu.get_value_at(cpu.registers.get_de());
             0
@@ -358,19 +210,14 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x1B,
-        name: InstructionCode::DEC,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
+            cpu.tick();
             cpu.registers.dec_de();
             0
         },
     },
     Instruction {
         opcode: 0x1C,
-        name: InstructionCode::INC,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.inc(RegisterNames::E);
             0
@@ -378,9 +225,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x1D,
-        name: InstructionCode::DEC,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.dec(RegisterNames::E);
             0
@@ -388,9 +232,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x1E,
-        name: InstructionCode::LD,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.registers.e = cpu.get_immediate();
             0
@@ -398,9 +239,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x1F,
-        name: InstructionCode::RRA,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.rr(RegisterNames::A);
             cpu.flags.zero = false;
@@ -409,13 +247,10 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x20,
-        name: InstructionCode::JR,
-        length: 2,
-        cycles: [12, 8],
         operation: |cpu| {
             let signed_value = cpu.get_immediate() as i8;
             if !cpu.flags.zero {
-                cpu.registers.pc = cpu.registers.pc.wrapping_add(signed_value as u16);
+                cpu.jump(cpu.registers.pc.wrapping_add(signed_value as u16));
                 0
             } else {
                 1
@@ -424,9 +259,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x21,
-        name: InstructionCode::LD,
-        length: 3,
-        cycles: [12, 12],
         operation: |cpu| {
             let value = cpu.get_immediate_16();
             cpu.registers.set_hl(value);
@@ -435,9 +267,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x22,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set_value_at_hl(cpu.registers.a);
             cpu.registers.inc_hl();
@@ -446,19 +275,14 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x23,
-        name: InstructionCode::INC,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
+            cpu.tick();
             cpu.registers.inc_hl();
             0
         },
     },
     Instruction {
         opcode: 0x24,
-        name: InstructionCode::INC,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.inc(RegisterNames::H);
             0
@@ -466,9 +290,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x25,
-        name: InstructionCode::DEC,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.dec(RegisterNames::H);
             0
@@ -476,9 +297,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x26,
-        name: InstructionCode::LD,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.registers.h = cpu.get_immediate();
             0
@@ -486,20 +304,17 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x27,
-        name: InstructionCode::DAA,
-        length: 1,
-        cycles: [4, 4],
-        operation: |_cpu| 0,
+        operation: |cpu| {
+            cpu.daa();
+            0
+        },
     },
     Instruction {
         opcode: 0x28,
-        name: InstructionCode::JR,
-        length: 2,
-        cycles: [12, 8],
         operation: |cpu| {
             let signed_value = cpu.get_immediate() as i8;
             if cpu.flags.zero {
-                cpu.registers.pc = cpu.registers.pc.wrapping_add(signed_value as u16);
+                cpu.jump(cpu.registers.pc.wrapping_add(signed_value as u16));
                 0
             } else {
                 1
@@ -508,9 +323,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x29,
-        name: InstructionCode::ADD,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.add_16b_register_to_hl(RegisterNames16b::HL);
             0
@@ -518,9 +330,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x2A,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.registers.a = cpu.get_value_at_hl();
             cpu.registers.inc_hl();
@@ -529,19 +338,14 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x2B,
-        name: InstructionCode::DEC,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
+            cpu.tick();
             cpu.registers.dec_hl();
             0
         },
     },
     Instruction {
         opcode: 0x2C,
-        name: InstructionCode::INC,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.inc(RegisterNames::L);
             0
@@ -549,9 +353,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x2D,
-        name: InstructionCode::DEC,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.dec(RegisterNames::L);
             0
@@ -559,9 +360,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x2E,
-        name: InstructionCode::LD,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.registers.l = cpu.get_immediate();
             0
@@ -569,9 +367,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x2F,
-        name: InstructionCode::CPL,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.cpl();
             0
@@ -579,13 +374,10 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x30,
-        name: InstructionCode::JR,
-        length: 2,
-        cycles: [12, 8],
         operation: |cpu| {
             let signed_value = cpu.get_immediate() as i8;
             if !cpu.flags.carry {
-                cpu.registers.pc = cpu.registers.pc.wrapping_add(signed_value as u16);
+                cpu.jump(cpu.registers.pc.wrapping_add(signed_value as u16));
                 0
             } else {
                 1
@@ -594,9 +386,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x31,
-        name: InstructionCode::LD,
-        length: 3,
-        cycles: [12, 12],
         operation: |cpu| {
             cpu.registers.sp = cpu.get_immediate_16();
             0
@@ -604,9 +393,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x32,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set_value_at_hl(cpu.registers.a);
             cpu.registers.dec_hl();
@@ -615,19 +401,14 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x33,
-        name: InstructionCode::INC,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
+            cpu.tick();
             cpu.registers.inc_sp();
             0
         },
     },
     Instruction {
         opcode: 0x34,
-        name: InstructionCode::INC,
-        length: 1,
-        cycles: [12, 12],
         operation: |cpu| {
             cpu.inc(RegisterNames::IndirectHL);
             0
@@ -635,9 +416,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x35,
-        name: InstructionCode::DEC,
-        length: 1,
-        cycles: [12, 12],
         operation: |cpu| {
             cpu.dec(RegisterNames::IndirectHL);
             0
@@ -645,9 +423,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x36,
-        name: InstructionCode::LD,
-        length: 2,
-        cycles: [12, 12],
         operation: |cpu| {
             let value = cpu.get_immediate();
             cpu.set_value_at_hl(value);
@@ -656,9 +431,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x37,
-        name: InstructionCode::SCF,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.flags.negative = false;
             cpu.flags.half_carry = false;
@@ -668,13 +440,10 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x38,
-        name: InstructionCode::JR,
-        length: 2,
-        cycles: [12, 8],
         operation: |cpu| {
             let signed_value = cpu.get_immediate() as i8;
             if cpu.flags.carry {
-                cpu.registers.pc = cpu.registers.pc.wrapping_add(signed_value as u16);
+                cpu.jump(cpu.registers.pc.wrapping_add(signed_value as u16));
                 0
             } else {
                 1
@@ -683,9 +452,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x39,
-        name: InstructionCode::ADD,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.add_16b_register_to_hl(RegisterNames16b::SP);
             0
@@ -693,9 +459,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x3A,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.registers.a = cpu.get_value_at_hl();
             cpu.registers.dec_hl();
@@ -704,19 +467,14 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x3B,
-        name: InstructionCode::DEC,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
+            cpu.tick();
             cpu.registers.dec_sp();
             0
         },
     },
     Instruction {
         opcode: 0x3C,
-        name: InstructionCode::INC,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.inc(RegisterNames::A);
             0
@@ -724,9 +482,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x3D,
-        name: InstructionCode::DEC,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.dec(RegisterNames::A);
             0
@@ -734,9 +489,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x3E,
-        name: InstructionCode::LD,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.registers.a = cpu.get_immediate();
             0
@@ -744,9 +496,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x3F,
-        name: InstructionCode::CCF,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.flags.negative = false;
             cpu.flags.half_carry = false;
@@ -756,19 +505,13 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x40,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
-            cpu.registers.b = cpu.registers.b;
+            cpu.breakpoint_hit = true;
             0
         },
     },
     Instruction {
         opcode: 0x41,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.b = cpu.registers.c;
             0
@@ -776,9 +519,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x42,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.b = cpu.registers.d;
             0
@@ -786,9 +526,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x43,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.b = cpu.registers.e;
             0
@@ -796,9 +533,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x44,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.b = cpu.registers.h;
             0
@@ -806,9 +540,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x45,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.b = cpu.registers.l;
             0
@@ -816,9 +547,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x46,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.registers.b = cpu.get_value_at_hl();
             0
@@ -826,9 +554,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x47,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.b = cpu.registers.a;
             0
@@ -836,9 +561,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x48,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.c = cpu.registers.b;
             0
@@ -846,9 +568,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x49,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.c = cpu.registers.c;
             0
@@ -856,9 +575,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x4A,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.c = cpu.registers.d;
             0
@@ -866,9 +582,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x4B,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.c = cpu.registers.e;
             0
@@ -876,9 +589,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x4C,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.c = cpu.registers.h;
             0
@@ -886,9 +596,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x4D,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.c = cpu.registers.l;
             0
@@ -896,9 +603,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x4E,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.registers.c = cpu.get_value_at_hl();
             0
@@ -906,9 +610,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x4F,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.c = cpu.registers.a;
             0
@@ -916,9 +617,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x50,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.d = cpu.registers.b;
             0
@@ -926,9 +624,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x51,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.d = cpu.registers.c;
             0
@@ -936,9 +631,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x52,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.d = cpu.registers.d;
             0
@@ -946,9 +638,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x53,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.d = cpu.registers.e;
             0
@@ -956,9 +645,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x54,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.d = cpu.registers.h;
             0
@@ -966,9 +652,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x55,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.d = cpu.registers.l;
             0
@@ -976,9 +659,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x56,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.registers.d = cpu.get_value_at_hl();
             0
@@ -986,9 +666,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x57,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.d = cpu.registers.a;
             0
@@ -996,9 +673,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x58,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.e = cpu.registers.b;
             0
@@ -1006,9 +680,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x59,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.e = cpu.registers.c;
             0
@@ -1016,9 +687,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x5A,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.e = cpu.registers.d;
             0
@@ -1026,9 +694,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x5B,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.e = cpu.registers.e;
             0
@@ -1036,9 +701,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x5C,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.e = cpu.registers.h;
             0
@@ -1046,9 +708,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x5D,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.e = cpu.registers.l;
             0
@@ -1056,9 +715,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x5E,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.registers.e = cpu.get_value_at_hl();
             0
@@ -1066,9 +722,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x5F,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.e = cpu.registers.a;
             0
@@ -1076,9 +729,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x60,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.h = cpu.registers.b;
             0
@@ -1086,9 +736,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x61,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.h = cpu.registers.c;
             0
@@ -1096,9 +743,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x62,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.h = cpu.registers.d;
             0
@@ -1106,9 +750,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x63,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.h = cpu.registers.e;
             0
@@ -1116,9 +757,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x64,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.h = cpu.registers.h;
             0
@@ -1126,9 +764,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x65,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.h = cpu.registers.l;
             0
@@ -1136,9 +771,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x66,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.registers.h = cpu.get_value_at_hl();
             0
@@ -1146,9 +778,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x67,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.h = cpu.registers.a;
             0
@@ -1156,9 +785,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x68,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.l = cpu.registers.b;
             0
@@ -1166,9 +792,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x69,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.l = cpu.registers.c;
             0
@@ -1176,9 +799,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x6A,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.l = cpu.registers.d;
             0
@@ -1186,9 +806,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x6B,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.l = cpu.registers.e;
             0
@@ -1196,9 +813,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x6C,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.l = cpu.registers.h;
             0
@@ -1206,9 +820,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x6D,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.l = cpu.registers.l;
             0
@@ -1216,9 +827,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x6E,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.registers.l = cpu.get_value_at_hl();
             0
@@ -1226,9 +834,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x6F,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.l = cpu.registers.a;
             0
@@ -1236,9 +841,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x70,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set_value_at_hl(cpu.registers.b);
             0
@@ -1246,9 +848,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x71,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set_value_at_hl(cpu.registers.c);
             0
@@ -1256,9 +855,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x72,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set_value_at_hl(cpu.registers.d);
             0
@@ -1266,9 +862,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x73,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set_value_at_hl(cpu.registers.e);
             0
@@ -1276,9 +869,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x74,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set_value_at_hl(cpu.registers.h);
             0
@@ -1286,9 +876,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x75,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set_value_at_hl(cpu.registers.l);
             0
@@ -1296,9 +883,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x76,
-        name: InstructionCode::HALT,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.halt();
             0
@@ -1306,9 +890,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x77,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.set_value_at_hl(cpu.registers.a);
             0
@@ -1316,9 +897,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x78,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.a = cpu.registers.b;
             0
@@ -1326,9 +904,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x79,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.a = cpu.registers.c;
             0
@@ -1336,9 +911,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x7A,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.a = cpu.registers.d;
             0
@@ -1346,9 +918,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x7B,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.a = cpu.registers.e;
             0
@@ -1356,9 +925,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x7C,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.a = cpu.registers.h;
             0
@@ -1366,9 +932,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x7D,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.a = cpu.registers.l;
             0
@@ -1376,9 +939,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x7E,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
             cpu.registers.a = cpu.get_value_at_hl();
             0
@@ -1386,9 +946,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x7F,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.registers.a = cpu.registers.a;
             0
@@ -1396,9 +953,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x80,
-        name: InstructionCode::ADD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.add(cpu.registers.b);
             0
@@ -1406,9 +960,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x81,
-        name: InstructionCode::ADD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.add(cpu.registers.c);
             0
@@ -1416,9 +967,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x82,
-        name: InstructionCode::ADD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.add(cpu.registers.d);
             0
@@ -1426,9 +974,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x83,
-        name: InstructionCode::ADD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.add(cpu.registers.e);
             0
@@ -1436,9 +981,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x84,
-        name: InstructionCode::ADD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.add(cpu.registers.h);
             0
@@ -1446,9 +988,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x85,
-        name: InstructionCode::ADD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.add(cpu.registers.l);
             0
@@ -1456,9 +995,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x86,
-        name: InstructionCode::ADD,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
             let value = cpu.get_value_at_hl();
             cpu.add(value);
@@ -1467,9 +1003,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x87,
-        name: InstructionCode::ADD,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.add(cpu.registers.a);
             0
@@ -1477,9 +1010,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x88,
-        name: InstructionCode::ADC,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.adc(cpu.registers.b);
             0
@@ -1487,9 +1017,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x89,
-        name: InstructionCode::ADC,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.adc(cpu.registers.c);
             0
@@ -1497,9 +1024,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x8A,
-        name: InstructionCode::ADC,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.adc(cpu.registers.d);
             0
@@ -1507,9 +1031,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x8B,
-        name: InstructionCode::ADC,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.adc(cpu.registers.e);
             0
@@ -1517,9 +1038,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x8C,
-        name: InstructionCode::ADC,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.adc(cpu.registers.h);
             0
@@ -1527,9 +1045,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x8D,
-        name: InstructionCode::ADC,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.adc(cpu.registers.l);
             0
@@ -1537,9 +1052,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x8E,
-        name: InstructionCode::ADC,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
             let value = cpu.get_value_at_hl();
             cpu.adc(value);
@@ -1548,9 +1060,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x8F,
-        name: InstructionCode::ADC,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.adc(cpu.registers.a);
             0
@@ -1558,9 +1067,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x90,
-        name: InstructionCode::SUB,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.sub(cpu.registers.b);
             0
@@ -1568,9 +1074,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x91,
-        name: InstructionCode::SUB,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.sub(cpu.registers.c);
             0
@@ -1578,9 +1081,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x92,
-        name: InstructionCode::SUB,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.sub(cpu.registers.d);
             0
@@ -1588,9 +1088,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x93,
-        name: InstructionCode::SUB,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.sub(cpu.registers.e);
             0
@@ -1598,9 +1095,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x94,
-        name: InstructionCode::SUB,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.sub(cpu.registers.h);
             0
@@ -1608,9 +1102,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x95,
-        name: InstructionCode::SUB,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.sub(cpu.registers.l);
             0
@@ -1618,9 +1109,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x96,
-        name: InstructionCode::SUB,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
             let value = cpu.get_value_at_hl();
             cpu.sub(value);
@@ -1629,9 +1117,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x97,
-        name: InstructionCode::SUB,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.sub(cpu.registers.a);
             0
@@ -1639,9 +1124,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x98,
-        name: InstructionCode::SBC,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.sbc(cpu.registers.b);
             0
@@ -1649,9 +1131,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x99,
-        name: InstructionCode::SBC,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.sbc(cpu.registers.c);
             0
@@ -1659,9 +1138,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x9A,
-        name: InstructionCode::SBC,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.sbc(cpu.registers.d);
             0
@@ -1669,9 +1145,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x9B,
-        name: InstructionCode::SBC,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.sbc(cpu.registers.e);
             0
@@ -1679,9 +1152,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x9C,
-        name: InstructionCode::SBC,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.sbc(cpu.registers.h);
             0
@@ -1689,9 +1159,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x9D,
-        name: InstructionCode::SBC,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.sbc(cpu.registers.l);
             0
@@ -1699,9 +1166,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x9E,
-        name: InstructionCode::SBC,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
             let value = cpu.get_value_at_hl();
             cpu.sbc(value);
@@ -1710,9 +1174,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0x9F,
-        name: InstructionCode::SBC,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.sbc(cpu.registers.a);
             0
@@ -1720,9 +1181,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xA0,
-        name: InstructionCode::AND,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.xor(cpu.registers.b);
             0
@@ -1730,9 +1188,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xA1,
-        name: InstructionCode::AND,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.xor(cpu.registers.c);
             0
@@ -1740,9 +1195,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xA2,
-        name: InstructionCode::AND,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.xor(cpu.registers.d);
             0
@@ -1750,9 +1202,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xA3,
-        name: InstructionCode::AND,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.xor(cpu.registers.e);
             0
@@ -1760,9 +1209,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xA4,
-        name: InstructionCode::AND,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.xor(cpu.registers.h);
             0
@@ -1770,9 +1216,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xA5,
-        name: InstructionCode::AND,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.xor(cpu.registers.l);
             0
@@ -1780,9 +1223,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xA6,
-        name: InstructionCode::AND,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
             let value = cpu.get_value_at_hl();
             cpu.and(value);
@@ -1791,9 +1231,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xA7,
-        name: InstructionCode::AND,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.xor(cpu.registers.a);
             0
@@ -1801,9 +1238,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xA8,
-        name: InstructionCode::XOR,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.xor(cpu.registers.b);
             0
@@ -1811,9 +1245,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xA9,
-        name: InstructionCode::XOR,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.xor(cpu.registers.c);
             0
@@ -1821,9 +1252,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xAA,
-        name: InstructionCode::XOR,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.xor(cpu.registers.d);
             0
@@ -1831,9 +1259,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xAB,
-        name: InstructionCode::XOR,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.xor(cpu.registers.e);
             0
@@ -1841,9 +1266,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xAC,
-        name: InstructionCode::XOR,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.xor(cpu.registers.h);
             0
@@ -1851,9 +1273,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xAD,
-        name: InstructionCode::XOR,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.xor(cpu.registers.l);
             0
@@ -1861,9 +1280,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xAE,
-        name: InstructionCode::XOR,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
             let value = cpu.get_value_at_hl();
             cpu.xor(value);
@@ -1872,9 +1288,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xAF,
-        name: InstructionCode::XOR,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.xor(cpu.registers.a);
             0
@@ -1882,9 +1295,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xB0,
-        name: InstructionCode::OR,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.or(cpu.registers.b);
             0
@@ -1892,9 +1302,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xB1,
-        name: InstructionCode::OR,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.or(cpu.registers.c);
             0
@@ -1902,9 +1309,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xB2,
-        name: InstructionCode::OR,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.or(cpu.registers.d);
             0
@@ -1912,9 +1316,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xB3,
-        name: InstructionCode::OR,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.or(cpu.registers.e);
             0
@@ -1922,9 +1323,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xB4,
-        name: InstructionCode::OR,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.or(cpu.registers.h);
             0
@@ -1932,9 +1330,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xB5,
-        name: InstructionCode::OR,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.or(cpu.registers.l);
             0
@@ -1942,9 +1337,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xB6,
-        name: InstructionCode::OR,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
             let value = cpu.get_value_at_hl();
             cpu.or(value);
@@ -1953,9 +1345,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xB7,
-        name: InstructionCode::OR,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.or(cpu.registers.a);
             0
@@ -1963,9 +1352,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xB8,
-        name: InstructionCode::CP,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.cp(cpu.registers.b);
             0
@@ -1973,9 +1359,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xB9,
-        name: InstructionCode::CP,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.cp(cpu.registers.c);
             0
@@ -1983,9 +1366,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xBA,
-        name: InstructionCode::CP,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.cp(cpu.registers.d);
             0
@@ -1993,9 +1373,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xBB,
-        name: InstructionCode::CP,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.cp(cpu.registers.e);
             0
@@ -2003,9 +1380,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xBC,
-        name: InstructionCode::CP,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.cp(cpu.registers.h);
             0
@@ -2013,9 +1387,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xBD,
-        name: InstructionCode::CP,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.cp(cpu.registers.l);
             0
@@ -2023,9 +1394,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xBE,
-        name: InstructionCode::CP,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
             let value = cpu.get_value_at_hl();
             cpu.cp(value);
@@ -2034,9 +1402,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xBF,
-        name: InstructionCode::CP,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.cp(cpu.registers.a);
             0
@@ -2044,10 +1409,9 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xC0,
-        name: InstructionCode::RET,
-        length: 1,
-        cycles: [20, 8],
         operation: |cpu| {
+            // The condition is checked during an internal M-cycle
+            cpu.tick();
             if !cpu.flags.zero {
                 cpu.ret();
                 0
@@ -2058,9 +1422,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xC1,
-        name: InstructionCode::POP,
-        length: 1,
-        cycles: [12, 12],
         operation: |cpu| {
             let value = cpu.pop();
             cpu.registers.set_bc(value);
@@ -2069,13 +1430,10 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xC2,
-        name: InstructionCode::JP,
-        length: 3,
-        cycles: [16, 12],
         operation: |cpu| {
             let value = cpu.get_immediate_16();
             if !cpu.flags.zero {
-                cpu.registers.pc = value;
+                cpu.jump(value);
                 0
             } else {
                 1
@@ -2084,33 +1442,26 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xC3,
-        name: InstructionCode::JP,
-        length: 3,
-        cycles: [16, 16],
         operation: |cpu| {
-            cpu.registers.pc = cpu.get_immediate_16();
+            let address = cpu.get_immediate_16();
+            cpu.jump(address);
             0
         },
     },
     Instruction {
         opcode: 0xC4,
-        name: InstructionCode::CALL,
-        length: 3,
-        cycles: [24, 12],
-        operation: |cpu| match cpu.flags.zero {
-            false => {
-                let address = cpu.get_immediate_16();
+        operation: |cpu| {
+            let address = cpu.get_immediate_16();
+            if !cpu.flags.zero {
                 cpu.call(address);
                 0
+            } else {
+                1
             }
-            true => 1,
         },
     },
     Instruction {
         opcode: 0xC5,
-        name: InstructionCode::PUSH,
-        length: 1,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.push(cpu.registers.get_bc());
             0
@@ -2118,9 +1469,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xC6,
-        name: InstructionCode::ADD,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             let value = cpu.get_immediate();
             cpu.add(value);
@@ -2129,9 +1477,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xC7,
-        name: InstructionCode::RST,
-        length: 1,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.rst(0);
             0
@@ -2139,10 +1484,9 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xC8,
-        name: InstructionCode::RET,
-        length: 1,
-        cycles: [20, 8],
         operation: |cpu| {
+            // The condition is checked during an internal M-cycle
+            cpu.tick();
             if cpu.flags.zero {
                 cpu.ret();
                 0
@@ -2153,9 +1497,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xC9,
-        name: InstructionCode::RET,
-        length: 1,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.ret();
             0
@@ -2163,13 +1504,10 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xCA,
-        name: InstructionCode::JP,
-        length: 3,
-        cycles: [16, 12],
         operation: |cpu| {
             let value = cpu.get_immediate_16();
             if cpu.flags.zero {
-                cpu.registers.pc = value;
+                cpu.jump(value);
                 0
             } else {
                 1
@@ -2178,9 +1516,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xCB,
-        name: InstructionCode::PREFIX,
-        length: 2,
-        cycles: [4, 4],
         operation: |cpu| {
             let opcode = cpu.get_immediate() as usize;
             (&CB_INSTRUCTION_TABLE[opcode].operation)(cpu)
@@ -2188,23 +1523,18 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xCC,
-        name: InstructionCode::CALL,
-        length: 3,
-        cycles: [24, 12],
-        operation: |cpu| match cpu.flags.zero {
-            true => {
-                let address = cpu.get_immediate_16();
+        operation: |cpu| {
+            let address = cpu.get_immediate_16();
+            if cpu.flags.zero {
                 cpu.call(address);
                 0
+            } else {
+                1
             }
-            false => 1,
         },
     },
     Instruction {
         opcode: 0xCD,
-        name: InstructionCode::CALL,
-        length: 3,
-        cycles: [24, 24],
         operation: |cpu| {
             let address = cpu.get_immediate_16();
             cpu.call(address);
@@ -2213,9 +1543,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xCE,
-        name: InstructionCode::ADC,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             let value = cpu.get_immediate();
             cpu.adc(value);
@@ -2224,9 +1551,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xCF,
-        name: InstructionCode::RST,
-        length: 1,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.rst(0x08);
             0
@@ -2234,10 +1558,9 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xD0,
-        name: InstructionCode::RET,
-        length: 1,
-        cycles: [20, 8],
         operation: |cpu| {
+            // The condition is checked during an internal M-cycle
+            cpu.tick();
             if !cpu.flags.carry {
                 cpu.ret();
                 0
@@ -2248,9 +1571,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xD1,
-        name: InstructionCode::POP,
-        length: 1,
-        cycles: [12, 12],
         operation: |cpu| {
             let value = cpu.pop();
             cpu.registers.set_de(value);
@@ -2259,13 +1579,10 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xD2,
-        name: InstructionCode::JP,
-        length: 3,
-        cycles: [16, 12],
         operation: |cpu| {
             let value = cpu.get_immediate_16();
             if !cpu.flags.carry {
-                cpu.registers.pc = value;
+                cpu.jump(value);
                 0
             } else {
                 1
@@ -2274,30 +1591,25 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xD3,
-        name: InstructionCode::ILLEGAL_D3,
-        length: 1,
-        cycles: [4, 4],
-        operation: |_| panic!("Illegal instruction D3"),
+        operation: |cpu| {
+            cpu.lock_up(0xD3);
+            0
+        },
     },
     Instruction {
         opcode: 0xD4,
-        name: InstructionCode::CALL,
-        length: 3,
-        cycles: [24, 12],
-        operation: |cpu| match cpu.flags.carry {
-            false => {
-                let address = cpu.get_immediate_16();
+        operation: |cpu| {
+            let address = cpu.get_immediate_16();
+            if !cpu.flags.carry {
                 cpu.call(address);
                 0
+            } else {
+                1
             }
-            true => 1,
         },
     },
     Instruction {
         opcode: 0xD5,
-        name: InstructionCode::PUSH,
-        length: 1,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.push(cpu.registers.get_de());
             0
@@ -2305,9 +1617,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xD6,
-        name: InstructionCode::SUB,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             let value = cpu.get_immediate();
             cpu.sub(value);
@@ -2316,9 +1625,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xD7,
-        name: InstructionCode::RST,
-        length: 1,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.rst(0x10);
             0
@@ -2326,11 +1632,10 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xD8,
-        name: InstructionCode::RET,
-        length: 1,
-        cycles: [20, 8],
         operation: |cpu| {
-            if !cpu.flags.carry {
+            // The condition is checked during an internal M-cycle
+            cpu.tick();
+            if cpu.flags.carry {
                 cpu.ret();
                 0
             } else {
@@ -2340,9 +1645,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xD9,
-        name: InstructionCode::RETI,
-        length: 1,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.reti();
             0
@@ -2350,13 +1652,10 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xDA,
-        name: InstructionCode::JP,
-        length: 3,
-        cycles: [16, 12],
         operation: |cpu| {
             let value = cpu.get_immediate_16();
             if cpu.flags.carry {
-                cpu.registers.pc = value;
+                cpu.jump(value);
                 0
             } else {
                 1
@@ -2365,37 +1664,32 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xDB,
-        name: InstructionCode::ILLEGAL_DB,
-        length: 1,
-        cycles: [4, 4],
-        operation: |_| panic!("Illegal instruction DB"),
+        operation: |cpu| {
+            cpu.lock_up(0xDB);
+            0
+        },
     },
     Instruction {
         opcode: 0xDC,
-        name: InstructionCode::CALL,
-        length: 3,
-        cycles: [24, 12],
-        operation: |cpu| match cpu.flags.carry {
-            true => {
-                let address = cpu.get_immediate_16();
+        operation: |cpu| {
+            let address = cpu.get_immediate_16();
+            if cpu.flags.carry {
                 cpu.call(address);
                 0
+            } else {
+                1
             }
-            false => 1,
         },
     },
     Instruction {
         opcode: 0xDD,
-        name: InstructionCode::ILLEGAL_DD,
-        length: 1,
-        cycles: [4, 4],
-        operation: |_| panic!("Illegal instruction DD"),
+        operation: |cpu| {
+            cpu.lock_up(0xDD);
+            0
+        },
     },
     Instruction {
         opcode: 0xDE,
-        name: InstructionCode::SBC,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             let value = cpu.get_immediate();
             cpu.sbc(value);
@@ -2404,9 +1698,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xDF,
-        name: InstructionCode::RST,
-        length: 1,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.rst(0x18);
             0
@@ -2414,9 +1705,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xE0,
-        name: InstructionCode::LDH,
-        length: 2,
-        cycles: [12, 12],
         operation: |cpu| {
             let address = 0xff00 | cpu.get_immediate() as u16;
             cpu.set_value_at(address, cpu.registers.a);
@@ -2425,9 +1713,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xE1,
-        name: InstructionCode::POP,
-        length: 1,
-        cycles: [12, 12],
         operation: |cpu| {
             let value = cpu.pop();
             cpu.registers.set_hl(value);
@@ -2436,9 +1721,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xE2,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
             let address = 0xff00 | cpu.registers.c as u16;
             cpu.set_value_at(address, cpu.registers.a);
@@ -2447,23 +1729,20 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xE3,
-        name: InstructionCode::ILLEGAL_E3,
-        length: 1,
-        cycles: [4, 4],
-        operation: |_| panic!("Illegal instruction E3"),
+        operation: |cpu| {
+            cpu.lock_up(0xE3);
+            0
+        },
     },
     Instruction {
         opcode: 0xE4,
-        name: InstructionCode::ILLEGAL_E4,
-        length: 1,
-        cycles: [4, 4],
-        operation: |_| panic!("Illegal instruction E4"),
+        operation: |cpu| {
+            cpu.lock_up(0xE4);
+            0
+        },
     },
     Instruction {
         opcode: 0xE5,
-        name: InstructionCode::PUSH,
-        length: 1,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.push(cpu.registers.get_hl());
             0
@@ -2471,9 +1750,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xE6,
-        name: InstructionCode::AND,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             let value = cpu.get_immediate();
             cpu.and(value);
@@ -2482,9 +1758,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xE7,
-        name: InstructionCode::RST,
-        length: 1,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.rst(0x20);
             0
@@ -2492,9 +1765,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xE8,
-        name: InstructionCode::ADD,
-        length: 2,
-        cycles: [16, 16],
         operation: |cpu| {
             let value = cpu.get_immediate();
             cpu.add_to_sp(value);
@@ -2503,20 +1773,13 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xE9,
-        name: InstructionCode::JP,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
-            let address = cpu.registers.get_hl();
-            cpu.registers.pc = cpu.get_value_16_at(address);
+            cpu.registers.pc = cpu.registers.get_hl();
             0
         },
     },
     Instruction {
         opcode: 0xEA,
-        name: InstructionCode::LD,
-        length: 3,
-        cycles: [16, 16],
         operation: |cpu| {
             let address = cpu.get_immediate_16();
             cpu.set_value_at(address, cpu.registers.a);
@@ -2525,30 +1788,27 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xEB,
-        name: InstructionCode::ILLEGAL_EB,
-        length: 1,
-        cycles: [4, 4],
-        operation: |_| panic!("Illegal instruction EB"),
+        operation: |cpu| {
+            cpu.lock_up(0xEB);
+            0
+        },
     },
     Instruction {
         opcode: 0xEC,
-        name: InstructionCode::ILLEGAL_EC,
-        length: 1,
-        cycles: [4, 4],
-        operation: |_| panic!("Illegal instruction EC"),
+        operation: |cpu| {
+            cpu.lock_up(0xEC);
+            0
+        },
     },
     Instruction {
         opcode: 0xED,
-        name: InstructionCode::ILLEGAL_ED,
-        length: 1,
-        cycles: [4, 4],
-        operation: |_| panic!("Illegal instruction ED"),
+        operation: |cpu| {
+            cpu.lock_up(0xED);
+            0
+        },
     },
     Instruction {
         opcode: 0xEE,
-        name: InstructionCode::XOR,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             let value = cpu.get_immediate();
             cpu.xor(value);
@@ -2557,9 +1817,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xEF,
-        name: InstructionCode::RST,
-        length: 1,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.rst(0x28);
             0
@@ -2567,9 +1824,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xF0,
-        name: InstructionCode::LDH,
-        length: 2,
-        cycles: [12, 12],
         operation: |cpu| {
             let address = 0xff00 | cpu.get_immediate() as u16;
             cpu.registers.a = cpu.get_value_at(address);
@@ -2578,9 +1832,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xF1,
-        name: InstructionCode::POP,
-        length: 1,
-        cycles: [12, 12],
         operation: |cpu| {
             let value = cpu.pop();
             cpu.set_af(value);
@@ -2589,9 +1840,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xF2,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
             let address = 0xff00 | cpu.registers.c as u16;
             cpu.registers.a = cpu.get_value_at(address);
@@ -2600,9 +1848,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xF3,
-        name: InstructionCode::DI,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.di();
             0
@@ -2610,16 +1855,13 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xF4,
-        name: InstructionCode::ILLEGAL_F4,
-        length: 1,
-        cycles: [4, 4],
-        operation: |_| panic!("Illegal instruction F4"),
+        operation: |cpu| {
+            cpu.lock_up(0xF4);
+            0
+        },
     },
     Instruction {
         opcode: 0xF5,
-        name: InstructionCode::PUSH,
-        length: 1,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.push(cpu.get_af());
             0
@@ -2627,9 +1869,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xF6,
-        name: InstructionCode::OR,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             let value = cpu.get_immediate();
             cpu.or(value);
@@ -2638,9 +1877,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xF7,
-        name: InstructionCode::RST,
-        length: 1,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.rst(0x30);
             0
@@ -2648,15 +1884,13 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xF8,
-        name: InstructionCode::LD,
-        length: 2,
-        cycles: [12, 12],
         operation: |cpu| {
             cpu.flags.clear_flags();
             let value = cpu.get_immediate() as u16;
 
             cpu.flags.set_half_carry_16(value, cpu.registers.sp);
 
+            cpu.tick();
             let (value, carry) = cpu.registers.sp.overflowing_add(value);
             cpu.registers.set_hl(value);
             cpu.flags.carry = carry;
@@ -2665,19 +1899,14 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xF9,
-        name: InstructionCode::LD,
-        length: 1,
-        cycles: [8, 8],
         operation: |cpu| {
+            cpu.tick();
             cpu.registers.sp = cpu.registers.get_hl();
             0
         },
     },
     Instruction {
         opcode: 0xFA,
-        name: InstructionCode::LD,
-        length: 3,
-        cycles: [16, 16],
         operation: |cpu| {
             let address = cpu.get_immediate_16();
             cpu.registers.a = cpu.get_value_at(address);
@@ -2686,9 +1915,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xFB,
-        name: InstructionCode::EI,
-        length: 1,
-        cycles: [4, 4],
         operation: |cpu| {
             cpu.ei();
             0
@@ -2696,23 +1922,20 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xFC,
-        name: InstructionCode::ILLEGAL_FC,
-        length: 1,
-        cycles: [4, 4],
-        operation: |_| panic!("Illegal instruction FC"),
+        operation: |cpu| {
+            cpu.lock_up(0xFC);
+            0
+        },
     },
     Instruction {
         opcode: 0xFD,
-        name: InstructionCode::ILLEGAL_FD,
-        length: 1,
-        cycles: [4, 4],
-        operation: |_| panic!("Illegal instruction FD"),
+        operation: |cpu| {
+            cpu.lock_up(0xFD);
+            0
+        },
     },
     Instruction {
         opcode: 0xFE,
-        name: InstructionCode::CP,
-        length: 2,
-        cycles: [8, 8],
         operation: |cpu| {
             let value = cpu.get_immediate();
             cpu.cp(value);
@@ -2721,9 +1944,6 @@ pub const INSTRUCTION_TABLE: [Instruction; 0x100] = [
     },
     Instruction {
         opcode: 0xFF,
-        name: InstructionCode::RST,
-        length: 1,
-        cycles: [16, 16],
         operation: |cpu| {
             cpu.rst(0x38);
             0
@@ -0,0 +1,60 @@
+//! Description of every opcode, generated at build time from ressources/instruction_set.json
+
+use std::fmt;
+
+/// How an instruction changes a flag
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum FlagEffect {
+    Unaffected,
+    Reset,
+    Set,
+    /// Depends on the result
+    Affected,
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct FlagEffects {
+    pub zero: FlagEffect,
+    pub negative: FlagEffect,
+    pub half_carry: FlagEffect,
+    pub carry: FlagEffect,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct Operand {
+    /// Register, condition, RST vector or kind of immediate value (d8, d16, a8, a16, r8)
+    pub name: &'static str,
+    /// False when the operand is dereferenced, e.g. (HL)
+    pub immediate: bool,
+    /// Size of the immediate value following the opcode, 0 if none
+    pub bytes: u8,
+    /// HL+
+    pub increment: bool,
+    /// HL-
+    pub decrement: bool,
+}
+
+pub struct InstructionMetadata {
+    pub opcode: u8,
+    pub mnemonic: InstructionCode,
+    /// In bytes, opcode included
+    pub length: u16,
+    /// Clock cycles, when branching and when not for conditional instructions
+    pub cycles: [u32; 2],
+    pub operands: &'static [Operand],
+    pub flags: FlagEffects,
+}
+
+include!(concat!(env!("OUT_DIR"), "/instruction_metadata.rs"));
+
+impl fmt::Display for InstructionCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl fmt::Display for InstructionMetadata {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.mnemonic)
+    }
+}
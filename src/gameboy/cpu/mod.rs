@@ -1,21 +1,25 @@
-mod cb_instructions;
+pub mod cb_instructions;
 pub mod flags;
-mod instructions;
-mod registers;
-
-use crate::gameboy::bus::Bus;
+pub mod instructions;
+pub mod metadata;
+pub mod registers;
+
+use crate::gameboy::bus::{
+    Bus, MemoryBus, INTERRUPT_JOYPAD, INTERRUPT_SERIAL, INTERRUPT_STAT, INTERRUPT_TIMER,
+    INTERRUPT_VBLANK,
+};
 use cb_instructions::CB_INSTRUCTION_TABLE;
 use flags::Flags;
 use instructions::INSTRUCTION_TABLE;
 use registers::Registers;
-use std::cell::RefCell;
+use std::collections::VecDeque;
 use std::fmt;
-use std::rc::Rc;
 
-use self::instructions::InstructionCode;
 use self::registers::{RegisterNames, RegisterNames16b};
 
 use super::cartridge::Cartridge;
+use super::disassembler::{self, Symbols};
+use super::trace::Tracer;
 
 const INTERRUPT_ADDRESS_VBLANK: u16 = 0x0040;
 const INTERRUPT_ADDRESS_STAT: u16 = 0x0048;
@@ -23,46 +27,139 @@ const INTERRUPT_ADDRESS_TIMER: u16 = 0x0050;
 const INTERRUPT_ADDRESS_SERIAL: u16 = 0x0058;
 const INTERRUPT_ADDRESS_JOYPAD: u16 = 0x0060;
 
-pub struct Cpu<'a> {
+/// Number of instructions kept to report where a fault comes from
+const TRACE_LENGTH: usize = 16;
+
+/// Deepest call stack tracked, older frames are forgotten
+const CALL_STACK_LENGTH: usize = 64;
+
+/// Reported when the CPU locks up after executing an illegal opcode
+#[derive(Debug, Clone)]
+pub struct CpuFault {
+    pub pc: u16,
+    pub opcode: u8,
+    /// Addresses of the last instructions executed, oldest first, the faulty one included
+    pub trace: Vec<u16>,
+}
+
+impl fmt::Display for CpuFault {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "CPU locked up by illegal opcode {:02x} at {:04x}, trace:",
+            self.opcode, self.pc
+        )?;
+        for pc in &self.trace {
+            write!(f, " {:04x}", pc)?;
+        }
+        Ok(())
+    }
+}
+
+/// Subroutine entered with CALL, RST or an interrupt dispatch, used to build backtraces
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CallFrame {
+    /// Address of the subroutine
+    pub target: u16,
+    /// Pushed on the stack, where RET goes back to
+    pub return_address: u16,
+    /// SP once the return address is pushed, the frame is left when SP gets above
+    pub sp: u16,
+    pub is_interrupt: bool,
+}
+
+/// The bus is the last field so that Cpu<B> can be used as Cpu<dyn MemoryBus>
+/// by the instruction tables.
+pub struct Cpu<B: MemoryBus + ?Sized> {
     pub registers: Registers,
     pub flags: Flags,
-    pub bus: Bus<'a>,
-    remaining_cycles: u32,
-    total_cycles: u32,
+    /// Clock cycles elapsed since the beginning of the current instruction
+    instruction_cycles: u32,
+    total_cycles: u64,
     interruption_enabled: bool,
     future_interruption_enabled: bool,
     switch_interruption_enabled_in: u8,
     pub is_halted: bool,
+    /// Low power mode entered with STOP, left when a button is pressed
+    pub is_stopped: bool,
+    /// HALT executed with a pending interrupt while IME is off: the next opcode is read twice
+    halt_bug: bool,
+    /// Set by illegal opcodes, nothing is fetched anymore and interrupts are ignored
+    pub is_locked: bool,
+    fault: Option<CpuFault>,
+    trace: VecDeque<u16>,
+    /// Subroutines being executed, innermost last, tracked from CALL/RST/interrupts and RET
+    pub call_stack: Vec<CallFrame>,
+    pub tracer: Option<Tracer>,
+    /// Set when LD B,B is executed, used as a software breakpoint by test ROMs
+    pub breakpoint_hit: bool,
+    pub bus: B,
 }
 
-impl fmt::Display for Cpu<'_> {
+impl<B: MemoryBus + ?Sized> fmt::Display for Cpu<B> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "Cycles:{},{}", self.total_cycles, self.registers)
     }
 }
 
-impl Cpu<'_> {
-    pub fn new(sdl_context: Rc<RefCell<sdl2::Sdl>>) -> Cpu<'static> {
+impl Cpu<Bus> {
+    pub fn start(&mut self, cartridge: Cartridge) {
+        self.bus.load_cartridge(cartridge);
+    }
+
+    /// Registers and I/O as left by the CGB boot ROM for a CGB cartridge, A = 0x11 tells
+    /// the cartridge it runs on a CGB
+    pub fn skip_cgb_boot_rom(&mut self) {
+        self.bus.skip_cgb_boot_rom();
+        self.registers.a = 0x11;
+        self.flags.set_flags(0x80);
+        self.registers.set_bc(0x0000);
+        self.registers.set_de(0xff56);
+        self.registers.set_hl(0x000d);
+        self.registers.sp = 0xfffe;
+        self.registers.pc = 0x0100;
+    }
+}
+
+impl<B: MemoryBus + 'static> Cpu<B> {
+    pub fn new(bus: B) -> Cpu<B> {
         Cpu {
             registers: Registers::default(),
             flags: Flags::default(),
-            bus: Bus::new(sdl_context),
-            remaining_cycles: 0,
+            instruction_cycles: 0,
             total_cycles: 0,
             interruption_enabled: true,
             future_interruption_enabled: true,
             switch_interruption_enabled_in: 0,
             is_halted: false,
+            is_stopped: false,
+            halt_bug: false,
+            is_locked: false,
+            fault: None,
+            trace: VecDeque::with_capacity(TRACE_LENGTH),
+            call_stack: Vec::new(),
+            tracer: None,
+            breakpoint_hit: false,
+            bus,
         }
     }
 
-    pub fn start(&mut self, cartridge: Cartridge) {
-        self.bus.load_cartridge(cartridge);
-    }
-
-    pub fn next(&mut self) {
-        if self.registers.pc > 0x1000 {
-            panic!("Out of boot rom : {:04x}", self.registers.pc);
+    /// Run the CPU for one instruction, one interrupt dispatch or one M-cycle when halted
+    /// or stopped. The bus is ticked along the way, returns the number of clock cycles
+    /// elapsed.
+    pub fn next(&mut self) -> u32 {
+        if self.is_stopped {
+            return self.poll_joypad();
+        }
+        if self.is_halted || self.is_locked || self.bus.is_cpu_halted() {
+            self.instruction_cycles = 0;
+            self.tick();
+            self.total_cycles += self.instruction_cycles as u64;
+            // HALT ends with any enabled interrupt, even when IME is off
+            if self.is_halted && self.bus.get_pending_interrupts() != 0 {
+                self.is_halted = false;
+            }
+            return self.instruction_cycles;
         }
 
         if self.switch_interruption_enabled_in > 0 {
@@ -73,82 +170,153 @@ impl Cpu<'_> {
         }
 
         if self.interruption_enabled {
+            self.instruction_cycles = 0;
             if self.check_interrupt() {
-                return;
+                self.total_cycles += self.instruction_cycles as u64;
+                return self.instruction_cycles;
             }
         }
 
-        if self.remaining_cycles > 0 {
-            self.remaining_cycles -= 1;
-            return;
+        self.execute()
+    }
+
+    /// Fetch and execute the instruction at PC, returns the number of clock cycles it takes.
+    ///
+    /// The bus is ticked before each memory access and on each internal M-cycle, in the
+    /// order they happen during the instruction.
+    pub fn execute(&mut self) -> u32 {
+        self.instruction_cycles = 0;
+        if self.trace.len() == TRACE_LENGTH {
+            self.trace.pop_front();
+        }
+        self.trace.push_back(self.registers.pc);
+        if let Some(mut tracer) = self.tracer.take() {
+            tracer.trace(self);
+            self.tracer = Some(tracer);
+        }
+        let opcode = self.get_immediate();
+        if self.halt_bug {
+            self.registers.pc = self.registers.pc.wrapping_sub(1);
+            self.halt_bug = false;
         }
 
-        let opcode = self.get_immediate() as usize;
+        // The prefix only fetches the second byte, timing is given by the CB table
+        let operation = if opcode == 0xcb {
+            CB_INSTRUCTION_TABLE[self.get_immediate() as usize].operation
+        } else {
+            INSTRUCTION_TABLE[opcode as usize].operation
+        };
+        operation(self);
+        self.total_cycles += self.instruction_cycles as u64;
+        self.instruction_cycles
+    }
+}
 
-        let instruction_result = (&INSTRUCTION_TABLE[opcode].operation)(self);
+impl<B: MemoryBus + ?Sized> Cpu<B> {
+    pub fn is_interrupt_master_enabled(&self) -> bool {
+        self.interruption_enabled
+    }
+
+    /// Clock cycles elapsed since power on
+    pub fn get_total_cycles(&self) -> u64 {
+        self.total_cycles
+    }
+
+    /// Fault that locked the CPU up, returned only once
+    pub fn take_fault(&mut self) -> Option<CpuFault> {
+        self.fault.take()
+    }
 
-        self.remaining_cycles = INSTRUCTION_TABLE[opcode].cycles[instruction_result];
-        self.total_cycles += self.remaining_cycles;
-        self.remaining_cycles -= 1; // Do not count current cycle twice
+    pub fn set_interrupt_master_enabled(&mut self, enabled: bool) {
+        self.interruption_enabled = enabled;
+        self.future_interruption_enabled = enabled;
+        self.switch_interruption_enabled_in = 0;
     }
 
+    /// Interrupt dispatch takes 5 M-cycles: 1 idle, 3 to push PC and 1 to jump
     fn check_interrupt(&mut self) -> bool {
-        if self.bus.is_vblank_interrup_enabled() && self.bus.ppu.is_vblank_interrupted() {
-            self.call(INTERRUPT_ADDRESS_VBLANK);
-            true
-        } else if self.bus.is_stat_interrup_enabled() && self.bus.ppu.is_stat_interrupted() {
-            self.call(INTERRUPT_ADDRESS_STAT);
-            true
-        } else if self.bus.is_time_interrup_enabled() && self.bus.timer.is_interrupted() {
-            self.call(INTERRUPT_ADDRESS_TIMER);
-            true
-        } else if self.bus.is_serial_interrup_enabled() && self.bus.controller.is_interrupted() {
-            self.call(INTERRUPT_ADDRESS_SERIAL);
-            true
-        } else if self.bus.is_joypad_interrup_enabled() && self.bus.controller.is_interrupted() {
-            self.call(INTERRUPT_ADDRESS_JOYPAD);
-            true
+        let pending = self.bus.get_pending_interrupts();
+        let (interrupt, address) = if pending & INTERRUPT_VBLANK != 0 {
+            (INTERRUPT_VBLANK, INTERRUPT_ADDRESS_VBLANK)
+        } else if pending & INTERRUPT_STAT != 0 {
+            (INTERRUPT_STAT, INTERRUPT_ADDRESS_STAT)
+        } else if pending & INTERRUPT_TIMER != 0 {
+            (INTERRUPT_TIMER, INTERRUPT_ADDRESS_TIMER)
+        } else if pending & INTERRUPT_SERIAL != 0 {
+            (INTERRUPT_SERIAL, INTERRUPT_ADDRESS_SERIAL)
+        } else if pending & INTERRUPT_JOYPAD != 0 {
+            (INTERRUPT_JOYPAD, INTERRUPT_ADDRESS_JOYPAD)
         } else {
-            false
+            return false;
+        };
+        self.bus.acknowledge_interrupt(interrupt);
+        self.tick();
+        self.push(self.registers.pc);
+        self.tick();
+        self.enter_subroutine(address, true);
+        true
+    }
+
+    /// Advance everything but the CPU by one M-cycle (4 clock cycles)
+    fn tick(&mut self) {
+        for _ in 0..4 {
+            self.bus.tick();
         }
+        self.instruction_cycles += 4;
+    }
+
+    /// Every memory access takes one M-cycle
+    fn read(&mut self, address: u16) -> u8 {
+        self.tick();
+        self.bus.read_8(address)
+    }
+
+    fn write(&mut self, address: u16, value: u8) {
+        self.tick();
+        self.bus.write_8(address, value);
     }
 
     /// Get 8 bit immediate value on PC and increment PC of 1
     fn get_immediate(&mut self) -> u8 {
-        let value = self.bus.read_8(self.registers.pc);
+        let value = self.read(self.registers.pc);
         self.registers.pc += 1;
         value
     }
 
     /// Get 16 bit immediate value on PC and increment PC of 2
     fn get_immediate_16(&mut self) -> u16 {
-        let value = self.bus.read_16(self.registers.pc);
+        let value = self.get_value_16_at(self.registers.pc);
         self.registers.pc += 2;
         value
     }
 
     fn get_value_at(&mut self, address: u16) -> u8 {
-        self.bus.read_8(address)
+        self.read(address)
     }
 
+    /// Little endian, low byte is read first
     fn get_value_16_at(&mut self, address: u16) -> u16 {
-        self.bus.read_16(address)
+        let low = self.read(address) as u16;
+        let high = self.read(address + 1) as u16;
+        high << 8 | low
     }
 
     fn get_value_at_hl(&mut self) -> u8 {
-        self.bus.read_8(self.registers.get_hl())
+        self.read(self.registers.get_hl())
     }
 
     fn set_value_at(&mut self, address: u16, value: u8) {
-        self.bus.write_8(address, value);
+        self.write(address, value);
     }
 
     fn set_value_at_hl(&mut self, value: u8) {
-        self.bus.write_8(self.registers.get_hl(), value);
+        self.write(self.registers.get_hl(), value);
     }
 
+    /// Little endian, low byte is written first
     fn set_value_16_at(&mut self, address: u16, value: u16) {
-        self.bus.write_16(address, value);
+        self.write(address, (value & 0xff) as u8);
+        self.write(address + 1, (value >> 8) as u8);
     }
 
     fn get_af(&self) -> u16 {
@@ -160,13 +328,41 @@ impl Cpu<'_> {
         self.flags.set_flags((value & 0xff) as u8);
     }
 
+    /// PC is already past the operand, it is the return address
     fn call(&mut self, address: u16) {
-        self.push(self.registers.pc + 3);
-        self.registers.pc = address;
+        self.push(self.registers.pc);
+        self.enter_subroutine(address, false);
     }
 
+    /// Loading PC takes an internal M-cycle after the address is popped
     fn ret(&mut self) {
         let address = self.pop();
+        self.jump(address);
+        // Frames whose return address is now below SP are left, even if the stack was
+        // unwound by hand before
+        let sp = self.registers.sp;
+        while self.call_stack.last().is_some_and(|frame| frame.sp < sp) {
+            self.call_stack.pop();
+        }
+    }
+
+    /// Loading PC with the address of a JP or JR takes an internal M-cycle
+    fn jump(&mut self, address: u16) {
+        self.tick();
+        self.registers.pc = address;
+    }
+
+    /// Jump to a subroutine whose return address has just been pushed
+    fn enter_subroutine(&mut self, address: u16, is_interrupt: bool) {
+        if self.call_stack.len() == CALL_STACK_LENGTH {
+            self.call_stack.remove(0);
+        }
+        self.call_stack.push(CallFrame {
+            target: address,
+            return_address: self.registers.pc,
+            sp: self.registers.sp,
+            is_interrupt,
+        });
         self.registers.pc = address;
     }
 
@@ -208,6 +404,7 @@ impl Cpu<'_> {
             RegisterNames16b::HL => self.registers.get_hl(),
             RegisterNames16b::SP => self.registers.sp,
         };
+        self.tick();
         self.flags.negative = false;
         let old_value = self.registers.get_hl();
         self.flags.set_half_carry_16(old_value, value);
@@ -215,7 +412,10 @@ impl Cpu<'_> {
         self.registers.set_hl(value);
     }
 
+    /// The ALU works on 8 bits, the addition takes 2 internal M-cycles
     fn add_to_sp(&mut self, value: u8) {
+        self.tick();
+        self.tick();
         self.flags.clear_flags();
         let old_value = self.registers.sp;
         (self.registers.sp, self.flags.carry) = self.registers.sp.overflowing_add(value as u16);
@@ -252,17 +452,23 @@ impl Cpu<'_> {
         // TODO : handle half carry with sub and sbc
     }
 
+    /// SP is decremented during an internal M-cycle, then the high byte is written
+    /// below it and the low byte below the high byte
     fn push(&mut self, value: u16) {
-        self.set_value_16_at(self.registers.sp, value);
+        self.tick();
         self.registers.dec_sp();
+        self.write(self.registers.sp, (value >> 8) as u8);
         self.registers.dec_sp();
+        self.write(self.registers.sp, (value & 0xff) as u8);
     }
 
+    /// SP points to the low byte, the high byte is above it
     fn pop(&mut self) -> u16 {
+        let low = self.read(self.registers.sp) as u16;
         self.registers.inc_sp();
+        let high = self.read(self.registers.sp) as u16;
         self.registers.inc_sp();
-        let value = self.get_value_16_at(self.registers.sp);
-        value
+        high << 8 | low
     }
 
     /// Rotates arg1 to the left with bit 7 being moved to bit 0 and also stored into the carry
@@ -679,21 +885,20 @@ impl Cpu<'_> {
         self.flags.set_zero(new_value);
     }
 
-    /// ei activate interrupt, but only in 3 cycles
+    /// ei activate interrupt, but only after the next instruction
     fn ei(&mut self) {
         self.future_interruption_enabled = true;
-        self.switch_interruption_enabled_in = 3;
+        self.switch_interruption_enabled_in = 2;
     }
 
-    /// di deactivate interrupt, but only in 3 cycles
+    /// di deactivate interrupt immediately
     fn di(&mut self) {
-        self.future_interruption_enabled = true;
-        self.switch_interruption_enabled_in = 3;
+        self.set_interrupt_master_enabled(false);
     }
 
     fn rst(&mut self, n: u16) {
         self.push(self.registers.pc);
-        self.registers.pc = n;
+        self.enter_subroutine(n, false);
     }
 
     fn cpl(&mut self) {
@@ -703,56 +908,85 @@ impl Cpu<'_> {
         self.flags.half_carry = true;
     }
 
-    fn halt(&mut self) {
-        self.is_halted = true;
+    /// Adjust A to a valid BCD number after an addition or a subtraction of BCD numbers
+    fn daa(&mut self) {
+        let mut value = self.registers.a;
+        if self.flags.negative {
+            if self.flags.carry {
+                value = value.wrapping_sub(0x60);
+            }
+            if self.flags.half_carry {
+                value = value.wrapping_sub(0x06);
+            }
+        } else {
+            if self.flags.carry || value > 0x99 {
+                value = value.wrapping_add(0x60);
+                self.flags.carry = true;
+            }
+            if self.flags.half_carry || value & 0x0f > 0x09 {
+                value = value.wrapping_add(0x06);
+            }
+        }
+        self.registers.a = value;
+        self.flags.set_zero(value);
+        self.flags.half_carry = false;
     }
 
-    pub fn print_status(&mut self) {
-        let opcode = self.bus.read_8(self.registers.pc) as usize;
+    /// If an interrupt is already pending while IME is off, the CPU does not halt
+    /// and fails to increment PC after the next opcode fetch (HALT bug)
+    fn halt(&mut self) {
+        if !self.interruption_enabled && self.bus.get_pending_interrupts() != 0 {
+            self.halt_bug = true;
+        } else {
+            self.is_halted = true;
+        }
+    }
 
-        let mut instruction = &INSTRUCTION_TABLE[opcode];
-        if instruction.name == InstructionCode::PREFIX {
-            let opcode = self.bus.read_8(self.registers.pc + 1) as usize;
+    /// Illegal opcodes hang the CPU until the Game Boy is turned off
+    fn lock_up(&mut self, opcode: u8) {
+        self.is_locked = true;
+        self.fault = Some(CpuFault {
+            pc: self.registers.pc.wrapping_sub(1),
+            opcode,
+            trace: self.trace.iter().copied().collect(),
+        });
+    }
 
-            instruction = &CB_INSTRUCTION_TABLE[opcode];
+    /// STOP resets DIV and performs the CGB speed switch when armed in KEY1,
+    /// otherwise enters low power mode. The byte following STOP is skipped.
+    fn stop(&mut self) {
+        self.registers.pc = self.registers.pc.wrapping_add(1);
+        self.bus.reset_divider();
+        if !self.bus.switch_speed() {
+            self.is_stopped = true;
         }
+    }
 
-        if instruction.length == 1 {
-            println!(
-                "{:04x}  {:02x}        {:indent$}    {}   Flags:{:08b}",
-                self.registers.pc,
-                opcode,
-                instruction,
-                self,
-                self.flags.get_flags(),
-                indent = 30
-            );
-        } else if instruction.length == 2 {
-            let imm1 = self.get_value_at(self.registers.pc + 1);
-            println!(
-                "{:04x}  {:02x} {:02x}     {:indent$}    {}   Flags:{:08b}",
-                self.registers.pc,
-                opcode,
-                imm1,
-                instruction,
-                self,
-                self.flags.get_flags(),
-                indent = 30
-            );
-        } else if instruction.length == 3 {
-            let imm1 = self.get_value_at(self.registers.pc + 1);
-            let imm2 = self.get_value_at(self.registers.pc + 2);
-            println!(
-                "{:04x}  {:02x} {:02x} {:02x}  {:indent$}    {}   Flags:{:08b}",
-                self.registers.pc,
-                opcode,
-                imm1,
-                imm2,
-                instruction,
-                self,
-                self.flags.get_flags(),
-                indent = 30
-            );
+    /// In low power mode the system clock is halted: the bus isn't ticked, so the timer
+    /// and the LCD stand still until a joypad line goes low. IF is polled without a bus
+    /// read, watchpoints only see the program.
+    fn poll_joypad(&mut self) -> u32 {
+        if self.bus.peek(0xff0f) & INTERRUPT_JOYPAD != 0 {
+            self.is_stopped = false;
         }
+        self.instruction_cycles = 4;
+        self.total_cycles += self.instruction_cycles as u64;
+        self.instruction_cycles
+    }
+
+    /// Memory is peeked, printing the status does not change the emulation
+    pub fn print_status(&self) {
+        let bus = &self.bus;
+        let instruction =
+            disassembler::disassemble(self.registers.pc, 0, &Symbols::default(), |address| {
+                bus.peek(address)
+            });
+        println!(
+            "{:indent$}    {}   Flags:{:08b}",
+            instruction.to_string(),
+            self,
+            self.flags.get_flags(),
+            indent = 40
+        );
     }
 }
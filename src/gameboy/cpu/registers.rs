@@ -102,10 +102,10 @@ impl Registers {
     }
 
     pub fn inc_sp(&mut self) {
-        self.sp += 1;
+        self.sp = self.sp.wrapping_add(1);
     }
 
     pub fn dec_sp(&mut self) {
-        self.sp -= 1;
+        self.sp = self.sp.wrapping_sub(1);
     }
 }
@@ -0,0 +1,309 @@
+//! Live hex dump of the 64 KiB map as the CPU sees it, read without side effects.
+//!
+//! Keys: arrows and page up/down move the cursor, tab and shift+tab jump to the next and
+//! previous region, [ and ] select the ROM bank shown at 4000-7fff, - and = the cartridge
+//! RAM bank shown at a000-bfff, M shows the mapped banks again. Typing two hex digits
+//! writes the byte at the cursor in the banks shown, ROM included, escape cancels the first digit.
+//! Registers are set without their side effects, see Bus::poke_banked.
+
+use super::{DebugView, Image, Rgb, CHAR_HEIGHT, CHAR_WIDTH};
+use crate::gameboy::bus::{region_name, Bus, MemoryBanks};
+use sdl2::keyboard::Keycode;
+
+const MEMORY_SIZE: usize = 0x10000;
+
+const ROWS: usize = 32;
+const BYTES_PER_ROW: usize = 16;
+const PAGE_SIZE: i32 = (ROWS * BYTES_PER_ROW) as i32;
+
+/// Character columns of each row: address, region, then 3 characters per byte
+const REGION_COLUMN: usize = 5;
+const BYTES_COLUMN: usize = 10;
+const COLUMNS: usize = BYTES_COLUMN + BYTES_PER_ROW * 3;
+
+/// Banks line and a blank line above the bytes
+const HEADER_ROWS: usize = 2;
+
+pub const WIDTH: usize = COLUMNS * CHAR_WIDTH;
+pub const HEIGHT: usize = (HEADER_ROWS + ROWS) * CHAR_HEIGHT;
+
+const BACKGROUND: Rgb = [0x10, 0x10, 0x18];
+const TEXT: Rgb = [0xe0, 0xe0, 0xe0];
+const DIM: Rgb = [0x80, 0x80, 0x90];
+const HEADER: Rgb = [0xe0, 0xc0, 0x40];
+pub const CHANGED: Rgb = [0xff, 0x50, 0x50];
+pub const CURSOR: Rgb = [0x30, 0x50, 0xa0];
+
+pub struct MemoryViewer {
+    /// Address of the first row shown
+    top: u16,
+    cursor: u16,
+    /// First digit typed at the cursor
+    pending_nibble: Option<u8>,
+    /// Banks shown instead of the mapped ones
+    rom_bank: Option<usize>,
+    ram_bank: Option<usize>,
+    /// Snapshots of the map at the last two updates, empty until the first update
+    current: Vec<u8>,
+    previous: Vec<u8>,
+}
+
+impl Default for MemoryViewer {
+    fn default() -> MemoryViewer {
+        MemoryViewer::new()
+    }
+}
+
+impl MemoryViewer {
+    pub fn new() -> MemoryViewer {
+        MemoryViewer {
+            top: 0,
+            cursor: 0,
+            pending_nibble: None,
+            rom_bank: None,
+            ram_bank: None,
+            current: vec![],
+            previous: vec![],
+        }
+    }
+
+    pub fn get_cursor(&self) -> u16 {
+        self.cursor
+    }
+
+    fn get_rom_bank(&self, bus: &Bus) -> usize {
+        self.rom_bank.unwrap_or(bus.get_banks().rom)
+    }
+
+    fn get_ram_bank(&self, bus: &Bus) -> usize {
+        self.ram_bank.unwrap_or(bus.get_banks().ram)
+    }
+
+    fn get_banks(&self, bus: &Bus) -> MemoryBanks {
+        MemoryBanks {
+            rom: self.get_rom_bank(bus),
+            ram: self.get_ram_bank(bus),
+            ..bus.get_banks()
+        }
+    }
+
+    fn peek(&self, bus: &Bus, address: u16) -> u8 {
+        bus.peek_banked(address, self.get_banks(bus))
+    }
+
+    fn is_changed(&self, address: u16) -> bool {
+        let address = address as usize;
+        match (self.current.get(address), self.previous.get(address)) {
+            (Some(current), Some(previous)) => current != previous,
+            _ => false,
+        }
+    }
+
+    fn move_cursor(&mut self, delta: i32) {
+        self.pending_nibble = None;
+        self.cursor = (self.cursor as i32 + delta).clamp(0, MEMORY_SIZE as i32 - 1) as u16;
+        let row_start = self.cursor & !(BYTES_PER_ROW as u16 - 1);
+        if self.cursor < self.top {
+            self.top = row_start;
+        } else if self.cursor as i32 >= self.top as i32 + PAGE_SIZE {
+            self.top = row_start - (PAGE_SIZE as u16 - BYTES_PER_ROW as u16);
+        }
+    }
+
+    /// The second digit writes the byte
+    fn type_digit(&mut self, digit: u8, bus: &mut Bus) {
+        match self.pending_nibble.take() {
+            None => self.pending_nibble = Some(digit),
+            Some(high) => {
+                let banks = self.get_banks(bus);
+                bus.poke_banked(self.cursor, high << 4 | digit, banks);
+                self.move_cursor(1);
+            }
+        }
+    }
+
+    /// Switchable banks are 1 to count - 1
+    fn select_rom_bank(&mut self, delta: i32, bus: &Bus) {
+        let count = bus.get_rom_bank_count() as i32;
+        if count <= 2 {
+            return;
+        }
+        let bank = self.get_rom_bank(bus) as i32 - 1 + delta;
+        self.rom_bank = Some((bank.rem_euclid(count - 1) + 1) as usize);
+        self.forget_snapshots();
+    }
+
+    fn select_ram_bank(&mut self, delta: i32, bus: &Bus) {
+        let count = bus.get_ram_bank_count() as i32;
+        if count == 0 {
+            return;
+        }
+        let bank = self.get_ram_bank(bus) as i32 + delta;
+        self.ram_bank = Some(bank.rem_euclid(count) as usize);
+        self.forget_snapshots();
+    }
+
+    /// Bytes of another bank are not changes
+    fn forget_snapshots(&mut self) {
+        self.current.clear();
+        self.previous.clear();
+    }
+}
+
+impl DebugView for MemoryViewer {
+    fn get_title(&self) -> &'static str {
+        "Memory"
+    }
+
+    /// Snapshot the map, bytes differing between the last two snapshots are highlighted.
+    /// Called once per frame.
+    fn update(&mut self, bus: &Bus) {
+        std::mem::swap(&mut self.current, &mut self.previous);
+        self.current = (0..MEMORY_SIZE)
+            .map(|address| self.peek(bus, address as u16))
+            .collect();
+        if self.previous.len() != MEMORY_SIZE {
+            self.previous = self.current.clone();
+        }
+    }
+
+    fn render(&self, bus: &Bus) -> Image {
+        let mut image = Image::new(WIDTH, HEIGHT, BACKGROUND);
+
+        let rom_bank_count = bus.get_rom_bank_count();
+        let ram_bank_count = bus.get_ram_bank_count();
+        let mapped = |bank: Option<usize>| if bank.is_none() { " MAPPED" } else { "" };
+        let ram = if ram_bank_count == 0 {
+            String::from("NONE")
+        } else {
+            format!(
+                "{:02X}/{:02X}{}",
+                self.get_ram_bank(bus),
+                ram_bank_count - 1,
+                mapped(self.ram_bank)
+            )
+        };
+        let header = format!(
+            "ROMX {:02X}/{:02X}{}  SRAM {}  {:04X} {}",
+            self.get_rom_bank(bus),
+            rom_bank_count - 1,
+            mapped(self.rom_bank),
+            ram,
+            self.cursor,
+            region_name(self.cursor)
+        );
+        image.draw_text(0, 0, &header, HEADER);
+
+        for row in 0..ROWS {
+            let start = self.top as usize + row * BYTES_PER_ROW;
+            if start >= MEMORY_SIZE {
+                break;
+            }
+            let screen_row = HEADER_ROWS + row;
+            image.draw_text(0, screen_row, &format!("{:04X}", start), DIM);
+            image.draw_text(REGION_COLUMN, screen_row, region_name(start as u16), DIM);
+            for offset in 0..BYTES_PER_ROW {
+                let address = (start + offset) as u16;
+                let column = BYTES_COLUMN + offset * 3;
+                let color = if self.is_changed(address) {
+                    CHANGED
+                } else {
+                    TEXT
+                };
+                let text = match self.pending_nibble {
+                    Some(nibble) if address == self.cursor => format!("{:X}-", nibble),
+                    _ => format!("{:02X}", self.peek(bus, address)),
+                };
+                if address == self.cursor {
+                    image.highlight_text(column, screen_row, 2, CURSOR);
+                }
+                image.draw_text(column, screen_row, &text, color);
+            }
+        }
+        image
+    }
+
+    fn handle_key(&mut self, keycode: Keycode, shift: bool, bus: &mut Bus) {
+        match keycode {
+            Keycode::Up => self.move_cursor(-(BYTES_PER_ROW as i32)),
+            Keycode::Down => self.move_cursor(BYTES_PER_ROW as i32),
+            Keycode::Left => self.move_cursor(-1),
+            Keycode::Right => self.move_cursor(1),
+            Keycode::PageUp => self.move_cursor(-PAGE_SIZE),
+            Keycode::PageDown => self.move_cursor(PAGE_SIZE),
+            Keycode::Tab => {
+                let target = if shift {
+                    previous_region_start(self.cursor)
+                } else {
+                    next_region_start(self.cursor)
+                };
+                self.move_cursor(target as i32 - self.cursor as i32);
+                // The region starts on the first row
+                self.top = self.cursor & !(BYTES_PER_ROW as u16 - 1);
+            }
+            Keycode::LeftBracket => self.select_rom_bank(-1, bus),
+            Keycode::RightBracket => self.select_rom_bank(1, bus),
+            Keycode::Minus => self.select_ram_bank(-1, bus),
+            Keycode::Equals => self.select_ram_bank(1, bus),
+            Keycode::M => {
+                self.rom_bank = None;
+                self.ram_bank = None;
+                self.forget_snapshots();
+            }
+            Keycode::Escape => self.pending_nibble = None,
+            _ => {
+                if let Some(digit) = hex_digit(keycode) {
+                    self.type_digit(digit, bus);
+                }
+            }
+        }
+    }
+}
+
+fn hex_digit(keycode: Keycode) -> Option<u8> {
+    match keycode {
+        Keycode::Num0 | Keycode::Kp0 => Some(0x0),
+        Keycode::Num1 | Keycode::Kp1 => Some(0x1),
+        Keycode::Num2 | Keycode::Kp2 => Some(0x2),
+        Keycode::Num3 | Keycode::Kp3 => Some(0x3),
+        Keycode::Num4 | Keycode::Kp4 => Some(0x4),
+        Keycode::Num5 | Keycode::Kp5 => Some(0x5),
+        Keycode::Num6 | Keycode::Kp6 => Some(0x6),
+        Keycode::Num7 | Keycode::Kp7 => Some(0x7),
+        Keycode::Num8 | Keycode::Kp8 => Some(0x8),
+        Keycode::Num9 | Keycode::Kp9 => Some(0x9),
+        Keycode::A => Some(0xa),
+        Keycode::B => Some(0xb),
+        Keycode::C => Some(0xc),
+        Keycode::D => Some(0xd),
+        Keycode::E => Some(0xe),
+        Keycode::F => Some(0xf),
+        _ => None,
+    }
+}
+
+fn region_start(address: u16) -> u16 {
+    let name = region_name(address);
+    let mut start = address;
+    while start > 0 && region_name(start - 1) == name {
+        start -= 1;
+    }
+    start
+}
+
+fn next_region_start(address: u16) -> u16 {
+    let name = region_name(address);
+    (address..=0xffff)
+        .find(|address| region_name(*address) != name)
+        .unwrap_or(address)
+}
+
+/// Start of the current region, or of the previous one when already at the start
+fn previous_region_start(address: u16) -> u16 {
+    let start = region_start(address);
+    if start < address || start == 0 {
+        start
+    } else {
+        region_start(start - 1)
+    }
+}
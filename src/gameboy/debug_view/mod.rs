@@ -0,0 +1,340 @@
+//! Debug windows showing the emulator internals, separate from the game Screen.
+//! Views render to an Image so that they can also be used without a window.
+
+pub mod memory;
+pub mod oam;
+pub mod tile_map;
+pub mod tiles;
+
+use crate::gameboy::bus::{Bus, MemoryBanks, MemoryBus};
+use crate::gameboy::palette::{self, ColorCorrection};
+use crate::gameboy::ppu::Model;
+use sdl2::keyboard::Keycode;
+use sdl2::pixels::PixelFormatEnum;
+use sdl2::render::Canvas;
+use sdl2::video::Window;
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::rc::Rc;
+
+pub use crate::gameboy::palette::Rgb;
+
+/// Colors of the DMG shades, the default palette of the Screen
+pub const SHADES: [Rgb; 4] = palette::GRAYSCALE;
+
+/// Size in bytes of a tile of 8x8 pixels, 2 bits per pixel
+pub const TILE_SIZE: u16 = 16;
+
+/// Glyphs are 3x5 pixels drawn at FONT_SCALE, one pixel row and column of spacing
+const FONT_SCALE: usize = 2;
+pub const CHAR_WIDTH: usize = 4 * FONT_SCALE;
+pub const CHAR_HEIGHT: usize = 6 * FONT_SCALE;
+
+/// Rows of 3 pixels, the most significant bit on the left. Letters are upper case only.
+const FONT: [(char, [u8; 5]); 48] = [
+    ('0', [0b111, 0b101, 0b101, 0b101, 0b111]),
+    ('1', [0b010, 0b110, 0b010, 0b010, 0b111]),
+    ('2', [0b111, 0b001, 0b111, 0b100, 0b111]),
+    ('3', [0b111, 0b001, 0b111, 0b001, 0b111]),
+    ('4', [0b101, 0b101, 0b111, 0b001, 0b001]),
+    ('5', [0b111, 0b100, 0b111, 0b001, 0b111]),
+    ('6', [0b111, 0b100, 0b111, 0b101, 0b111]),
+    ('7', [0b111, 0b001, 0b001, 0b001, 0b001]),
+    ('8', [0b111, 0b101, 0b111, 0b101, 0b111]),
+    ('9', [0b111, 0b101, 0b111, 0b001, 0b111]),
+    ('A', [0b010, 0b101, 0b111, 0b101, 0b101]),
+    ('B', [0b110, 0b101, 0b110, 0b101, 0b110]),
+    ('C', [0b011, 0b100, 0b100, 0b100, 0b011]),
+    ('D', [0b110, 0b101, 0b101, 0b101, 0b110]),
+    ('E', [0b111, 0b100, 0b110, 0b100, 0b111]),
+    ('F', [0b111, 0b100, 0b110, 0b100, 0b100]),
+    ('G', [0b011, 0b100, 0b101, 0b101, 0b011]),
+    ('H', [0b101, 0b101, 0b111, 0b101, 0b101]),
+    ('I', [0b111, 0b010, 0b010, 0b010, 0b111]),
+    ('J', [0b001, 0b001, 0b001, 0b101, 0b010]),
+    ('K', [0b101, 0b101, 0b110, 0b101, 0b101]),
+    ('L', [0b100, 0b100, 0b100, 0b100, 0b111]),
+    ('M', [0b101, 0b111, 0b111, 0b101, 0b101]),
+    ('N', [0b110, 0b101, 0b101, 0b101, 0b101]),
+    ('O', [0b010, 0b101, 0b101, 0b101, 0b010]),
+    ('P', [0b110, 0b101, 0b110, 0b100, 0b100]),
+    ('Q', [0b010, 0b101, 0b101, 0b110, 0b011]),
+    ('R', [0b110, 0b101, 0b110, 0b101, 0b101]),
+    ('S', [0b011, 0b100, 0b010, 0b001, 0b110]),
+    ('T', [0b111, 0b010, 0b010, 0b010, 0b010]),
+    ('U', [0b101, 0b101, 0b101, 0b101, 0b111]),
+    ('V', [0b101, 0b101, 0b101, 0b101, 0b010]),
+    ('W', [0b101, 0b101, 0b111, 0b111, 0b101]),
+    ('X', [0b101, 0b101, 0b010, 0b101, 0b101]),
+    ('Y', [0b101, 0b101, 0b010, 0b010, 0b010]),
+    ('Z', [0b111, 0b001, 0b010, 0b100, 0b111]),
+    (':', [0b000, 0b010, 0b000, 0b010, 0b000]),
+    ('-', [0b000, 0b000, 0b111, 0b000, 0b000]),
+    ('/', [0b001, 0b001, 0b010, 0b100, 0b100]),
+    ('.', [0b000, 0b000, 0b000, 0b000, 0b010]),
+    (',', [0b000, 0b000, 0b000, 0b010, 0b100]),
+    ('=', [0b000, 0b111, 0b000, 0b111, 0b000]),
+    ('+', [0b000, 0b010, 0b111, 0b010, 0b000]),
+    ('[', [0b110, 0b100, 0b100, 0b100, 0b110]),
+    (']', [0b011, 0b001, 0b001, 0b001, 0b011]),
+    ('<', [0b001, 0b010, 0b100, 0b010, 0b001]),
+    ('>', [0b100, 0b010, 0b001, 0b010, 0b100]),
+    ('$', [0b011, 0b110, 0b010, 0b011, 0b110]),
+];
+
+/// A view of the emulator internals, shown in its own window or rendered headlessly.
+/// Memory is only accessed through peek and poke so that views don't disturb the game.
+pub trait DebugView {
+    /// Window title, also used to tell the views apart
+    fn get_title(&self) -> &'static str;
+
+    /// Called once per frame, before rendering
+    fn update(&mut self, _bus: &Bus) {}
+
+    fn render(&self, bus: &Bus) -> Image;
+
+    fn handle_key(&mut self, _keycode: Keycode, _shift: bool, _bus: &mut Bus) {}
+}
+
+/// Palette applied to the color indices (0-3) of the tiles
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Palette {
+    /// Color indices shown as shades
+    Raw,
+    Bgp,
+    Obp0,
+    Obp1,
+    /// CGB background palette 0-7
+    Bg(u8),
+    /// CGB object palette 0-7
+    Obj(u8),
+}
+
+impl Palette {
+    /// Cycle through the palettes, the CGB ones follow Raw in CGB mode
+    pub fn next(self, cgb: bool) -> Palette {
+        match self {
+            Palette::Raw if cgb => Palette::Bg(0),
+            Palette::Raw => Palette::Bgp,
+            Palette::Bgp => Palette::Obp0,
+            Palette::Obp0 => Palette::Obp1,
+            Palette::Obp1 => Palette::Raw,
+            Palette::Bg(7) => Palette::Obj(0),
+            Palette::Bg(index) => Palette::Bg(index + 1),
+            Palette::Obj(7) => Palette::Raw,
+            Palette::Obj(index) => Palette::Obj(index + 1),
+        }
+    }
+
+    pub fn get_name(self) -> String {
+        match self {
+            Palette::Raw => "RAW".to_string(),
+            Palette::Bgp => "BGP".to_string(),
+            Palette::Obp0 => "OBP0".to_string(),
+            Palette::Obp1 => "OBP1".to_string(),
+            Palette::Bg(index) => format!("BG{}", index),
+            Palette::Obj(index) => format!("OBJ{}", index),
+        }
+    }
+
+    /// Colors of the 4 color indices with the current palette registers or palette RAM
+    pub fn get_colors(self, bus: &Bus) -> [Rgb; 4] {
+        let shades = |register: u8| {
+            [0, 1, 2, 3].map(|color| SHADES[(register >> (color * 2) & 0x3) as usize])
+        };
+        let rgb = |color: u16| palette::to_rgb(color, ColorCorrection::Raw);
+        match self {
+            Palette::Raw => shades(0b11_10_01_00),
+            Palette::Bgp => shades(bus.peek(0xff47)),
+            Palette::Obp0 => shades(bus.peek(0xff48)),
+            Palette::Obp1 => shades(bus.peek(0xff49)),
+            Palette::Bg(index) => [0, 1, 2, 3].map(|color| rgb(bus.ppu.get_bg_color(index, color))),
+            Palette::Obj(index) => {
+                [0, 1, 2, 3].map(|color| rgb(bus.ppu.get_object_color(index, color)))
+            }
+        }
+    }
+}
+
+/// Tiles have attributes selecting their VRAM bank and palette in CGB mode only
+pub fn is_cgb_mode(bus: &Bus) -> bool {
+    bus.get_model() == Model::Cgb
+}
+
+/// Color indices (0-3) of the 8 pixels of a tile line, leftmost first
+pub fn read_tile_line(bus: &Bus, vram_bank: usize, tile_address: u16, line: u16) -> [u8; 8] {
+    let banks = MemoryBanks {
+        vram: vram_bank,
+        ..bus.get_banks()
+    };
+    let low = bus.peek_banked(tile_address + line * 2, banks);
+    let high = bus.peek_banked(tile_address + line * 2 + 1, banks);
+    std::array::from_fn(|x| (high >> (7 - x) & 0x1) << 1 | (low >> (7 - x) & 0x1))
+}
+
+/// RGB pixels, 3 bytes per pixel, line by line
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u8>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, color: Rgb) -> Image {
+        Image {
+            width,
+            height,
+            pixels: color.repeat(width * height),
+        }
+    }
+
+    pub fn get_pixel(&self, x: usize, y: usize) -> Rgb {
+        let index = (y * self.width + x) * 3;
+        [
+            self.pixels[index],
+            self.pixels[index + 1],
+            self.pixels[index + 2],
+        ]
+    }
+
+    /// Pixels out of the image are ignored
+    pub fn set_pixel(&mut self, x: usize, y: usize, color: Rgb) {
+        if x < self.width && y < self.height {
+            let index = (y * self.width + x) * 3;
+            self.pixels[index..index + 3].copy_from_slice(&color);
+        }
+    }
+
+    pub fn fill_rect(&mut self, x: usize, y: usize, width: usize, height: usize, color: Rgb) {
+        for y in y..y + height {
+            for x in x..x + width {
+                self.set_pixel(x, y, color);
+            }
+        }
+    }
+
+    /// Outline of a rectangle, one pixel wide
+    pub fn draw_rect(&mut self, x: usize, y: usize, width: usize, height: usize, color: Rgb) {
+        self.fill_rect(x, y, width, 1, color);
+        self.fill_rect(x, y + height - 1, width, 1, color);
+        self.fill_rect(x, y, 1, height, color);
+        self.fill_rect(x + width - 1, y, 1, height, color);
+    }
+
+    /// Tile of the given VRAM bank with its top left corner at (x, y), each pixel
+    /// being a square of scale pixels
+    pub fn draw_tile(
+        &mut self,
+        bus: &Bus,
+        vram_bank: usize,
+        tile_address: u16,
+        (x, y): (usize, usize),
+        scale: usize,
+        colors: &[Rgb; 4],
+    ) {
+        for line in 0..8 {
+            let pixels = read_tile_line(bus, vram_bank, tile_address, line);
+            for (column, color) in pixels.iter().enumerate() {
+                self.fill_rect(
+                    x + column * scale,
+                    y + line as usize * scale,
+                    scale,
+                    scale,
+                    colors[*color as usize],
+                );
+            }
+        }
+    }
+
+    pub fn write_png(&self, path: &Path) -> Result<(), png::EncodingError> {
+        let file = File::create(path)?;
+        let mut encoder =
+            png::Encoder::new(BufWriter::new(file), self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header()?.write_image_data(&self.pixels)
+    }
+
+    /// Text at column and row of characters, characters without glyph are left blank
+    pub fn draw_text(&mut self, column: usize, row: usize, text: &str, color: Rgb) {
+        for (index, character) in text.chars().enumerate() {
+            let character = character.to_ascii_uppercase();
+            let Some((_, glyph)) = FONT.iter().find(|(glyph, _)| *glyph == character) else {
+                continue;
+            };
+            let left = (column + index) * CHAR_WIDTH;
+            let top = row * CHAR_HEIGHT;
+            for (y, line) in glyph.iter().enumerate() {
+                for x in 0..3 {
+                    if line & (0b100 >> x) != 0 {
+                        self.fill_rect(
+                            left + x * FONT_SCALE,
+                            top + y * FONT_SCALE,
+                            FONT_SCALE,
+                            FONT_SCALE,
+                            color,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Background of the character cells from column to column + length
+    pub fn highlight_text(&mut self, column: usize, row: usize, length: usize, color: Rgb) {
+        self.fill_rect(
+            column * CHAR_WIDTH,
+            row * CHAR_HEIGHT,
+            length * CHAR_WIDTH,
+            CHAR_HEIGHT,
+            color,
+        );
+    }
+}
+
+/// SDL window presenting images of a fixed size
+pub struct DebugWindow {
+    canvas: Canvas<Window>,
+}
+
+impl DebugWindow {
+    pub fn new(
+        sdl_context: Rc<RefCell<sdl2::Sdl>>,
+        title: &str,
+        width: usize,
+        height: usize,
+    ) -> DebugWindow {
+        let video_subsystem = sdl_context.borrow_mut().video().unwrap();
+        let window = video_subsystem
+            .window(title, width as u32, height as u32)
+            .build()
+            .unwrap();
+        DebugWindow {
+            canvas: window.into_canvas().build().unwrap(),
+        }
+    }
+
+    /// SDL window id, to dispatch events
+    pub fn get_id(&self) -> u32 {
+        self.canvas.window().id()
+    }
+
+    pub fn present(&mut self, image: &Image) {
+        let creator = self.canvas.texture_creator();
+        let mut texture = creator
+            .create_texture_static(
+                PixelFormatEnum::RGB24,
+                image.width as u32,
+                image.height as u32,
+            )
+            .unwrap();
+        texture
+            .update(None, &image.pixels, image.width * 3)
+            .unwrap();
+        self.canvas.clear();
+        self.canvas.copy(&texture, None, None).unwrap();
+        self.canvas.present();
+    }
+}
@@ -0,0 +1,192 @@
+//! The 40 sprites of the OAM with their position, tile, flags and image. In CGB mode,
+//! sprites take their tile from the VRAM bank and their colors from the OBJ palette of
+//! their flags. Sprites out of the screen are dimmed.
+
+use super::{
+    is_cgb_mode, read_tile_line, DebugView, Image, Palette, Rgb, CHAR_HEIGHT, CHAR_WIDTH, TILE_SIZE,
+};
+use crate::gameboy::bus::{Bus, MemoryBus};
+use crate::gameboy::ppu::{SCREEN_HEIGHT, SCREEN_WIDTH};
+
+const OAM_START: u16 = 0xfe00;
+pub const SPRITE_COUNT: usize = 40;
+
+const COLUMNS: usize = 2;
+const ROWS: usize = SPRITE_COUNT / COLUMNS;
+const SCALE: usize = 2;
+/// Room for a 8x16 sprite
+const ROW_HEIGHT: usize = 16 * SCALE + 4;
+/// Image of the sprite, then its text
+const TEXT_LEFT: usize = 8 * SCALE + 8;
+const TEXT_COLUMNS: usize = 19;
+const COLUMN_WIDTH: usize = TEXT_LEFT + TEXT_COLUMNS * CHAR_WIDTH;
+
+pub const WIDTH: usize = COLUMNS * COLUMN_WIDTH;
+pub const HEIGHT: usize = ROWS * ROW_HEIGHT;
+
+const BACKGROUND: Rgb = [0x10, 0x10, 0x18];
+/// Color 0 of the sprites
+pub const TRANSPARENT: Rgb = [0x30, 0x30, 0x40];
+const TEXT: Rgb = [0xe0, 0xe0, 0xe0];
+const DIM: Rgb = [0x80, 0x80, 0x90];
+
+/// Attributes of an OAM entry
+pub struct Sprite {
+    pub y: u8,
+    pub x: u8,
+    pub tile: u8,
+    pub flags: u8,
+}
+
+impl Sprite {
+    pub fn read(bus: &Bus, index: usize) -> Sprite {
+        let address = OAM_START + index as u16 * 4;
+        Sprite {
+            y: bus.peek(address),
+            x: bus.peek(address + 1),
+            tile: bus.peek(address + 2),
+            flags: bus.peek(address + 3),
+        }
+    }
+
+    /// Y and X are the position of the bottom right corner of a 8x16 sprite
+    pub fn is_on_screen(&self, height: usize) -> bool {
+        let (x, y) = (self.x as usize, self.y as usize);
+        x > 0 && x < SCREEN_WIDTH + 8 && y + height > 16 && y < SCREEN_HEIGHT + 16
+    }
+
+    /// OBJ palette 0-7 in CGB mode, OBP0 or OBP1 otherwise
+    pub fn get_palette(&self, cgb: bool) -> Palette {
+        if cgb {
+            Palette::Obj(self.flags & 0x7)
+        } else if self.flags >> 4 & 0x1 == 0x1 {
+            Palette::Obp1
+        } else {
+            Palette::Obp0
+        }
+    }
+
+    /// Bank 1 can only be selected in CGB mode
+    pub fn get_vram_bank(&self, cgb: bool) -> usize {
+        if cgb {
+            (self.flags >> 3 & 0x1) as usize
+        } else {
+            0
+        }
+    }
+
+    fn is_behind_background(&self) -> bool {
+        self.flags >> 7 & 0x1 == 0x1
+    }
+
+    fn is_y_flipped(&self) -> bool {
+        self.flags >> 6 & 0x1 == 0x1
+    }
+
+    fn is_x_flipped(&self) -> bool {
+        self.flags >> 5 & 0x1 == 0x1
+    }
+}
+
+#[derive(Default)]
+pub struct OamViewer {}
+
+impl OamViewer {
+    pub fn new() -> OamViewer {
+        OamViewer {}
+    }
+
+    /// Top left corner of the row of a sprite
+    pub fn get_row_position(index: usize) -> (usize, usize) {
+        (index / ROWS * COLUMN_WIDTH, index % ROWS * ROW_HEIGHT)
+    }
+
+    /// Sprite with its flips and palette, color 0 being transparent
+    fn draw_sprite(&self, image: &mut Image, bus: &Bus, sprite: &Sprite, (x, y): (usize, usize)) {
+        let height = get_sprite_height(bus);
+        // The tile number of 8x16 sprites ignores bit 0
+        let tile = if height == 16 {
+            sprite.tile & 0xfe
+        } else {
+            sprite.tile
+        };
+        let tile_address = 0x8000 + tile as u16 * TILE_SIZE;
+        let cgb = is_cgb_mode(bus);
+        let colors = sprite.get_palette(cgb).get_colors(bus);
+        let vram_bank = sprite.get_vram_bank(cgb);
+        for line in 0..height {
+            let source_line = if sprite.is_y_flipped() {
+                height - 1 - line
+            } else {
+                line
+            };
+            let pixels = read_tile_line(bus, vram_bank, tile_address, source_line as u16);
+            for column in 0..8 {
+                let source_column = if sprite.is_x_flipped() {
+                    7 - column
+                } else {
+                    column
+                };
+                let color = match pixels[source_column] {
+                    0 => TRANSPARENT,
+                    color => colors[color as usize],
+                };
+                image.fill_rect(x + column * SCALE, y + line * SCALE, SCALE, SCALE, color);
+            }
+        }
+    }
+}
+
+impl DebugView for OamViewer {
+    fn get_title(&self) -> &'static str {
+        "OAM"
+    }
+
+    fn render(&self, bus: &Bus) -> Image {
+        let mut image = Image::new(WIDTH, HEIGHT, BACKGROUND);
+        let height = get_sprite_height(bus);
+        let cgb = is_cgb_mode(bus);
+        for index in 0..SPRITE_COUNT {
+            let sprite = Sprite::read(bus, index);
+            let (x, y) = Self::get_row_position(index);
+            self.draw_sprite(&mut image, bus, &sprite, (x + 4, y + 2));
+
+            let color = if sprite.is_on_screen(height) {
+                TEXT
+            } else {
+                DIM
+            };
+            let column = (x + TEXT_LEFT) / CHAR_WIDTH;
+            let row = y / CHAR_HEIGHT;
+            let position = format!(
+                "{:02} X:{:02X} Y:{:02X} T:{:02X}",
+                index, sprite.x, sprite.y, sprite.tile
+            );
+            let mut flags = format!("   {}", sprite.get_palette(cgb).get_name());
+            if sprite.get_vram_bank(cgb) == 1 {
+                flags += " V1";
+            }
+            if sprite.is_x_flipped() {
+                flags += " XF";
+            }
+            if sprite.is_y_flipped() {
+                flags += " YF";
+            }
+            if sprite.is_behind_background() {
+                flags += " BG";
+            }
+            image.draw_text(column, row, &position, color);
+            image.draw_text(column, row + 1, &flags, color);
+        }
+        image
+    }
+}
+
+/// 8 or 16 pixels depending on LCDC bit 2
+fn get_sprite_height(bus: &Bus) -> usize {
+    if bus.peek(0xff40) >> 2 & 0x1 == 0x1 {
+        16
+    } else {
+        8
+    }
+}
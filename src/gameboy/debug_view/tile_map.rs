@@ -0,0 +1,155 @@
+//! Both 32x32 tile maps, at 9800 and 9c00, drawn with the tile data and palette selected
+//! in LCDC and BGP. In CGB mode, the attributes in VRAM bank 1 give each tile its bank,
+//! flips and palette. The area shown on screen is outlined on the background map and the
+//! window on the window map.
+
+use super::{
+    is_cgb_mode, read_tile_line, DebugView, Image, Palette, Rgb, CHAR_HEIGHT, CHAR_WIDTH, TILE_SIZE,
+};
+use crate::gameboy::bus::{Bus, MemoryBanks, MemoryBus};
+use crate::gameboy::ppu::{BACKGROUND_MAP_SIZE, SCREEN_HEIGHT, SCREEN_WIDTH};
+
+const MAP_ADDRESSES: [u16; 2] = [0x9800, 0x9c00];
+const MAP_TILES: usize = 32;
+const MAP_SPACING: usize = 8;
+
+/// Map names, then a blank line above the maps
+const HEADER_HEIGHT: usize = 2 * CHAR_HEIGHT;
+
+pub const WIDTH: usize = 2 * BACKGROUND_MAP_SIZE + MAP_SPACING;
+pub const HEIGHT: usize = HEADER_HEIGHT + BACKGROUND_MAP_SIZE;
+
+const BACKGROUND: Rgb = [0x10, 0x10, 0x18];
+const HEADER: Rgb = [0xe0, 0xc0, 0x40];
+pub const VIEWPORT: Rgb = [0xff, 0x50, 0x50];
+pub const WINDOW: Rgb = [0x30, 0x90, 0xff];
+
+#[derive(Default)]
+pub struct TileMapViewer {}
+
+impl TileMapViewer {
+    pub fn new() -> TileMapViewer {
+        TileMapViewer {}
+    }
+
+    /// Left of a map in the image
+    fn get_map_left(map: usize) -> usize {
+        map * (BACKGROUND_MAP_SIZE + MAP_SPACING)
+    }
+
+    fn draw_map(&self, image: &mut Image, bus: &Bus, map: usize, colors: &[Rgb; 4]) {
+        let lcd_control = bus.peek(0xff40);
+        let cgb = is_cgb_mode(bus);
+        let banks = bus.get_banks();
+        let left = Self::get_map_left(map);
+        for index in 0..MAP_TILES * MAP_TILES {
+            // Tile numbers are in bank 0 whichever bank VBK selects
+            let address = MAP_ADDRESSES[map] + index as u16;
+            let tile = bus.peek_banked(address, MemoryBanks { vram: 0, ..banks });
+            let attributes = if cgb {
+                bus.peek_banked(address, MemoryBanks { vram: 1, ..banks })
+            } else {
+                0
+            };
+            let colors = if cgb {
+                Palette::Bg(attributes & 0x7).get_colors(bus)
+            } else {
+                *colors
+            };
+            let vram_bank = (attributes >> 3 & 0x1) as usize;
+            let (x_flip, y_flip) = (attributes >> 5 & 0x1 == 0x1, attributes >> 6 & 0x1 == 0x1);
+            let tile_address = get_tile_address(lcd_control, tile);
+            let (x, y) = (
+                left + index % MAP_TILES * 8,
+                HEADER_HEIGHT + index / MAP_TILES * 8,
+            );
+            for line in 0..8 {
+                let source_line = if y_flip { 7 - line } else { line };
+                let pixels = read_tile_line(bus, vram_bank, tile_address, source_line as u16);
+                for column in 0..8 {
+                    let source_column = if x_flip { 7 - column } else { column };
+                    image.set_pixel(x + column, y + line, colors[pixels[source_column] as usize]);
+                }
+            }
+        }
+    }
+
+    /// The 160x144 area at SCX, SCY, wrapping around the map
+    fn draw_viewport(&self, image: &mut Image, bus: &Bus, map: usize) {
+        let (scroll_x, scroll_y) = (bus.peek(0xff43) as usize, bus.peek(0xff42) as usize);
+        let left = Self::get_map_left(map);
+        let mut plot = |x: usize, y: usize| {
+            image.set_pixel(
+                left + (scroll_x + x) % BACKGROUND_MAP_SIZE,
+                HEADER_HEIGHT + (scroll_y + y) % BACKGROUND_MAP_SIZE,
+                VIEWPORT,
+            )
+        };
+        for x in 0..SCREEN_WIDTH {
+            plot(x, 0);
+            plot(x, SCREEN_HEIGHT - 1);
+        }
+        for y in 0..SCREEN_HEIGHT {
+            plot(0, y);
+            plot(SCREEN_WIDTH - 1, y);
+        }
+    }
+
+    /// The part of the window on screen, from the top left of the map
+    fn draw_window(&self, image: &mut Image, bus: &Bus, map: usize) {
+        let (window_x, window_y) = (bus.peek(0xff4b) as usize, bus.peek(0xff4a) as usize);
+        if window_x >= SCREEN_WIDTH + 7 || window_y >= SCREEN_HEIGHT {
+            return;
+        }
+        let width = (SCREEN_WIDTH + 7 - window_x).min(SCREEN_WIDTH);
+        let height = SCREEN_HEIGHT - window_y;
+        image.draw_rect(
+            Self::get_map_left(map),
+            HEADER_HEIGHT,
+            width,
+            height,
+            WINDOW,
+        );
+    }
+}
+
+impl DebugView for TileMapViewer {
+    fn get_title(&self) -> &'static str {
+        "Tile maps"
+    }
+
+    fn render(&self, bus: &Bus) -> Image {
+        let mut image = Image::new(WIDTH, HEIGHT, BACKGROUND);
+        let lcd_control = bus.peek(0xff40);
+        let background_map = (lcd_control >> 3 & 0x1) as usize;
+        let window_map = (lcd_control >> 6 & 0x1) as usize;
+        let window_enabled = lcd_control >> 5 & 0x1 == 0x1;
+
+        let colors = Palette::Bgp.get_colors(bus);
+        for (map, address) in MAP_ADDRESSES.iter().enumerate() {
+            let mut name = format!("{:04X}", address);
+            if map == background_map {
+                name += " BG";
+            }
+            if map == window_map && window_enabled {
+                name += " WINDOW";
+            }
+            image.draw_text(Self::get_map_left(map) / CHAR_WIDTH, 0, &name, HEADER);
+            self.draw_map(&mut image, bus, map, &colors);
+        }
+        self.draw_viewport(&mut image, bus, background_map);
+        if window_enabled {
+            self.draw_window(&mut image, bus, window_map);
+        }
+        image
+    }
+}
+
+/// Tile data of a tile number with the addressing mode selected by LCDC bit 4
+fn get_tile_address(lcd_control: u8, tile: u8) -> u16 {
+    if lcd_control >> 4 & 0x1 == 0x1 {
+        0x8000 + tile as u16 * TILE_SIZE
+    } else {
+        (0x9000_i32 + tile as i8 as i32 * TILE_SIZE as i32) as u16
+    }
+}
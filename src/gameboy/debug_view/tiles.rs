@@ -0,0 +1,119 @@
+//! The 384 tiles of the tile data at 8000-97ff, one table per VRAM bank.
+//!
+//! Keys: arrows move the cursor, tab selects the next VRAM bank, P cycles through the palettes.
+
+use super::{is_cgb_mode, DebugView, Image, Palette, Rgb, CHAR_HEIGHT, TILE_SIZE};
+use crate::gameboy::bus::Bus;
+use sdl2::keyboard::Keycode;
+
+const TILE_DATA_START: u16 = 0x8000;
+pub const TILE_COUNT: usize = 384;
+
+const TILES_PER_ROW: usize = 16;
+const TILE_ROWS: usize = TILE_COUNT / TILES_PER_ROW;
+const SCALE: usize = 2;
+/// Tiles are separated by a one pixel grid
+const CELL_SIZE: usize = 8 * SCALE + 1;
+const TABLE_WIDTH: usize = TILES_PER_ROW * CELL_SIZE + 1;
+const TABLE_HEIGHT: usize = TILE_ROWS * CELL_SIZE + 1;
+const TABLE_SPACING: usize = 8;
+
+/// Palette and selected tile line, then a blank line above the tables
+const HEADER_HEIGHT: usize = 2 * CHAR_HEIGHT;
+
+const BACKGROUND: Rgb = [0x10, 0x10, 0x18];
+const GRID: Rgb = [0x30, 0x30, 0x40];
+const HEADER: Rgb = [0xe0, 0xc0, 0x40];
+pub const CURSOR: Rgb = [0xff, 0x50, 0x50];
+
+pub struct TileViewer {
+    palette: Palette,
+    /// Selected tile and its VRAM bank
+    cursor: usize,
+    bank: usize,
+}
+
+impl Default for TileViewer {
+    fn default() -> TileViewer {
+        TileViewer::new()
+    }
+}
+
+impl TileViewer {
+    pub fn new() -> TileViewer {
+        TileViewer {
+            palette: Palette::Bgp,
+            cursor: 0,
+            bank: 0,
+        }
+    }
+
+    pub fn get_cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn get_palette(&self) -> Palette {
+        self.palette
+    }
+
+    /// Top left corner of the cell of a tile, on the grid
+    fn get_cell_position(bank: usize, tile: usize) -> (usize, usize) {
+        (
+            bank * (TABLE_WIDTH + TABLE_SPACING) + tile % TILES_PER_ROW * CELL_SIZE,
+            HEADER_HEIGHT + tile / TILES_PER_ROW * CELL_SIZE,
+        )
+    }
+
+    fn move_cursor(&mut self, delta: i32) {
+        self.cursor = (self.cursor as i32 + delta).rem_euclid(TILE_COUNT as i32) as usize;
+    }
+}
+
+impl DebugView for TileViewer {
+    fn get_title(&self) -> &'static str {
+        "Tiles"
+    }
+
+    fn render(&self, bus: &Bus) -> Image {
+        let bank_count = bus.get_vram_bank_count();
+        let width = bank_count * (TABLE_WIDTH + TABLE_SPACING) - TABLE_SPACING;
+        let mut image = Image::new(width, HEADER_HEIGHT + TABLE_HEIGHT, BACKGROUND);
+
+        // Tiles past $ff are tiles $00-$7f in the 8800 addressing mode
+        let address = TILE_DATA_START + self.cursor as u16 * TILE_SIZE;
+        let header = format!(
+            "{}  TILE ${:02X} {}:${:04X}",
+            self.palette.get_name(),
+            self.cursor % 0x100,
+            self.bank,
+            address
+        );
+        image.draw_text(0, 0, &header, HEADER);
+
+        let colors = self.palette.get_colors(bus);
+        for bank in 0..bank_count {
+            let (left, top) = Self::get_cell_position(bank, 0);
+            image.fill_rect(left, top, TABLE_WIDTH, TABLE_HEIGHT, GRID);
+            for tile in 0..TILE_COUNT {
+                let (x, y) = Self::get_cell_position(bank, tile);
+                let address = TILE_DATA_START + tile as u16 * TILE_SIZE;
+                image.draw_tile(bus, bank, address, (x + 1, y + 1), SCALE, &colors);
+            }
+        }
+        let (x, y) = Self::get_cell_position(self.bank, self.cursor);
+        image.draw_rect(x, y, CELL_SIZE + 1, CELL_SIZE + 1, CURSOR);
+        image
+    }
+
+    fn handle_key(&mut self, keycode: Keycode, _shift: bool, bus: &mut Bus) {
+        match keycode {
+            Keycode::Up => self.move_cursor(-(TILES_PER_ROW as i32)),
+            Keycode::Down => self.move_cursor(TILES_PER_ROW as i32),
+            Keycode::Left => self.move_cursor(-1),
+            Keycode::Right => self.move_cursor(1),
+            Keycode::Tab => self.bank = (self.bank + 1) % bus.get_vram_bank_count(),
+            Keycode::P => self.palette = self.palette.next(is_cgb_mode(bus)),
+            _ => (),
+        }
+    }
+}
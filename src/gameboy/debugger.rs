@@ -0,0 +1,878 @@
+//! Interactive debugger reading commands on the standard input, entered with --debug,
+//! the D key or when the CPU locks up. Type `help` at the prompt for the commands.
+//!
+//! Watchpoints are checked by the bus on every read_8 and write_8, the debugger stops
+//! before the instruction following the access. The debugger itself goes through
+//! peek and poke so that inspecting memory doesn't trigger them nor disturb the game.
+
+use super::bus::{Bus, MemoryBanks, MemoryBus};
+use super::cpu::Cpu;
+use super::disassembler::{self, Symbols};
+use super::trace::{self, rom_bank_at};
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::ops::RangeInclusive;
+
+/// Instructions shown by disas without a count
+const DISASSEMBLY_LENGTH: u16 = 5;
+
+/// Bytes shown by x without a count
+const EXAMINE_LENGTH: u16 = 64;
+
+const HELP: &str = "\
+c, continue                 resume execution
+s, step [count]             execute count instructions, 1 by default
+n, next                     execute the next instruction, stepping over calls
+finish                      run until the current subroutine returns
+b, break [location] [if <register> <op> <value>]
+                            break at location, PC by default, when the optional condition
+                            holds, op being one of == != < <= > >=
+watch <from> [to]           break after a write in the address range
+rwatch <from> [to]          break after a read in the address range
+awatch <from> [to]          break after a read or a write in the address range
+d, delete [id]              delete a breakpoint or watchpoint, all of them by default
+i, info                     list breakpoints and watchpoints
+r, regs                     show the registers
+set <register> <value>      change a register among a f b c d e h l af bc de hl sp pc
+x <location> [count]        dump count bytes of memory, 64 by default
+write <location> <byte>...  write bytes to memory, ROM included
+disas [location] [count]    disassemble count instructions from PC by default, 5 by default
+bt, backtrace               show the subroutines being executed
+q, quit                     stop the emulator
+
+Numbers are written $4000, 0x4000 or 16384. Locations are numbers, bank:address such as
+2:$4000 or labels of the .sym file, the bank being the one of the ROM, VRAM, cartridge RAM
+or work RAM area of the address. An empty line repeats the last command.";
+
+/// Accepts 0x4000, $4000 or 16384
+pub fn parse_number(text: &str) -> Option<u16> {
+    if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix('$')) {
+        u16::from_str_radix(hex, 16).ok()
+    } else {
+        text.parse().ok()
+    }
+}
+
+fn parse_value(text: &str) -> Result<u16, String> {
+    parse_number(text).ok_or_else(|| format!("Invalid number {}", text))
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Register {
+    A,
+    F,
+    B,
+    C,
+    D,
+    E,
+    H,
+    L,
+    AF,
+    BC,
+    DE,
+    HL,
+    SP,
+    PC,
+}
+
+impl Register {
+    pub fn parse(name: &str) -> Option<Register> {
+        match name.to_lowercase().as_str() {
+            "a" => Some(Register::A),
+            "f" => Some(Register::F),
+            "b" => Some(Register::B),
+            "c" => Some(Register::C),
+            "d" => Some(Register::D),
+            "e" => Some(Register::E),
+            "h" => Some(Register::H),
+            "l" => Some(Register::L),
+            "af" => Some(Register::AF),
+            "bc" => Some(Register::BC),
+            "de" => Some(Register::DE),
+            "hl" => Some(Register::HL),
+            "sp" => Some(Register::SP),
+            "pc" => Some(Register::PC),
+            _ => None,
+        }
+    }
+
+    fn is_16_bit(self) -> bool {
+        matches!(
+            self,
+            Register::AF | Register::BC | Register::DE | Register::HL | Register::SP | Register::PC
+        )
+    }
+
+    pub fn get<B: MemoryBus + ?Sized>(self, cpu: &Cpu<B>) -> u16 {
+        let registers = &cpu.registers;
+        match self {
+            Register::A => registers.a as u16,
+            Register::F => cpu.flags.get_flags() as u16,
+            Register::B => registers.b as u16,
+            Register::C => registers.c as u16,
+            Register::D => registers.d as u16,
+            Register::E => registers.e as u16,
+            Register::H => registers.h as u16,
+            Register::L => registers.l as u16,
+            Register::AF => (registers.a as u16) << 8 | cpu.flags.get_flags() as u16,
+            Register::BC => registers.get_bc(),
+            Register::DE => registers.get_de(),
+            Register::HL => registers.get_hl(),
+            Register::SP => registers.sp,
+            Register::PC => registers.pc,
+        }
+    }
+
+    /// Values of 8 bit registers are truncated
+    pub fn set<B: MemoryBus + ?Sized>(self, cpu: &mut Cpu<B>, value: u16) {
+        let registers = &mut cpu.registers;
+        match self {
+            Register::A => registers.a = value as u8,
+            Register::F => cpu.flags.set_flags(value as u8),
+            Register::B => registers.b = value as u8,
+            Register::C => registers.c = value as u8,
+            Register::D => registers.d = value as u8,
+            Register::E => registers.e = value as u8,
+            Register::H => registers.h = value as u8,
+            Register::L => registers.l = value as u8,
+            Register::AF => {
+                registers.a = (value >> 8) as u8;
+                cpu.flags.set_flags(value as u8);
+            }
+            Register::BC => registers.set_bc(value),
+            Register::DE => registers.set_de(value),
+            Register::HL => registers.set_hl(value),
+            Register::SP => registers.sp = value,
+            Register::PC => registers.pc = value,
+        }
+    }
+}
+
+impl fmt::Display for Register {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", format!("{:?}", self).to_lowercase())
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Comparison {
+    /// Longest operators first so that <= is not read as <
+    const OPERATORS: [(&'static str, Comparison); 6] = [
+        ("==", Comparison::Equal),
+        ("!=", Comparison::NotEqual),
+        ("<=", Comparison::LessOrEqual),
+        (">=", Comparison::GreaterOrEqual),
+        ("<", Comparison::Less),
+        (">", Comparison::Greater),
+    ];
+
+    fn evaluate(self, left: u16, right: u16) -> bool {
+        match self {
+            Comparison::Equal => left == right,
+            Comparison::NotEqual => left != right,
+            Comparison::Less => left < right,
+            Comparison::LessOrEqual => left <= right,
+            Comparison::Greater => left > right,
+            Comparison::GreaterOrEqual => left >= right,
+        }
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (operator, _) = Comparison::OPERATORS
+            .iter()
+            .find(|(_, comparison)| comparison == self)
+            .unwrap();
+        write!(f, "{}", operator)
+    }
+}
+
+/// Register value test of a conditional breakpoint
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct Condition {
+    pub register: Register,
+    pub comparison: Comparison,
+    pub value: u16,
+}
+
+impl Condition {
+    /// "a == $12", spaces are optional
+    pub fn parse(text: &str) -> Result<Condition, String> {
+        let text: String = text.split_whitespace().collect();
+        let (register, (_, comparison), value) = Comparison::OPERATORS
+            .iter()
+            .find_map(|operator| {
+                text.split_once(operator.0)
+                    .map(|(register, value)| (register, operator, value))
+            })
+            .ok_or_else(|| format!("Invalid condition {}", text))?;
+        let register =
+            Register::parse(register).ok_or_else(|| format!("Unknown register {}", register))?;
+        let value = parse_value(value)?;
+        if !register.is_16_bit() && value > 0xff {
+            return Err(format!("{} does not fit in {}", value, register));
+        }
+        Ok(Condition {
+            register,
+            comparison: *comparison,
+            value,
+        })
+    }
+
+    pub fn is_met<B: MemoryBus + ?Sized>(&self, cpu: &Cpu<B>) -> bool {
+        self.comparison.evaluate(self.register.get(cpu), self.value)
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} ${:02x}",
+            self.register, self.comparison, self.value
+        )
+    }
+}
+
+pub struct Breakpoint {
+    pub id: usize,
+    pub address: u16,
+    /// ROM bank the address has to be mapped from, any bank if None
+    pub bank: Option<u16>,
+    pub condition: Option<Condition>,
+}
+
+impl Breakpoint {
+    fn is_hit<B: MemoryBus + ?Sized>(&self, cpu: &Cpu<B>) -> bool {
+        let pc = cpu.registers.pc;
+        pc == self.address
+            && (self.bank.is_none() || self.bank == rom_bank_at(cpu, pc))
+            && self.condition.is_none_or(|condition| condition.is_met(cpu))
+    }
+}
+
+impl fmt::Display for Breakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(bank) = self.bank {
+            write!(f, "{:02x}:", bank)?;
+        }
+        write!(f, "${:04x}", self.address)?;
+        if let Some(condition) = self.condition {
+            write!(f, " if {}", condition)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum WatchKind {
+    Read,
+    Write,
+    /// Reads and writes
+    Access,
+}
+
+impl fmt::Display for WatchKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WatchKind::Read => write!(f, "read"),
+            WatchKind::Write => write!(f, "write"),
+            WatchKind::Access => write!(f, "access"),
+        }
+    }
+}
+
+pub struct Watchpoint {
+    pub id: usize,
+    pub range: RangeInclusive<u16>,
+    pub kind: WatchKind,
+}
+
+impl Watchpoint {
+    fn matches(&self, address: u16, access: WatchKind) -> bool {
+        self.range.contains(&address) && (self.kind == WatchKind::Access || self.kind == access)
+    }
+}
+
+impl fmt::Display for Watchpoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ${:04x}", self.kind, self.range.start())?;
+        if self.range.start() != self.range.end() {
+            write!(f, "-${:04x}", self.range.end())?;
+        }
+        Ok(())
+    }
+}
+
+/// Memory access that triggered a watchpoint
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct WatchHit {
+    pub id: usize,
+    pub address: u16,
+    /// Read or Write
+    pub access: WatchKind,
+    pub value: u8,
+}
+
+impl fmt::Display for WatchHit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Watchpoint {}: {} ${:04x} = ${:02x}",
+            self.id, self.access, self.address, self.value
+        )
+    }
+}
+
+/// Watchpoints checked by the bus, only the first hit is kept until it is taken
+#[derive(Default)]
+pub struct Watchpoints {
+    watchpoints: Vec<Watchpoint>,
+    hit: Option<WatchHit>,
+}
+
+impl Watchpoints {
+    pub fn add(&mut self, watchpoint: Watchpoint) {
+        self.watchpoints.push(watchpoint);
+    }
+
+    /// Returns false if there is no watchpoint with this id
+    pub fn remove(&mut self, id: usize) -> bool {
+        let count = self.watchpoints.len();
+        self.watchpoints.retain(|watchpoint| watchpoint.id != id);
+        self.watchpoints.len() != count
+    }
+
+    pub fn clear(&mut self) {
+        self.watchpoints.clear();
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Watchpoint> {
+        self.watchpoints.iter()
+    }
+
+    /// Called on every access, access being Read or Write
+    pub fn check(&mut self, address: u16, access: WatchKind, value: u8) {
+        if self.watchpoints.is_empty() || self.hit.is_some() {
+            return;
+        }
+        if let Some(watchpoint) = self
+            .watchpoints
+            .iter()
+            .find(|watchpoint| watchpoint.matches(address, access))
+        {
+            self.hit = Some(WatchHit {
+                id: watchpoint.id,
+                address,
+                access,
+                value,
+            });
+        }
+    }
+
+    pub fn take_hit(&mut self) -> Option<WatchHit> {
+        self.hit.take()
+    }
+}
+
+/// What the emulation loop does after a command
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum DebuggerAction {
+    Prompt,
+    Resume,
+    Quit,
+}
+
+/// When execution stops again, besides breakpoints and watchpoints
+enum RunMode {
+    Continue,
+    /// Number of CPU steps left
+    Step(u32),
+    /// Call stack depth to come back to
+    Next(usize),
+    /// Call stack depth to go below
+    Finish(usize),
+}
+
+pub struct Debugger {
+    symbols: Symbols,
+    breakpoints: Vec<Breakpoint>,
+    /// Shared by breakpoints and watchpoints
+    next_id: usize,
+    mode: RunMode,
+    break_requested: bool,
+    /// PC execution last stopped at, breakpoints there are ignored until PC moves
+    stopped_at: Option<u16>,
+    last_command: String,
+}
+
+impl Debugger {
+    pub fn new(symbols: Symbols) -> Debugger {
+        Debugger {
+            symbols,
+            breakpoints: Vec::new(),
+            next_id: 1,
+            mode: RunMode::Continue,
+            break_requested: false,
+            stopped_at: None,
+            last_command: String::new(),
+        }
+    }
+
+    /// Stop before the next instruction
+    pub fn request_break(&mut self) {
+        self.break_requested = true;
+    }
+
+    /// Called before each CPU step, returns why execution stops, empty at the end of a step
+    pub fn check(&mut self, cpu: &mut Cpu<Bus>) -> Option<String> {
+        let pc = cpu.registers.pc;
+        if self.stopped_at != Some(pc) {
+            self.stopped_at = None;
+        }
+        let reason = self.stop_reason(cpu)?;
+        self.stopped_at = Some(pc);
+        self.mode = RunMode::Continue;
+        Some(reason)
+    }
+
+    fn stop_reason(&mut self, cpu: &mut Cpu<Bus>) -> Option<String> {
+        if let Some(hit) = cpu.bus.watchpoints.take_hit() {
+            return Some(hit.to_string());
+        }
+        if self.break_requested {
+            self.break_requested = false;
+            return Some(String::from("Interrupted"));
+        }
+        if self.stopped_at.is_none() {
+            if let Some(breakpoint) = self
+                .breakpoints
+                .iter()
+                .find(|breakpoint| breakpoint.is_hit(cpu))
+            {
+                return Some(format!("Breakpoint {}: {}", breakpoint.id, breakpoint));
+            }
+        }
+        let depth = cpu.call_stack.len();
+        match self.mode {
+            RunMode::Continue => None,
+            RunMode::Step(count) if count > 1 => {
+                self.mode = RunMode::Step(count - 1);
+                None
+            }
+            RunMode::Step(_) => Some(String::new()),
+            RunMode::Next(target) if depth <= target => Some(String::new()),
+            RunMode::Finish(target) if depth < target => Some(String::new()),
+            RunMode::Next(_) | RunMode::Finish(_) => None,
+        }
+    }
+
+    /// Read and execute commands on the standard input until execution resumes
+    pub fn prompt(&mut self, cpu: &mut Cpu<Bus>, reason: &str) -> DebuggerAction {
+        let mut out = io::stdout();
+        if !reason.is_empty() {
+            println!("{}", reason);
+        }
+        if let Err(error) = self.print_location(cpu, &mut out) {
+            println!("{}", error);
+        }
+        let stdin = io::stdin();
+        loop {
+            print!("(gb) ");
+            out.flush().unwrap();
+            let mut line = String::new();
+            match stdin.lock().read_line(&mut line) {
+                Ok(0) | Err(_) => return DebuggerAction::Quit,
+                Ok(_) => (),
+            }
+            let action = self.execute(cpu, &line, &mut out);
+            if action != DebuggerAction::Prompt {
+                return action;
+            }
+        }
+    }
+
+    /// Run one command line, an empty line repeats the last command
+    pub fn execute(
+        &mut self,
+        cpu: &mut Cpu<Bus>,
+        line: &str,
+        out: &mut dyn Write,
+    ) -> DebuggerAction {
+        let line = match line.trim() {
+            "" => self.last_command.clone(),
+            line => line.to_string(),
+        };
+        self.last_command = line.clone();
+        let action = self.run(cpu, &line, out).unwrap_or_else(|error| {
+            writeln!(out, "{}", error).unwrap();
+            DebuggerAction::Prompt
+        });
+        if action == DebuggerAction::Resume {
+            // Accesses made by the commands are not the program's
+            cpu.bus.watchpoints.take_hit();
+        }
+        action
+    }
+
+    fn run(
+        &mut self,
+        cpu: &mut Cpu<Bus>,
+        line: &str,
+        out: &mut dyn Write,
+    ) -> Result<DebuggerAction, Box<dyn Error>> {
+        let mut words = line.split_whitespace();
+        let Some(command) = words.next() else {
+            return Ok(DebuggerAction::Prompt);
+        };
+        let args: Vec<&str> = words.collect();
+        match command {
+            "c" | "continue" => {
+                self.mode = RunMode::Continue;
+                return Ok(DebuggerAction::Resume);
+            }
+            "s" | "step" => {
+                let count = match args.first() {
+                    Some(count) => parse_value(count)?.max(1),
+                    None => 1,
+                };
+                self.mode = RunMode::Step(count as u32);
+                return Ok(DebuggerAction::Resume);
+            }
+            "n" | "next" => {
+                self.mode = RunMode::Next(cpu.call_stack.len());
+                return Ok(DebuggerAction::Resume);
+            }
+            "finish" => {
+                if cpu.call_stack.is_empty() {
+                    return Err("Not in a subroutine".into());
+                }
+                self.mode = RunMode::Finish(cpu.call_stack.len());
+                return Ok(DebuggerAction::Resume);
+            }
+            "q" | "quit" => return Ok(DebuggerAction::Quit),
+            "b" | "break" => self.add_breakpoint(cpu, &args, out)?,
+            "watch" => self.add_watchpoint(cpu, &args, WatchKind::Write, out)?,
+            "rwatch" => self.add_watchpoint(cpu, &args, WatchKind::Read, out)?,
+            "awatch" => self.add_watchpoint(cpu, &args, WatchKind::Access, out)?,
+            "d" | "delete" => self.delete(cpu, &args)?,
+            "i" | "info" => self.print_info(cpu, out)?,
+            "r" | "regs" => print_registers(cpu, out)?,
+            "set" => set_register(cpu, &args)?,
+            "x" => self.examine(cpu, &args, out)?,
+            "write" => self.write_memory(cpu, &args)?,
+            "disas" => self.disassemble(cpu, &args, out)?,
+            "bt" | "backtrace" => self.print_backtrace(cpu, out)?,
+            "h" | "help" => writeln!(out, "{}", HELP)?,
+            _ => {
+                return Err(
+                    format!("Unknown command {}, type help for the commands", command).into(),
+                )
+            }
+        }
+        Ok(DebuggerAction::Prompt)
+    }
+
+    /// Bank and address of a number, bank:address or label
+    fn parse_location(&self, text: &str) -> Result<(Option<u16>, u16), String> {
+        if let Some((bank, address)) = self.symbols.find(text) {
+            return Ok((Some(bank), address));
+        }
+        match text.split_once(':') {
+            Some((bank, address)) => Ok((Some(parse_value(bank)?), parse_value(address)?)),
+            None => Ok((None, parse_value(text)?)),
+        }
+    }
+
+    fn parse_address(&self, text: &str) -> Result<u16, String> {
+        self.parse_location(text).map(|(_, address)| address)
+    }
+
+    /// Location and the banks to map to access it
+    fn parse_memory_location(
+        &self,
+        cpu: &Cpu<Bus>,
+        text: &str,
+    ) -> Result<(u16, MemoryBanks), String> {
+        let (bank, address) = self.parse_location(text)?;
+        let mut banks = cpu.bus.get_banks();
+        if let Some(bank) = bank.map(|bank| bank as usize) {
+            match address {
+                0x4000..=0x7fff => banks.rom = bank,
+                0x8000..=0x9fff => banks.vram = bank,
+                0xa000..=0xbfff => banks.ram = bank,
+                0xd000..=0xdfff => banks.wram = bank,
+                _ => (),
+            }
+        }
+        Ok((address, banks))
+    }
+
+    fn add_breakpoint(
+        &mut self,
+        cpu: &Cpu<Bus>,
+        args: &[&str],
+        out: &mut dyn Write,
+    ) -> Result<(), Box<dyn Error>> {
+        let condition_start = args.iter().position(|arg| *arg == "if");
+        let location = &args[..condition_start.unwrap_or(args.len())];
+        let (bank, address) = match location {
+            [] => (None, cpu.registers.pc),
+            [location] => self.parse_location(location)?,
+            _ => return Err("Usage: break [location] [if <register> <op> <value>]".into()),
+        };
+        // Addresses out of the switchable bank are always mapped the same way
+        let bank = bank.filter(|_| (0x4000..=0x7fff).contains(&address));
+        let condition = match condition_start {
+            Some(start) => Some(Condition::parse(&args[start + 1..].join(" "))?),
+            None => None,
+        };
+        let breakpoint = Breakpoint {
+            id: self.next_id,
+            address,
+            bank,
+            condition,
+        };
+        writeln!(out, "Breakpoint {}: {}", breakpoint.id, breakpoint)?;
+        self.breakpoints.push(breakpoint);
+        self.next_id += 1;
+        Ok(())
+    }
+
+    fn add_watchpoint(
+        &mut self,
+        cpu: &mut Cpu<Bus>,
+        args: &[&str],
+        kind: WatchKind,
+        out: &mut dyn Write,
+    ) -> Result<(), Box<dyn Error>> {
+        let range = match args {
+            [address] => {
+                let address = self.parse_address(address)?;
+                address..=address
+            }
+            [from, to] => self.parse_address(from)?..=self.parse_address(to)?,
+            _ => return Err(format!("Usage: {} <from> [to]", kind).into()),
+        };
+        let watchpoint = Watchpoint {
+            id: self.next_id,
+            range,
+            kind,
+        };
+        writeln!(out, "Watchpoint {}: {}", watchpoint.id, watchpoint)?;
+        cpu.bus.watchpoints.add(watchpoint);
+        self.next_id += 1;
+        Ok(())
+    }
+
+    fn delete(&mut self, cpu: &mut Cpu<Bus>, args: &[&str]) -> Result<(), Box<dyn Error>> {
+        let Some(id) = args.first() else {
+            self.breakpoints.clear();
+            cpu.bus.watchpoints.clear();
+            return Ok(());
+        };
+        let id = parse_value(id)? as usize;
+        let count = self.breakpoints.len();
+        self.breakpoints.retain(|breakpoint| breakpoint.id != id);
+        if self.breakpoints.len() == count && !cpu.bus.watchpoints.remove(id) {
+            return Err(format!("No breakpoint or watchpoint {}", id).into());
+        }
+        Ok(())
+    }
+
+    fn print_info(&self, cpu: &Cpu<Bus>, out: &mut dyn Write) -> io::Result<()> {
+        if self.breakpoints.is_empty() && cpu.bus.watchpoints.iter().next().is_none() {
+            return writeln!(out, "No breakpoints or watchpoints");
+        }
+        for breakpoint in &self.breakpoints {
+            writeln!(out, "{:<4}break   {}", breakpoint.id, breakpoint)?;
+        }
+        for watchpoint in cpu.bus.watchpoints.iter() {
+            writeln!(out, "{:<4}watch   {}", watchpoint.id, watchpoint)?;
+        }
+        Ok(())
+    }
+
+    fn examine(
+        &self,
+        cpu: &mut Cpu<Bus>,
+        args: &[&str],
+        out: &mut dyn Write,
+    ) -> Result<(), Box<dyn Error>> {
+        let ((address, banks), count) = match args {
+            [location] => (self.parse_memory_location(cpu, location)?, EXAMINE_LENGTH),
+            [location, count] => (
+                self.parse_memory_location(cpu, location)?,
+                parse_value(count)?,
+            ),
+            _ => return Err("Usage: x <location> [count]".into()),
+        };
+        for row in (0..count as u32).step_by(16) {
+            let start = address.wrapping_add(row as u16);
+            write!(out, "{:04x} ", start)?;
+            for offset in row..(row + 16).min(count as u32) {
+                write!(
+                    out,
+                    " {:02x}",
+                    cpu.bus
+                        .peek_banked(address.wrapping_add(offset as u16), banks)
+                )?;
+            }
+            writeln!(out)?;
+        }
+        Ok(())
+    }
+
+    fn write_memory(&self, cpu: &mut Cpu<Bus>, args: &[&str]) -> Result<(), Box<dyn Error>> {
+        let [location, values @ ..] = args else {
+            return Err("Usage: write <location> <byte>...".into());
+        };
+        if values.is_empty() {
+            return Err("Usage: write <location> <byte>...".into());
+        }
+        let (address, banks) = self.parse_memory_location(cpu, location)?;
+        for (offset, value) in values.iter().enumerate() {
+            let value = parse_value(value)?;
+            if value > 0xff {
+                return Err(format!("{} is not a byte", value).into());
+            }
+            cpu.bus
+                .poke_banked(address.wrapping_add(offset as u16), value as u8, banks);
+        }
+        Ok(())
+    }
+
+    fn disassemble(
+        &self,
+        cpu: &mut Cpu<Bus>,
+        args: &[&str],
+        out: &mut dyn Write,
+    ) -> Result<(), Box<dyn Error>> {
+        let ((address, banks), count) = match args {
+            [] => ((cpu.registers.pc, cpu.bus.get_banks()), DISASSEMBLY_LENGTH),
+            [location] => (
+                self.parse_memory_location(cpu, location)?,
+                DISASSEMBLY_LENGTH,
+            ),
+            [location, count] => (
+                self.parse_memory_location(cpu, location)?,
+                parse_value(count)?,
+            ),
+            _ => return Err("Usage: disas [location] [count]".into()),
+        };
+        self.print_instructions(cpu, address, banks, count, out)?;
+        Ok(())
+    }
+
+    fn print_instructions(
+        &self,
+        cpu: &mut Cpu<Bus>,
+        mut address: u16,
+        banks: MemoryBanks,
+        count: u16,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        let bank = banks.rom as u16;
+        for _ in 0..count {
+            if let Some(label) = self.symbols.get(bank, address) {
+                writeln!(out, "{}:", label)?;
+            }
+            let bus = &cpu.bus;
+            let instruction = disassembler::disassemble(address, bank, &self.symbols, |address| {
+                bus.peek_banked(address, banks)
+            });
+            let marker = if address == cpu.registers.pc && banks == cpu.bus.get_banks() {
+                "=>"
+            } else {
+                "  "
+            };
+            writeln!(out, "{} {}", marker, instruction)?;
+            address = address.wrapping_add(instruction.bytes.len() as u16);
+        }
+        Ok(())
+    }
+
+    /// Instruction about to be executed
+    fn print_location(&self, cpu: &mut Cpu<Bus>, out: &mut dyn Write) -> io::Result<()> {
+        if cpu.is_locked {
+            writeln!(out, "CPU locked up")?;
+        } else if cpu.is_halted {
+            writeln!(out, "CPU halted")?;
+        } else if cpu.is_stopped {
+            writeln!(out, "CPU stopped")?;
+        }
+        self.print_instructions(cpu, cpu.registers.pc, cpu.bus.get_banks(), 1, out)
+    }
+
+    /// Innermost frame first, each with the address being executed and its subroutine
+    fn print_backtrace(&self, cpu: &Cpu<Bus>, out: &mut dyn Write) -> io::Result<()> {
+        let mut location = cpu.registers.pc;
+        for (depth, frame) in cpu.call_stack.iter().rev().enumerate() {
+            writeln!(
+                out,
+                "#{:<3}{} in {}{}",
+                depth,
+                self.format_address(cpu, location),
+                self.format_address(cpu, frame.target),
+                if frame.is_interrupt {
+                    " (interrupt)"
+                } else {
+                    ""
+                }
+            )?;
+            location = frame.return_address;
+        }
+        writeln!(
+            out,
+            "#{:<3}{}",
+            cpu.call_stack.len(),
+            self.format_address(cpu, location)
+        )
+    }
+
+    /// $4000 followed by its label if any
+    fn format_address(&self, cpu: &Cpu<Bus>, address: u16) -> String {
+        match self.symbols.get(cpu.bus.get_rom_bank(), address) {
+            Some(label) => format!("${:04x} <{}>", address, label),
+            None => format!("${:04x}", address),
+        }
+    }
+}
+
+fn print_registers(cpu: &mut Cpu<Bus>, out: &mut dyn Write) -> io::Result<()> {
+    let pc = cpu.registers.pc;
+    writeln!(out, "{}", trace::format_state(cpu))?;
+    writeln!(
+        out,
+        "Flags:{} IME:{} BANK:{:02X} CY:{}",
+        trace::flag_names(cpu.flags.get_flags()),
+        cpu.is_interrupt_master_enabled() as u8,
+        rom_bank_at(cpu, pc).unwrap_or_default(),
+        cpu.get_total_cycles()
+    )
+}
+
+fn set_register(cpu: &mut Cpu<Bus>, args: &[&str]) -> Result<(), Box<dyn Error>> {
+    let [register, value] = args else {
+        return Err("Usage: set <register> <value>".into());
+    };
+    let register =
+        Register::parse(register).ok_or_else(|| format!("Unknown register {}", register))?;
+    let value = parse_value(value)?;
+    if !register.is_16_bit() && value > 0xff {
+        return Err(format!("{} does not fit in {}", value, register).into());
+    }
+    register.set(cpu, value);
+    Ok(())
+}
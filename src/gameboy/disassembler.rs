@@ -0,0 +1,235 @@
+//! Decodes instructions to RGBDS syntax, using the operand descriptions generated
+//! from ressources/instruction_set.json.
+
+use super::cpu::metadata::{
+    InstructionCode, InstructionMetadata, Operand, CB_INSTRUCTION_METADATA, INSTRUCTION_METADATA,
+};
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+/// Start of the switchable ROM bank
+const ROM_BANK_N_START: u16 = 0x4000;
+
+/// Labels of an RGBDS .sym file, by bank and address
+#[derive(Default)]
+pub struct Symbols {
+    labels: HashMap<(u16, u16), String>,
+}
+
+impl Symbols {
+    pub fn load(path: &Path) -> io::Result<Symbols> {
+        Ok(Symbols::parse(&std::fs::read_to_string(path)?))
+    }
+
+    /// Lines look like "03:4a2f Label", anything after ';' is a comment
+    pub fn parse(content: &str) -> Symbols {
+        let mut labels = HashMap::new();
+        for line in content.lines() {
+            let line = line.split(';').next().unwrap_or_default().trim();
+            let Some((location, name)) = line.split_once(char::is_whitespace) else {
+                continue;
+            };
+            let Some((bank, address)) = location.split_once(':') else {
+                continue;
+            };
+            if let (Ok(bank), Ok(address)) = (
+                u16::from_str_radix(bank, 16),
+                u16::from_str_radix(address, 16),
+            ) {
+                labels.insert((bank, address), name.trim().to_string());
+            }
+        }
+        Symbols { labels }
+    }
+
+    /// Label at this address, bank being the one mapped when the address is in a banked area.
+    /// Unbanked areas are bank 0 in .sym files.
+    pub fn get(&self, bank: u16, address: u16) -> Option<&str> {
+        let bank = if address < ROM_BANK_N_START { 0 } else { bank };
+        self.labels
+            .get(&(bank, address))
+            .or_else(|| self.labels.get(&(0, address)))
+            .map(|label| label.as_str())
+    }
+
+    /// Bank and address of a label
+    pub fn find(&self, name: &str) -> Option<(u16, u16)> {
+        self.labels
+            .iter()
+            .find(|(_, label)| label.as_str() == name)
+            .map(|(location, _)| *location)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+}
+
+pub struct DisassembledInstruction {
+    pub address: u16,
+    pub bytes: Vec<u8>,
+    pub text: String,
+}
+
+impl fmt::Display for DisassembledInstruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let bytes: Vec<String> = self
+            .bytes
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        write!(
+            f,
+            "{:04x}  {:<8}  {}",
+            self.address,
+            bytes.join(" "),
+            self.text
+        )
+    }
+}
+
+/// Decode the instruction at address, read giving access to memory.
+/// bank is the ROM bank mapped at 4000-7fff, used to resolve labels.
+pub fn disassemble(
+    address: u16,
+    bank: u16,
+    symbols: &Symbols,
+    mut read: impl FnMut(u16) -> u8,
+) -> DisassembledInstruction {
+    let opcode = read(address);
+    let (metadata, operands_offset) = if opcode == 0xcb {
+        let opcode = read(address.wrapping_add(1));
+        (&CB_INSTRUCTION_METADATA[opcode as usize], 2)
+    } else {
+        (&INSTRUCTION_METADATA[opcode as usize], 1)
+    };
+    let bytes: Vec<u8> = (0..metadata.length)
+        .map(|offset| read(address.wrapping_add(offset)))
+        .collect();
+
+    let text = format_instruction(
+        metadata,
+        address,
+        &bytes[operands_offset.min(bytes.len())..],
+        bank,
+        symbols,
+    );
+    DisassembledInstruction {
+        address,
+        bytes,
+        text,
+    }
+}
+
+fn format_instruction(
+    metadata: &InstructionMetadata,
+    address: u16,
+    immediate: &[u8],
+    bank: u16,
+    symbols: &Symbols,
+) -> String {
+    let mut mnemonic = metadata.mnemonic.to_string().to_lowercase();
+    // The second byte of STOP is ignored by the CPU
+    if metadata.mnemonic == InstructionCode::STOP {
+        return mnemonic;
+    }
+    if mnemonic.starts_with("illegal") {
+        return format!("db ${:02x}", metadata.opcode);
+    }
+
+    // Only jr uses a relative address, add sp takes a signed offset
+    let relative_to = if metadata.mnemonic == InstructionCode::JR {
+        Some(address.wrapping_add(metadata.length))
+    } else {
+        None
+    };
+    let mut operands: Vec<String> = vec![];
+    let mut iterator = metadata.operands.iter();
+    while let Some(operand) = iterator.next() {
+        // ld hl,sp+e8
+        if operand.name == "SP" && operand.increment {
+            if let Some(offset) = iterator.next() {
+                let value = read_immediate(offset, immediate) as u8 as i8;
+                operands.push(format!("sp{:+}", value));
+            }
+            continue;
+        }
+        // ld [c],a is written ldh [c],a
+        if operand.name == "C" && !operand.immediate {
+            mnemonic = String::from("ldh");
+        }
+        let text = format_operand(
+            operand,
+            read_immediate(operand, immediate),
+            relative_to,
+            bank,
+            symbols,
+        );
+        if operand.immediate {
+            operands.push(text);
+        } else {
+            operands.push(format!("[{}]", text));
+        }
+    }
+
+    if operands.is_empty() {
+        mnemonic
+    } else {
+        format!("{} {}", mnemonic, operands.join(","))
+    }
+}
+
+/// Little endian value of the immediate bytes, if the operand has any
+fn read_immediate(operand: &Operand, immediate: &[u8]) -> u16 {
+    match operand.bytes {
+        1 => immediate.first().copied().unwrap_or_default() as u16,
+        2 => {
+            let low = immediate.first().copied().unwrap_or_default() as u16;
+            let high = immediate.get(1).copied().unwrap_or_default() as u16;
+            high << 8 | low
+        }
+        _ => 0,
+    }
+}
+
+fn format_address(address: u16, bank: u16, symbols: &Symbols) -> String {
+    match symbols.get(bank, address) {
+        Some(label) => label.to_string(),
+        None => format!("${:04x}", address),
+    }
+}
+
+fn format_operand(
+    operand: &Operand,
+    value: u16,
+    relative_to: Option<u16>,
+    bank: u16,
+    symbols: &Symbols,
+) -> String {
+    match (operand.name, relative_to) {
+        ("d8", _) => format!("${:02x}", value),
+        ("d16", _) => format!("${:04x}", value),
+        ("a8", _) => format_address(0xff00 | value, bank, symbols),
+        ("a16", _) => format_address(value, bank, symbols),
+        ("r8", Some(next_address)) => {
+            let target = next_address.wrapping_add(value as u8 as i8 as u16);
+            format_address(target, bank, symbols)
+        }
+        ("r8", None) => format!("{}", value as u8 as i8),
+        // RST vectors
+        (name, _) if name.len() == 3 && name.ends_with('H') => {
+            format!("${}", &name[..name.len() - 1].to_lowercase())
+        }
+        (name, _) => {
+            let mut text = name.to_lowercase();
+            if operand.increment {
+                text.push('+');
+            } else if operand.decrement {
+                text.push('-');
+            }
+            text
+        }
+    }
+}
@@ -0,0 +1,510 @@
+//! GDB remote serial protocol stub, to debug a running ROM from GDB or any RSP client:
+//!
+//! target remote localhost:<port>
+//!
+//! Registers are AF, BC, DE, HL, SP and PC as described by ressources/sm83-target.xml.
+//! Memory goes through the bus like CPU accesses. Software and hardware breakpoints are
+//! the same, watchpoints are the bus ones of the debugger module.
+
+use super::bus::{Bus, MemoryBus};
+use super::cpu::Cpu;
+use super::debugger::{DebuggerAction, Register, WatchHit, WatchKind, Watchpoint};
+use std::io::{self, BufRead, BufReader, ErrorKind, Read, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+
+const TARGET_XML: &str = include_str!("../../ressources/sm83-target.xml");
+
+/// Registers in g packet order
+const REGISTERS: [Register; 6] = [
+    Register::AF,
+    Register::BC,
+    Register::DE,
+    Register::HL,
+    Register::SP,
+    Register::PC,
+];
+
+/// Steps between two checks for a Ctrl-C from the client while running
+const POLL_INTERVAL: u32 = 4096;
+
+/// Largest packet accepted, advertised in qSupported
+const PACKET_SIZE: usize = 0x1000;
+
+/// Sent by the client to interrupt execution
+const INTERRUPT: u8 = 0x03;
+
+const SIGINT: u8 = 2;
+const SIGILL: u8 = 4;
+const SIGTRAP: u8 = 5;
+
+#[derive(Clone, Copy)]
+enum StopReason {
+    Signal(u8),
+    Watch(WatchHit),
+}
+
+/// Packet framing over the TCP connection: $data#checksum, acknowledged by + or -
+struct Connection {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+    /// Acknowledgments are disabled by QStartNoAckMode
+    no_ack: bool,
+}
+
+impl Connection {
+    fn new(stream: TcpStream) -> io::Result<Connection> {
+        stream.set_nodelay(true)?;
+        Ok(Connection {
+            reader: BufReader::new(stream.try_clone()?),
+            writer: stream,
+            no_ack: false,
+        })
+    }
+
+    fn read_byte(&mut self) -> io::Result<u8> {
+        let mut byte = [0];
+        self.reader.read_exact(&mut byte)?;
+        Ok(byte[0])
+    }
+
+    /// Next packet content, acknowledgments and interrupts in between are skipped
+    fn read_packet(&mut self) -> io::Result<Vec<u8>> {
+        loop {
+            while self.read_byte()? != b'$' {}
+            let mut data = Vec::new();
+            loop {
+                match self.read_byte()? {
+                    b'#' => break,
+                    byte => data.push(byte),
+                }
+            }
+            let checksum = [self.read_byte()?, self.read_byte()?];
+            let valid = std::str::from_utf8(&checksum)
+                .ok()
+                .and_then(|checksum| u8::from_str_radix(checksum, 16).ok())
+                == Some(checksum_of(&data));
+            if !self.no_ack {
+                self.writer.write_all(if valid { b"+" } else { b"-" })?;
+            }
+            if valid {
+                return Ok(data);
+            }
+        }
+    }
+
+    fn send(&mut self, data: &str) -> io::Result<()> {
+        let packet = format!("${}#{:02x}", data, checksum_of(data.as_bytes()));
+        self.writer.write_all(packet.as_bytes())
+    }
+
+    /// True if the client sent an interrupt, without waiting for it
+    fn poll_interrupt(&mut self) -> io::Result<bool> {
+        if self.reader.buffer().is_empty() {
+            self.reader.get_ref().set_nonblocking(true)?;
+            let filled = self.reader.fill_buf().map(|data| data.len());
+            self.reader.get_ref().set_nonblocking(false)?;
+            match filled {
+                Ok(0) => return Err(ErrorKind::UnexpectedEof.into()),
+                Ok(_) => (),
+                Err(error) if error.kind() == ErrorKind::WouldBlock => return Ok(false),
+                Err(error) => return Err(error),
+            }
+        }
+        let interrupted = self.reader.buffer().contains(&INTERRUPT);
+        if interrupted {
+            let length = self.reader.buffer().len();
+            self.reader.consume(length);
+        }
+        Ok(interrupted)
+    }
+}
+
+fn checksum_of(data: &[u8]) -> u8 {
+    data.iter().fold(0, |sum, byte| sum.wrapping_add(*byte))
+}
+
+/// qXfer data may contain $, #, } and *, they are escaped with } and xored with 0x20
+fn escape(data: &str) -> String {
+    let mut escaped = String::with_capacity(data.len());
+    for character in data.chars() {
+        if matches!(character, '$' | '#' | '}' | '*') {
+            escaped.push('}');
+            escaped.push((character as u8 ^ 0x20) as char);
+        } else {
+            escaped.push(character);
+        }
+    }
+    escaped
+}
+
+fn parse_hex(text: &str) -> Option<u16> {
+    u16::from_str_radix(text, 16).ok()
+}
+
+/// Two hex digits per byte
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(text.get(index..index + 2)?, 16).ok())
+        .collect()
+}
+
+/// "addr,length" of m, M and Z packets
+fn parse_range(text: &str) -> Option<(u16, u16)> {
+    let (address, length) = text.split_once(',')?;
+    Some((parse_hex(address)?, parse_hex(length)?))
+}
+
+/// 16 bit registers are sent little endian
+fn encode_register(value: u16) -> String {
+    format!("{:02x}{:02x}", value & 0xff, value >> 8)
+}
+
+fn decode_register(text: &str) -> Option<u16> {
+    match decode_hex(text)?.as_slice() {
+        [low, high] => Some((*high as u16) << 8 | *low as u16),
+        _ => None,
+    }
+}
+
+enum RunMode {
+    Stopped,
+    Continue,
+    Step,
+}
+
+pub struct GdbStub {
+    listener: TcpListener,
+    connection: Option<Connection>,
+    mode: RunMode,
+    breakpoints: Vec<u16>,
+    /// Kind, address and length given by the client, with the id on the bus
+    watchpoints: Vec<(WatchKind, u16, u16, usize)>,
+    next_watchpoint_id: usize,
+    /// PC execution last stopped at, breakpoints there are ignored until PC moves
+    stopped_at: Option<u16>,
+    last_stop: StopReason,
+    fault: bool,
+    steps_before_poll: u32,
+}
+
+impl GdbStub {
+    /// Listen on localhost, port 0 picks any free port
+    pub fn listen(port: u16) -> io::Result<GdbStub> {
+        Ok(GdbStub {
+            listener: TcpListener::bind((Ipv4Addr::LOCALHOST, port))?,
+            connection: None,
+            mode: RunMode::Continue,
+            breakpoints: Vec::new(),
+            watchpoints: Vec::new(),
+            // Far from the debugger ids
+            next_watchpoint_id: 0x10000,
+            stopped_at: None,
+            last_stop: StopReason::Signal(SIGTRAP),
+            fault: false,
+            steps_before_poll: POLL_INTERVAL,
+        })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.listener.local_addr().unwrap().port()
+    }
+
+    /// Wait for a client, execution stays stopped until it resumes it
+    pub fn accept(&mut self) -> io::Result<()> {
+        let (stream, _) = self.listener.accept()?;
+        self.connection = Some(Connection::new(stream)?);
+        self.mode = RunMode::Stopped;
+        self.last_stop = StopReason::Signal(SIGTRAP);
+        Ok(())
+    }
+
+    /// Stop with SIGILL before the next instruction, the CPU being locked up
+    pub fn report_fault(&mut self) {
+        self.fault = true;
+    }
+
+    /// Called before each CPU step, serves the client while execution is stopped.
+    /// Returns Quit when the client kills the target.
+    pub fn check(&mut self, cpu: &mut Cpu<Bus>) -> DebuggerAction {
+        if self.connection.is_none() {
+            return DebuggerAction::Resume;
+        }
+        let pc = cpu.registers.pc;
+        if self.stopped_at != Some(pc) {
+            self.stopped_at = None;
+        }
+        if !matches!(self.mode, RunMode::Stopped) {
+            let Some(reason) = self.stop_reason(cpu) else {
+                return DebuggerAction::Resume;
+            };
+            self.last_stop = reason;
+            let reply = self.stop_reply();
+            if self.send(&reply).is_err() {
+                return DebuggerAction::Resume;
+            }
+        }
+        self.stopped_at = Some(pc);
+        self.serve(cpu)
+    }
+
+    fn stop_reason(&mut self, cpu: &mut Cpu<Bus>) -> Option<StopReason> {
+        if let Some(hit) = cpu.bus.watchpoints.take_hit() {
+            return Some(StopReason::Watch(hit));
+        }
+        if self.fault {
+            self.fault = false;
+            return Some(StopReason::Signal(SIGILL));
+        }
+        if matches!(self.mode, RunMode::Step) {
+            return Some(StopReason::Signal(SIGTRAP));
+        }
+        if self.stopped_at.is_none() && self.breakpoints.contains(&cpu.registers.pc) {
+            return Some(StopReason::Signal(SIGTRAP));
+        }
+        self.steps_before_poll -= 1;
+        if self.steps_before_poll == 0 {
+            self.steps_before_poll = POLL_INTERVAL;
+            match self.connection.as_mut()?.poll_interrupt() {
+                Ok(true) => return Some(StopReason::Signal(SIGINT)),
+                Ok(false) => (),
+                Err(_) => self.detach(cpu),
+            }
+        }
+        None
+    }
+
+    fn stop_reply(&self) -> String {
+        match self.last_stop {
+            StopReason::Signal(signal) => format!("S{:02x}", signal),
+            StopReason::Watch(hit) => {
+                let kind = self
+                    .watchpoints
+                    .iter()
+                    .find(|watchpoint| watchpoint.3 == hit.id)
+                    .map_or(WatchKind::Write, |watchpoint| watchpoint.0);
+                let name = match kind {
+                    WatchKind::Write => "watch",
+                    WatchKind::Read => "rwatch",
+                    WatchKind::Access => "awatch",
+                };
+                format!("T{:02x}{}:{:04x};", SIGTRAP, name, hit.address)
+            }
+        }
+    }
+
+    fn send(&mut self, data: &str) -> io::Result<()> {
+        match &mut self.connection {
+            Some(connection) => connection.send(data),
+            None => Ok(()),
+        }
+    }
+
+    /// Answer packets until the client resumes execution, kills or leaves
+    fn serve(&mut self, cpu: &mut Cpu<Bus>) -> DebuggerAction {
+        self.mode = RunMode::Stopped;
+        loop {
+            let Some(connection) = &mut self.connection else {
+                return DebuggerAction::Resume;
+            };
+            let packet = match connection.read_packet() {
+                Ok(packet) => String::from_utf8_lossy(&packet).into_owned(),
+                Err(_) => {
+                    self.detach(cpu);
+                    return DebuggerAction::Resume;
+                }
+            };
+            let reply = match self.handle(cpu, &packet) {
+                Some(reply) => reply,
+                None if packet.starts_with('k') || packet == "vKill;1" => {
+                    return DebuggerAction::Quit
+                }
+                None => {
+                    // Accesses made for the client are not the program's
+                    cpu.bus.watchpoints.take_hit();
+                    return DebuggerAction::Resume;
+                }
+            };
+            if self.send(&reply).is_err() {
+                self.detach(cpu);
+            }
+            if packet == "D" {
+                self.detach(cpu);
+                return DebuggerAction::Resume;
+            }
+        }
+    }
+
+    /// Reply to a packet, None when execution resumes or the target is killed
+    fn handle(&mut self, cpu: &mut Cpu<Bus>, packet: &str) -> Option<String> {
+        let error = || String::from("E01");
+        let reply = match packet.as_bytes().first() {
+            Some(b'?') => self.stop_reply(),
+            Some(b'g') => REGISTERS
+                .iter()
+                .map(|register| encode_register(register.get(cpu)))
+                .collect(),
+            Some(b'G') => {
+                let values: Option<Vec<u16>> = (0..REGISTERS.len())
+                    .map(|index| decode_register(packet.get(1 + index * 4..5 + index * 4)?))
+                    .collect();
+                match values {
+                    Some(values) => {
+                        for (register, value) in REGISTERS.iter().zip(values) {
+                            register.set(cpu, value);
+                        }
+                        String::from("OK")
+                    }
+                    None => error(),
+                }
+            }
+            Some(b'p') => parse_hex(&packet[1..])
+                .and_then(|index| REGISTERS.get(index as usize))
+                .map_or_else(error, |register| encode_register(register.get(cpu))),
+            Some(b'P') => {
+                let register = packet[1..].split_once('=').and_then(|(index, value)| {
+                    let register = REGISTERS.get(parse_hex(index)? as usize)?;
+                    Some((register, decode_register(value)?))
+                });
+                match register {
+                    Some((register, value)) => {
+                        register.set(cpu, value);
+                        String::from("OK")
+                    }
+                    None => error(),
+                }
+            }
+            Some(b'm') => match parse_range(&packet[1..]) {
+                Some((address, length)) if (length as usize) * 2 <= PACKET_SIZE => (0..length)
+                    .map(|offset| format!("{:02x}", cpu.bus.peek(address.wrapping_add(offset))))
+                    .collect(),
+                _ => error(),
+            },
+            Some(b'M') => {
+                let write = packet[1..].split_once(':').and_then(|(range, data)| {
+                    let (address, length) = parse_range(range)?;
+                    let data = decode_hex(data)?;
+                    (data.len() == length as usize).then_some((address, data))
+                });
+                match write {
+                    Some((address, data)) => {
+                        for (offset, value) in data.into_iter().enumerate() {
+                            cpu.bus.poke(address.wrapping_add(offset as u16), value);
+                        }
+                        String::from("OK")
+                    }
+                    None => error(),
+                }
+            }
+            Some(b'c') | Some(b's') => {
+                if let Some(address) = parse_hex(&packet[1..]) {
+                    cpu.registers.pc = address;
+                }
+                self.mode = if packet.starts_with('s') {
+                    RunMode::Step
+                } else {
+                    RunMode::Continue
+                };
+                return None;
+            }
+            Some(b'Z') | Some(b'z') => self.update_breakpoint(cpu, packet),
+            Some(b'k') => return None,
+            Some(b'D') | Some(b'H') | Some(b'T') => String::from("OK"),
+            _ => self.handle_query(packet),
+        };
+        Some(reply)
+    }
+
+    /// Z0/Z1 breakpoints, Z2/Z3/Z4 write, read and access watchpoints, z removes them
+    fn update_breakpoint(&mut self, cpu: &mut Cpu<Bus>, packet: &str) -> String {
+        let insert = packet.starts_with('Z');
+        let Some((kind, range)) = packet[1..].split_once(',') else {
+            return String::from("E01");
+        };
+        let Some((address, length)) = parse_range(range) else {
+            return String::from("E01");
+        };
+        let watch_kind = match kind {
+            "0" | "1" => {
+                if insert {
+                    self.breakpoints.push(address);
+                } else if let Some(index) = self.breakpoints.iter().position(|a| *a == address) {
+                    self.breakpoints.remove(index);
+                }
+                return String::from("OK");
+            }
+            "2" => WatchKind::Write,
+            "3" => WatchKind::Read,
+            "4" => WatchKind::Access,
+            _ => return String::new(),
+        };
+        let length = length.max(1);
+        if insert {
+            let id = self.next_watchpoint_id;
+            self.next_watchpoint_id += 1;
+            cpu.bus.watchpoints.add(Watchpoint {
+                id,
+                range: address..=address.saturating_add(length - 1),
+                kind: watch_kind,
+            });
+            self.watchpoints.push((watch_kind, address, length, id));
+        } else if let Some(index) = self.watchpoints.iter().position(|&(kind, start, size, _)| {
+            kind == watch_kind && start == address && size == length
+        }) {
+            let (_, _, _, id) = self.watchpoints.remove(index);
+            cpu.bus.watchpoints.remove(id);
+        }
+        String::from("OK")
+    }
+
+    /// General queries, an empty reply tells the client the packet is not supported
+    fn handle_query(&mut self, packet: &str) -> String {
+        if packet.starts_with("qSupported") {
+            return format!(
+                "PacketSize={:x};qXfer:features:read+;QStartNoAckMode+",
+                PACKET_SIZE
+            );
+        }
+        if let Some(request) = packet.strip_prefix("qXfer:features:read:target.xml:") {
+            let Some((offset, length)) = request.split_once(',').and_then(|(offset, length)| {
+                Some((
+                    usize::from_str_radix(offset, 16).ok()?,
+                    usize::from_str_radix(length, 16).ok()?,
+                ))
+            }) else {
+                return String::from("E01");
+            };
+            let start = offset.min(TARGET_XML.len());
+            let end = (start + length).min(TARGET_XML.len());
+            let prefix = if end == TARGET_XML.len() { 'l' } else { 'm' };
+            return format!("{}{}", prefix, escape(&TARGET_XML[start..end]));
+        }
+        match packet {
+            "QStartNoAckMode" => {
+                // The OK itself is still acknowledged
+                if let Some(connection) = &mut self.connection {
+                    connection.no_ack = true;
+                }
+                String::from("OK")
+            }
+            "qAttached" => String::from("1"),
+            "qC" => String::from("QC1"),
+            "qfThreadInfo" => String::from("m1"),
+            "qsThreadInfo" => String::from("l"),
+            _ => String::new(),
+        }
+    }
+
+    /// Forget the client, its breakpoints and watchpoints, execution goes on
+    fn detach(&mut self, cpu: &mut Cpu<Bus>) {
+        self.connection = None;
+        self.breakpoints.clear();
+        for (_, _, _, id) in self.watchpoints.drain(..) {
+            cpu.bus.watchpoints.remove(id);
+        }
+        self.mode = RunMode::Continue;
+    }
+}
@@ -1,34 +1,192 @@
-mod bus;
+pub mod bus;
 mod cartridge;
-mod cpu;
-mod ppu;
+pub mod cpu;
+pub mod debug_view;
+pub mod debugger;
+pub mod disassembler;
+pub mod gdb;
+pub mod palette;
+pub mod ppu;
+mod screen;
+pub mod trace;
 
+use self::bus::printer::Printer;
+use self::bus::serial::SerialDevice;
+use self::bus::sgb::{BORDER_HEIGHT, BORDER_WIDTH};
+use self::bus::{Bus, MemoryBus};
 use self::cartridge::Cartridge;
-use cpu::Cpu;
-use sdl2::event::Event;
-use sdl2::keyboard::Keycode;
+use self::disassembler::{DisassembledInstruction, Symbols};
+use self::screen::Screen;
+use cpu::registers::Registers;
+use cpu::{Cpu, CpuFault};
+use debug_view::memory::MemoryViewer;
+use debug_view::oam::OamViewer;
+use debug_view::tile_map::TileMapViewer;
+use debug_view::tiles::TileViewer;
+use debug_view::{DebugView, DebugWindow, Image};
+use debugger::{Debugger, DebuggerAction};
+use gdb::GdbStub;
+use palette::{ColorCorrection, Rgb};
+use ppu::{Model, SCREEN_HEIGHT, SCREEN_WIDTH};
+use sdl2::event::{Event, WindowEvent};
+use sdl2::keyboard::{Keycode, Mod};
 use std::cell::RefCell;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use trace::Tracer;
+
+/// Content of the screenshots
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScreenshotOptions {
+    /// The whole Super Game Boy picture, ignored without SGB
+    pub border: bool,
+    /// Each pixel is a square of scale pixels
+    pub scale: usize,
+}
+
+impl Default for ScreenshotOptions {
+    fn default() -> ScreenshotOptions {
+        ScreenshotOptions {
+            border: false,
+            scale: 1,
+        }
+    }
+}
 
 pub struct GameBoy<'a> {
-    cpu: Cpu<'a>,
-    sdl_context: Rc<RefCell<sdl2::Sdl>>,
+    cpu: Cpu<Bus>,
+    sdl_context: Option<Rc<RefCell<sdl2::Sdl>>>,
+    screen: Option<Screen<'a>>,
     running: bool,
     paused: bool,
+    debugger: Option<Debugger>,
+    gdb: Option<GdbStub>,
+    /// Open debug windows
+    debug_views: Vec<(Box<dyn DebugView>, DebugWindow)>,
+    dmg_palette: [Rgb; 4],
+    color_correction: ColorCorrection,
+    /// DMG cartridges run on a CGB in DMG compatibility mode
+    colorization: bool,
+    /// Cartridges with SGB functions run on a Super Game Boy
+    sgb: bool,
+    /// Where the S key saves screenshots
+    screenshot_dir: PathBuf,
+    screenshot_options: ScreenshotOptions,
 }
 
 impl GameBoy<'_> {
     pub fn new() -> GameBoy<'static> {
         let _sdl_context = Rc::new(RefCell::new(sdl2::init().unwrap()));
+        let mut screen = Screen::new(_sdl_context.clone());
+        screen.start();
         GameBoy {
-            cpu: Cpu::new(_sdl_context.clone()),
-            sdl_context: _sdl_context,
+            cpu: Cpu::new(Bus::new()),
+            sdl_context: Some(_sdl_context),
+            screen: Some(screen),
             running: true,
             paused: false,
+            debugger: None,
+            gdb: None,
+            debug_views: vec![],
+            dmg_palette: palette::GRAYSCALE,
+            color_correction: ColorCorrection::default(),
+            colorization: false,
+            sgb: false,
+            screenshot_dir: PathBuf::from("."),
+            screenshot_options: ScreenshotOptions::default(),
         }
     }
 
-    pub fn start(&mut self, rom_file: String) {
+    /// Game Boy without window nor event handling, driven with run_frame
+    pub fn headless() -> GameBoy<'static> {
+        GameBoy {
+            cpu: Cpu::new(Bus::new()),
+            sdl_context: None,
+            screen: None,
+            running: true,
+            paused: false,
+            debugger: None,
+            gdb: None,
+            debug_views: vec![],
+            dmg_palette: palette::GRAYSCALE,
+            color_correction: ColorCorrection::default(),
+            colorization: false,
+            sgb: false,
+            screenshot_dir: PathBuf::from("."),
+            screenshot_options: ScreenshotOptions::default(),
+        }
+    }
+
+    /// Screenshots taken with the S key are saved in output_dir
+    pub fn set_screenshots(&mut self, output_dir: PathBuf, options: ScreenshotOptions) {
+        self.screenshot_dir = output_dir;
+        self.screenshot_options = options;
+    }
+
+    /// Colors of the 4 shades of DMG frames, see palette
+    pub fn set_dmg_palette(&mut self, colors: [Rgb; 4]) {
+        self.dmg_palette = colors;
+        if let Some(screen) = &mut self.screen {
+            screen.set_dmg_palette(&colors);
+        }
+    }
+
+    /// How CGB colors are shown
+    pub fn set_color_correction(&mut self, color_correction: ColorCorrection) {
+        self.color_correction = color_correction;
+        if let Some(screen) = &mut self.screen {
+            screen.set_color_correction(color_correction);
+        }
+    }
+
+    /// Run DMG cartridges on a CGB, with the colors its boot ROM picks from the title.
+    /// Takes effect on the next load.
+    pub fn set_colorization(&mut self, enabled: bool) {
+        self.colorization = enabled;
+    }
+
+    /// Run cartridges with SGB functions on a Super Game Boy, with its palettes and border.
+    /// Takes effect on the next load.
+    pub fn set_sgb(&mut self, enabled: bool) {
+        self.sgb = enabled;
+    }
+
+    /// Plug a Game Boy Printer in the link port, printouts are saved in output_dir
+    pub fn connect_printer(&mut self, output_dir: PathBuf) {
+        self.cpu
+            .bus
+            .serial
+            .connect(Box::new(Printer::new(output_dir)));
+    }
+
+    /// Write an execution trace, see trace::Tracer
+    pub fn set_tracer(&mut self, tracer: Tracer) {
+        self.cpu.tracer = Some(tracer);
+    }
+
+    /// The debugger prompt is shown on the D key, breakpoints, watchpoints and CPU faults
+    pub fn attach_debugger(&mut self, debugger: Debugger) {
+        self.debugger = Some(debugger);
+    }
+
+    /// Serve a GDB client connected to the stub
+    pub fn attach_gdb(&mut self, gdb: GdbStub) {
+        self.gdb = Some(gdb);
+    }
+
+    /// VRAM and OAM are locked while the PPU uses them like on hardware, unless turned off
+    /// for debugging
+    pub fn set_access_restrictions(&mut self, enabled: bool) {
+        self.cpu.bus.ppu.set_access_restrictions(enabled);
+    }
+
+    /// Plug any device in the link port
+    pub fn connect_serial(&mut self, device: Box<dyn SerialDevice>) {
+        self.cpu.bus.serial.connect(device);
+    }
+
+    pub fn load(&mut self, rom_file: String) {
         // Loading the cartridge
         let cartridge = Cartridge::load(rom_file);
         println!("{}", cartridge);
@@ -53,30 +211,72 @@ impl GameBoy<'_> {
             - Le processeur commence à exécuter le jeu.
         */
 
+        let sgb = self.sgb && cartridge.supports_sgb();
         self.cpu.start(cartridge);
+        if self.colorization && self.cpu.bus.get_model() == Model::Dmg {
+            self.cpu.bus.set_model(Model::DmgCompatibility);
+        }
+        if sgb && self.cpu.bus.get_model() == Model::Dmg {
+            self.cpu.bus.enable_sgb();
+            if let Some(screen) = &mut self.screen {
+                screen.show_border();
+            }
+        }
+
+        if self.cpu.bus.get_model() != Model::Dmg {
+            self.cpu.skip_cgb_boot_rom();
+        } else {
+            self.cpu.bus.load_boot_rom();
+            self.cpu.registers.pc = 0x0000;
+        }
+    }
 
-        self.cpu.bus.load_boot_rom();
-        self.cpu.registers.pc = 0x0000;
-        let sleep_time = std::time::Duration::from_millis(1000);
+    pub fn start(&mut self, rom_file: String) {
+        self.load(rom_file);
 
         while self.running {
-            // 4 cpu clock tick per ppu clock tick ?
             if !self.paused {
-                if !self.cpu.is_halted {
-                    self.cpu.bus.timer.tick();
-                    self.cpu.next();
-                    self.cpu.bus.timer.tick();
-                    self.cpu.next();
-                    self.cpu.bus.timer.tick();
-                    self.cpu.next();
-                    self.cpu.bus.timer.tick();
-                    self.cpu.next();
-                }
-                self.cpu.bus.ppu.next();
-                self.cpu.print_status();
+                if let Some(debugger) = &mut self.debugger {
+                    if let Some(reason) = debugger.check(&mut self.cpu) {
+                        if debugger.prompt(&mut self.cpu, &reason) == DebuggerAction::Quit {
+                            self.running = false;
+                            break;
+                        }
+                    }
+                }
+                if let Some(gdb) = &mut self.gdb {
+                    if gdb.check(&mut self.cpu) == DebuggerAction::Quit {
+                        self.running = false;
+                        break;
+                    }
+                }
+                self.tick();
+                if let Some(fault) = self.cpu.take_fault() {
+                    // Keep the state around instead of running a locked CPU
+                    println!("{}", fault);
+                    if let Some(gdb) = &mut self.gdb {
+                        gdb.report_fault();
+                    } else if let Some(debugger) = &mut self.debugger {
+                        debugger.request_break();
+                    } else {
+                        self.paused = true;
+                    }
+                }
                 //println!("{}", self.cpu.bus.ppu);
-                if false && self.cpu.registers.pc == 0x64 {
-                    std::thread::sleep(sleep_time);
+                if self.cpu.bus.ppu.is_frame_completed() {
+                    if let Some(screen) = &mut self.screen {
+                        if let Some(sgb) = self.cpu.bus.get_sgb() {
+                            screen.present_border(&sgb.get_border_frame());
+                        } else if let Some(colors) = self.cpu.bus.ppu.get_color_frame_buffer() {
+                            screen.present_colors(colors);
+                        } else {
+                            screen.present(self.cpu.bus.ppu.get_frame_buffer());
+                        }
+                    }
+                    for (view, window) in &mut self.debug_views {
+                        view.update(&self.cpu.bus);
+                        window.present(&view.render(&self.cpu.bus));
+                    }
                 }
             }
 
@@ -84,10 +284,160 @@ impl GameBoy<'_> {
         }
     }
 
+    /// Run until the PPU has completed a frame, or until the CPU is stopped as the LCD
+    /// stands still then
+    pub fn run_frame(&mut self) {
+        loop {
+            self.tick();
+            if self.cpu.bus.ppu.is_frame_completed() || self.cpu.is_stopped {
+                return;
+            }
+        }
+    }
+
+    /// Run a single instruction, interrupt dispatch or halted M-cycle
+    pub fn step(&mut self) {
+        self.tick();
+    }
+
+    /// The CPU ticks the bus, and so the PPU and the timer, on each of its M-cycles
+    fn tick(&mut self) {
+        self.cpu.next();
+    }
+
+    /// Shades (0-3) of the last frame, see ppu::SCREEN_WIDTH and ppu::SCREEN_HEIGHT
+    pub fn get_frame_buffer(&self) -> &[u8] {
+        self.cpu.bus.ppu.get_frame_buffer()
+    }
+
+    /// 15-bit colors of the last frame in CGB mode, see Ppu::get_color_frame_buffer
+    pub fn get_color_frame_buffer(&self) -> Option<&[u16]> {
+        self.cpu.bus.ppu.get_color_frame_buffer()
+    }
+
+    /// The last frame as shown on screen, with the DMG palette, the color correction or
+    /// the SGB palettes
+    pub fn get_rgb_frame_buffer(&self) -> Vec<Rgb> {
+        if let Some(sgb) = self.cpu.bus.get_sgb() {
+            return sgb
+                .get_colors()
+                .iter()
+                .map(|color| palette::to_rgb(*color, ColorCorrection::Raw))
+                .collect();
+        }
+        match self.get_color_frame_buffer() {
+            Some(colors) => colors
+                .iter()
+                .map(|color| palette::to_rgb(*color, self.color_correction))
+                .collect(),
+            None => self
+                .get_frame_buffer()
+                .iter()
+                .map(|shade| self.dmg_palette[*shade as usize])
+                .collect(),
+        }
+    }
+
+    /// The last frame as shown on screen
+    pub fn get_screenshot(&self, options: ScreenshotOptions) -> Image {
+        let (width, height, pixels) = match self.cpu.bus.get_sgb() {
+            Some(sgb) if options.border => (
+                BORDER_WIDTH,
+                BORDER_HEIGHT,
+                sgb.get_border_frame()
+                    .iter()
+                    .map(|color| palette::to_rgb(*color, ColorCorrection::Raw))
+                    .collect(),
+            ),
+            _ => (SCREEN_WIDTH, SCREEN_HEIGHT, self.get_rgb_frame_buffer()),
+        };
+        let scale = options.scale.max(1);
+        let mut image = Image::new(width * scale, height * scale, [0; 3]);
+        for (index, color) in pixels.iter().enumerate() {
+            let (x, y) = (index % width, index / width);
+            image.fill_rect(x * scale, y * scale, scale, scale, *color);
+        }
+        image
+    }
+
+    /// Save the last frame as shown on screen to a PNG named after the current time in
+    /// output_dir, returns its path
+    pub fn save_screenshot(
+        &self,
+        output_dir: &Path,
+        options: ScreenshotOptions,
+    ) -> Result<PathBuf, png::EncodingError> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis())
+            .unwrap_or_default();
+        let path = output_dir.join(format!("screenshot_{}.png", timestamp));
+        self.get_screenshot(options).write_png(&path)?;
+        Ok(path)
+    }
+
+    /// Run frame_limit frames, saving every interval-th one as frame_NNNNNN.png in
+    /// output_dir, returns the number of frames saved
+    pub fn dump_frames(
+        &mut self,
+        output_dir: &Path,
+        interval: u64,
+        frame_limit: u64,
+        options: ScreenshotOptions,
+    ) -> Result<u64, png::EncodingError> {
+        let interval = interval.max(1);
+        for frame in 1..=frame_limit {
+            self.run_frame();
+            if frame % interval == 0 {
+                let path = output_dir.join(format!("frame_{:06}.png", frame));
+                self.get_screenshot(options).write_png(&path)?;
+            }
+        }
+        Ok(frame_limit / interval)
+    }
+
+    /// Memory and peripherals, to render the debug views headlessly
+    pub fn get_bus(&self) -> &Bus {
+        &self.cpu.bus
+    }
+
+    /// Decode the instruction at address with the current memory mapping
+    pub fn disassemble(&mut self, address: u16, symbols: &Symbols) -> DisassembledInstruction {
+        let bank = self.cpu.bus.get_rom_bank();
+        let bus = &self.cpu.bus;
+        disassembler::disassemble(address, bank, symbols, |address| bus.peek(address))
+    }
+
+    pub fn get_registers(&self) -> Registers {
+        self.cpu.registers
+    }
+
+    /// Fault that locked the CPU up since last call, if any
+    pub fn take_cpu_fault(&mut self) -> Option<CpuFault> {
+        self.cpu.take_fault()
+    }
+
+    /// True if LD B,B was executed since last call, test ROMs use it as a breakpoint
+    pub fn is_breakpoint_hit(&mut self) -> bool {
+        let value = self.cpu.breakpoint_hit;
+        self.cpu.breakpoint_hit = false;
+        value
+    }
+
     fn event_handler(&mut self) {
-        let mut event_pump = self.sdl_context.borrow_mut().event_pump().unwrap();
+        let Some(sdl_context) = &self.sdl_context else {
+            return;
+        };
+        let mut event_pump = sdl_context.borrow_mut().event_pump().unwrap();
         for event in event_pump.poll_iter() {
+            if self.handle_debug_view_event(&event) {
+                continue;
+            }
             match event {
+                Event::Window {
+                    win_event: WindowEvent::Close,
+                    ..
+                } => self.running = false,
                 Event::Quit { .. }
                 | Event::KeyDown {
                     keycode: Some(Keycode::Q),
@@ -97,6 +447,37 @@ impl GameBoy<'_> {
                     keycode: Some(Keycode::P),
                     ..
                 } => self.paused = !self.paused,
+                Event::KeyDown {
+                    keycode: Some(Keycode::M),
+                    ..
+                } => self.toggle_debug_view(Box::new(MemoryViewer::new())),
+                Event::KeyDown {
+                    keycode: Some(Keycode::T),
+                    ..
+                } => self.toggle_debug_view(Box::new(TileViewer::new())),
+                Event::KeyDown {
+                    keycode: Some(Keycode::B),
+                    ..
+                } => self.toggle_debug_view(Box::new(TileMapViewer::new())),
+                Event::KeyDown {
+                    keycode: Some(Keycode::O),
+                    ..
+                } => self.toggle_debug_view(Box::new(OamViewer::new())),
+                Event::KeyDown {
+                    keycode: Some(Keycode::D),
+                    ..
+                } => {
+                    if let Some(debugger) = &mut self.debugger {
+                        debugger.request_break();
+                    }
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::S),
+                    ..
+                } => match self.save_screenshot(&self.screenshot_dir, self.screenshot_options) {
+                    Ok(path) => println!("Saved {}", path.display()),
+                    Err(error) => println!("Unable to save the screenshot: {}", error),
+                },
                 Event::KeyDown {
                     keycode: Some(Keycode::Up),
                     ..
@@ -180,6 +561,63 @@ impl GameBoy<'_> {
         }
     }
 
+    /// Open the window of the view, or close it if a view with the same title is open
+    fn toggle_debug_view(&mut self, view: Box<dyn DebugView>) {
+        let title = view.get_title();
+        if let Some(index) = self
+            .debug_views
+            .iter()
+            .position(|(open, _)| open.get_title() == title)
+        {
+            self.debug_views.remove(index);
+            return;
+        }
+        let Some(sdl_context) = &self.sdl_context else {
+            return;
+        };
+        let image = view.render(&self.cpu.bus);
+        let mut window = DebugWindow::new(sdl_context.clone(), title, image.width, image.height);
+        window.present(&image);
+        self.debug_views.push((view, window));
+    }
+
+    /// Events of the debug windows, returns false for the other windows
+    fn handle_debug_view_event(&mut self, event: &Event) -> bool {
+        let window_id = match *event {
+            Event::KeyDown { window_id, .. }
+            | Event::KeyUp { window_id, .. }
+            | Event::Window { window_id, .. } => window_id,
+            _ => return false,
+        };
+        let Some(index) = self
+            .debug_views
+            .iter()
+            .position(|(_, window)| window.get_id() == window_id)
+        else {
+            return false;
+        };
+        let (view, window) = &mut self.debug_views[index];
+        match *event {
+            Event::KeyDown {
+                keycode: Some(keycode),
+                keymod,
+                ..
+            } => {
+                let shift = keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD);
+                view.handle_key(keycode, shift, &mut self.cpu.bus);
+                window.present(&view.render(&self.cpu.bus));
+            }
+            Event::Window {
+                win_event: WindowEvent::Close,
+                ..
+            } => {
+                self.debug_views.remove(index);
+            }
+            _ => (),
+        }
+        true
+    }
+
     fn handle_controller_event_down(&mut self, keycode: Keycode) {
         match keycode {
             Keycode::Up => self.cpu.bus.controller.press_up(),
@@ -195,7 +633,6 @@ impl GameBoy<'_> {
     }
 
     fn handle_controller_event_up(&mut self, keycode: Keycode) {
-        self.cpu.is_halted = false;
         match keycode {
             Keycode::Up => self.cpu.bus.controller.unpress_up(),
             Keycode::Down => self.cpu.bus.controller.unpress_down(),
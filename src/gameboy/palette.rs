@@ -0,0 +1,315 @@
+//! Colors shown on the LCD: the palettes giving the 4 DMG shades a color, the colors the
+//! CGB boot ROM gives to DMG cartridges and the conversion of 15-bit CGB colors to RGB.
+
+use std::fs;
+use std::path::Path;
+
+pub type Rgb = [u8; 3];
+
+/// Shades from white to black, the default
+pub const GRAYSCALE: [Rgb; 4] = [
+    [0xff, 0xff, 0xff],
+    [0x80, 0x80, 0x80],
+    [0x40, 0x40, 0x40],
+    [0x05, 0x05, 0x05],
+];
+
+/// Green shades of the original DMG screen
+pub const PEA_SOUP: [Rgb; 4] = [
+    [0x9b, 0xbc, 0x0f],
+    [0x8b, 0xac, 0x0f],
+    [0x30, 0x62, 0x30],
+    [0x0f, 0x38, 0x0f],
+];
+
+/// Slightly green grays of the Game Boy Pocket screen
+pub const POCKET: [Rgb; 4] = [
+    [0xc4, 0xcf, 0xa1],
+    [0x8b, 0x95, 0x6d],
+    [0x4d, 0x53, 0x3c],
+    [0x1f, 0x1f, 0x1f],
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DmgPalette {
+    pub name: String,
+    /// From shade 0 (lightest) to shade 3
+    pub colors: [Rgb; 4],
+}
+
+pub fn get_builtin_palettes() -> Vec<DmgPalette> {
+    [
+        ("grayscale", GRAYSCALE),
+        ("pea-soup", PEA_SOUP),
+        ("pocket", POCKET),
+    ]
+    .iter()
+    .map(|(name, colors)| DmgPalette {
+        name: name.to_string(),
+        colors: *colors,
+    })
+    .collect()
+}
+
+/// Palettes of a palette file, one per line: a name then 4 colors as RRGGBB from the
+/// lightest shade, with an optional # prefix. Lines starting with # are comments.
+///
+/// ```text
+/// # Super Game Boy default
+/// sgb: #f7e7c6 #d68e49 #a63725 #331e50
+/// ```
+pub fn parse_palettes(text: &str) -> Result<Vec<DmgPalette>, String> {
+    let mut palettes = vec![];
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let palette =
+            parse_palette(line).map_err(|error| format!("line {}: {}", index + 1, error))?;
+        palettes.push(palette);
+    }
+    Ok(palettes)
+}
+
+fn parse_palette(line: &str) -> Result<DmgPalette, String> {
+    let (name, colors) = line
+        .split_once(':')
+        .ok_or_else(|| "missing ':' after the palette name".to_string())?;
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("missing palette name".to_string());
+    }
+    let colors = colors
+        .split_whitespace()
+        .map(parse_color)
+        .collect::<Result<Vec<Rgb>, String>>()?;
+    let colors: [Rgb; 4] = colors
+        .try_into()
+        .map_err(|colors: Vec<Rgb>| format!("expected 4 colors, found {}", colors.len()))?;
+    Ok(DmgPalette {
+        name: name.to_string(),
+        colors,
+    })
+}
+
+fn parse_color(text: &str) -> Result<Rgb, String> {
+    let hex = text.strip_prefix('#').unwrap_or(text);
+    let value = match hex.len() {
+        6 => u32::from_str_radix(hex, 16).ok(),
+        _ => None,
+    }
+    .ok_or_else(|| format!("invalid color {}", text))?;
+    Ok([(value >> 16) as u8, (value >> 8) as u8, value as u8])
+}
+
+pub fn load_palettes(path: &Path) -> Result<Vec<DmgPalette>, String> {
+    let text = fs::read_to_string(path).map_err(|error| error.to_string())?;
+    parse_palettes(&text)
+}
+
+/// How 15-bit CGB colors are turned into RGB
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorCorrection {
+    /// Each 5-bit channel is scaled to 8 bits, colors look brighter and more saturated
+    /// than on the console
+    #[default]
+    Raw,
+    /// The colors go through the response of the CGB LCD, channels bleed into each other
+    /// and white is not quite white
+    Lcd,
+}
+
+pub fn to_rgb(color: u16, correction: ColorCorrection) -> Rgb {
+    let (red, green, blue) = (
+        (color & 0x1f) as u32,
+        (color >> 5 & 0x1f) as u32,
+        (color >> 10 & 0x1f) as u32,
+    );
+    match correction {
+        ColorCorrection::Raw => {
+            let scale = |value: u32| (value << 3 | value >> 2) as u8;
+            [scale(red), scale(green), scale(blue)]
+        }
+        ColorCorrection::Lcd => {
+            // Each output channel mixes the three inputs, then saturates at 960 / 4
+            let curve = |value: u32| (value.min(960) >> 2) as u8;
+            [
+                curve(red * 26 + green * 4 + blue * 2),
+                curve(green * 24 + blue * 8),
+                curve(red * 6 + green * 4 + blue * 22),
+            ]
+        }
+    }
+}
+
+/// CGB colors of the DMG shades in BGP, OBP0 and OBP1, in DMG compatibility mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Colorization {
+    pub background: [u16; 4],
+    pub object_0: [u16; 4],
+    pub object_1: [u16; 4],
+}
+
+// https://gbdev.io/pandocs/Power_Up_Sequence.html#compatibility-palettes
+// The tables below are those of the CGB boot ROM
+
+/// Palettes of 4 colors the combinations pick from
+#[rustfmt::skip]
+const BOOT_PALETTES: [u16; 30 * 4] = [
+    0x7fff, 0x32bf, 0x00d0, 0x0000,
+    0x639f, 0x4279, 0x15b0, 0x04cb,
+    0x7fff, 0x6e31, 0x454a, 0x0000,
+    0x7fff, 0x1bef, 0x0200, 0x0000,
+    0x7fff, 0x421f, 0x1cf2, 0x0000,
+    0x7fff, 0x5294, 0x294a, 0x0000,
+    0x7fff, 0x03ff, 0x012f, 0x0000,
+    0x7fff, 0x03ef, 0x01d6, 0x0000,
+    0x7fff, 0x42b5, 0x3dc8, 0x0000,
+    0x7e74, 0x03ff, 0x0180, 0x0000,
+    0x67ff, 0x77ac, 0x1a13, 0x2d6b,
+    0x7ed6, 0x4bff, 0x2175, 0x0000,
+    0x53ff, 0x4a5f, 0x7e52, 0x0000,
+    0x4fff, 0x7ed2, 0x3a4c, 0x1ce0,
+    0x03ed, 0x7fff, 0x255f, 0x0000,
+    0x036a, 0x021f, 0x03ff, 0x7fff,
+    0x7fff, 0x01df, 0x0112, 0x0000,
+    0x231f, 0x035f, 0x00f2, 0x0009,
+    0x7fff, 0x03ea, 0x011f, 0x0000,
+    0x299f, 0x001a, 0x000c, 0x0000,
+    0x7fff, 0x027f, 0x001f, 0x0000,
+    0x7fff, 0x03e0, 0x0206, 0x0120,
+    0x7fff, 0x7eeb, 0x001f, 0x7c00,
+    0x7fff, 0x3fff, 0x7e00, 0x001f,
+    0x7fff, 0x03ff, 0x001f, 0x0000,
+    0x03ff, 0x001f, 0x000c, 0x0000,
+    0x7fff, 0x033f, 0x0193, 0x0000,
+    0x0000, 0x4200, 0x037f, 0x7fff,
+    0x7fff, 0x7e8c, 0x7c00, 0x0000,
+    0x7fff, 0x1bef, 0x6180, 0x0000,
+];
+
+/// Index in BOOT_PALETTES of the first color of OBJ0, OBJ1 and BG. Most combinations
+/// start on a palette but a few start in the middle of one.
+#[rustfmt::skip]
+const PALETTE_COMBINATIONS: [(usize, usize, usize); 51] = [
+    (4 * 4, 4 * 4, 29 * 4),
+    (18 * 4, 18 * 4, 18 * 4),
+    (20 * 4, 20 * 4, 20 * 4),
+    (24 * 4, 24 * 4, 24 * 4),
+    (9 * 4, 9 * 4, 9 * 4),
+    (0, 0, 0),
+    (27 * 4, 27 * 4, 27 * 4),
+    (5 * 4, 5 * 4, 5 * 4),
+    (12 * 4, 12 * 4, 12 * 4),
+    (26 * 4, 26 * 4, 26 * 4),
+    (16 * 4, 8 * 4, 8 * 4),
+    (4 * 4, 28 * 4, 28 * 4),
+    (4 * 4, 2 * 4, 2 * 4),
+    (3 * 4, 4 * 4, 4 * 4),
+    (4 * 4, 29 * 4, 29 * 4),
+    (28 * 4, 4 * 4, 28 * 4),
+    (2 * 4, 17 * 4, 2 * 4),
+    (16 * 4, 16 * 4, 8 * 4),
+    (4 * 4, 4 * 4, 7 * 4),
+    (4 * 4, 4 * 4, 18 * 4),
+    (4 * 4, 4 * 4, 20 * 4),
+    (19 * 4, 19 * 4, 9 * 4),
+    (4 * 4 - 1, 4 * 4 - 1, 11 * 4),
+    (17 * 4, 17 * 4, 2 * 4),
+    (4 * 4, 4 * 4, 2 * 4),
+    (4 * 4, 4 * 4, 3 * 4),
+    (28 * 4, 28 * 4, 0),
+    (3 * 4, 3 * 4, 0),
+    (0, 0, 4),
+    (18 * 4, 22 * 4, 18 * 4),
+    (20 * 4, 22 * 4, 20 * 4),
+    (24 * 4, 22 * 4, 24 * 4),
+    (16 * 4, 22 * 4, 8 * 4),
+    (17 * 4, 4 * 4, 13 * 4),
+    (28 * 4 - 1, 0, 14 * 4),
+    (28 * 4 - 1, 4 * 4, 15 * 4),
+    (19 * 4, 23 * 4 - 1, 9 * 4),
+    (16 * 4, 28 * 4, 10 * 4),
+    (4 * 4, 23 * 4, 28 * 4),
+    (17 * 4, 22 * 4, 2 * 4),
+    (4 * 4, 0, 2 * 4),
+    (4 * 4, 28 * 4, 3 * 4),
+    (28 * 4, 3 * 4, 0),
+    (3 * 4, 28 * 4, 4 * 4),
+    (21 * 4, 28 * 4, 4 * 4),
+    (3 * 4, 28 * 4, 0),
+    (25 * 4, 3 * 4, 28 * 4),
+    (0, 28 * 4, 8 * 4),
+    (4 * 4, 3 * 4, 28 * 4),
+    (28 * 4, 3 * 4, 6 * 4),
+    (4 * 4, 28 * 4, 29 * 4),
+];
+
+/// Sums of the title bytes the boot ROM knows, the titles from FIRST_SHARED_CHECKSUM
+/// share their checksum with another title and also need a matching 4th letter
+#[rustfmt::skip]
+const TITLE_CHECKSUMS: [u8; 93] = [
+    0x88, 0x16, 0x36, 0xd1, 0xdb, 0xf2, 0x3c, 0x8c, 0x92, 0x3d, 0x5c, 0x58, 0xc9, 0x3e, 0x70,
+    0x1d, 0x59, 0x69, 0x19, 0x35, 0xa8, 0x14, 0xaa, 0x75, 0x95, 0x99, 0x34, 0x6f, 0x15, 0xff,
+    0x97, 0x4b, 0x90, 0x17, 0x10, 0x39, 0xf7, 0xf6, 0xa2, 0x49, 0x4e, 0x43, 0x68, 0xe0, 0x8b,
+    0xf0, 0xce, 0x0c, 0x29, 0xe8, 0xb7, 0x86, 0x9a, 0x52, 0x01, 0x9d, 0x71, 0x9c, 0xbd, 0x5d,
+    0x6d, 0x67, 0x3f, 0x6b,
+    0xb3, 0x46, 0x28, 0xa5, 0xc6, 0xd3, 0x27, 0x61, 0x18, 0x66, 0x6a, 0xbf, 0x0d, 0xf4, 0xb3,
+    0x46, 0x28, 0xa5, 0xc6, 0xd3, 0x27, 0x61, 0x18, 0x66, 0x6a, 0xbf, 0x0d, 0xf4, 0xb3,
+];
+
+const FIRST_SHARED_CHECKSUM: usize = 64;
+
+/// 4th title letter of the entries of TITLE_CHECKSUMS from FIRST_SHARED_CHECKSUM
+const FOURTH_LETTERS: &[u8; 29] = b"BEFAARBEKEK R-URAR INAILICE R";
+
+/// Index in PALETTE_COMBINATIONS of each entry of TITLE_CHECKSUMS
+#[rustfmt::skip]
+const TITLE_COMBINATIONS: [usize; 93] = [
+    4, 5, 35, 34, 3, 31, 15, 10, 5, 19, 36, 7, 37, 30, 44, 21, 32, 31, 20, 5, 33, 13, 14, 5,
+    29, 5, 18, 9, 3, 2, 26, 25, 25, 41, 42, 26, 45, 42, 45, 36, 38, 26, 42, 30, 41, 34, 34, 5,
+    42, 6, 5, 33, 25, 42, 42, 40, 2, 16, 25, 42, 42, 5, 0, 39,
+    36, 22, 25, 6, 32, 12, 36, 11, 39, 18, 39, 24, 31, 50, 17, 46, 6, 27, 0, 47, 41, 41, 0, 0,
+    19, 34, 23, 18, 29,
+];
+
+const fn get_boot_palette(index: usize) -> [u16; 4] {
+    [
+        BOOT_PALETTES[index],
+        BOOT_PALETTES[index + 1],
+        BOOT_PALETTES[index + 2],
+        BOOT_PALETTES[index + 3],
+    ]
+}
+
+const fn get_combination(index: usize) -> Colorization {
+    let (object_0, object_1, background) = PALETTE_COMBINATIONS[index];
+    Colorization {
+        background: get_boot_palette(background),
+        object_0: get_boot_palette(object_0),
+        object_1: get_boot_palette(object_1),
+    }
+}
+
+/// Colors of cartridges unknown to the boot ROM or from another licensee than Nintendo
+pub const DEFAULT_COLORIZATION: Colorization = get_combination(0);
+
+/// Colors the CGB boot ROM picks for a DMG cartridge from the 16 bytes of its title
+/// (0134-0143), only Nintendo titles are looked up
+pub fn get_colorization(title: &[u8], nintendo_licensee: bool) -> Colorization {
+    if !nintendo_licensee {
+        return DEFAULT_COLORIZATION;
+    }
+    let checksum = title.iter().fold(0_u8, |sum, byte| sum.wrapping_add(*byte));
+    let fourth_letter = title.get(3).copied();
+    (0..TITLE_CHECKSUMS.len())
+        .find(|&index| {
+            TITLE_CHECKSUMS[index] == checksum
+                && (index < FIRST_SHARED_CHECKSUM
+                    || Some(FOURTH_LETTERS[index - FIRST_SHARED_CHECKSUM]) == fourth_letter)
+        })
+        .map_or(DEFAULT_COLORIZATION, |index| {
+            get_combination(TITLE_COMBINATIONS[index])
+        })
+}
@@ -2,22 +2,90 @@
 // http://bgb.bircd.org/pandocs.htm#videodisplay
 // https://blog.tigris.fr/2019/09/15/writing-an-emulator-the-first-pixel/
 
-mod screen;
-
-use screen::{Screen, SCREEN_HEIGHT, SCREEN_WIDTH};
-use std::cell::RefCell;
+use super::palette::Colorization;
 use std::collections::VecDeque;
-use std::rc::Rc;
-use std::{fmt, u16};
+use std::fmt;
+
+pub const SCREEN_WIDTH: usize = 160;
+pub const SCREEN_HEIGHT: usize = 144;
+/// The background map is 32x32 tiles of 8x8 pixels
+pub const BACKGROUND_MAP_SIZE: usize = 256;
+
+/// Clock cycles of a frame, 154 lines of 456 cycles
+pub const FRAME_CYCLES: u32 = 70224;
+const LINE_CYCLES: u16 = 456;
+/// Lines of a frame, the last 10 in VBlank
+const LINES: u8 = 154;
+
+/// OAM search checks one OAM entry every 2 clock cycles
+const OAM_SEARCH_CYCLES: u16 = 80;
+/// Only the first 10 sprites found on a line are shown
+const MAX_LINE_SPRITES: usize = 10;
+/// Pixel transfer starts with a tile fetch whose pixels are thrown away
+const FIRST_FETCH_CYCLES: u8 = 6;
+/// Pixel transfer stalls for 6 to 11 clock cycles while a sprite is fetched
+const SPRITE_FETCH_CYCLES: u8 = 6;
 
+/// Size of a VRAM bank, the CGB has 2
 const VRAM_SIZE: usize = 0x2000;
-pub const OAM_SIZE: usize = 0x9f;
+pub const OAM_SIZE: usize = 0xa0;
+/// 8 palettes of 4 colors of 2 bytes, for the background and for the sprites
+const PALETTE_RAM_SIZE: usize = 64;
+/// 15-bit color of a blank screen in CGB mode
+const WHITE: u16 = 0x7fff;
 const TILE_SIZE: u16 = 16;
 enum SpriteSize {
     Size8x8,
     Size8x16,
 }
 
+/// Hardware the PPU renders for, selected from the cartridge header
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Model {
+    #[default]
+    Dmg,
+    Cgb,
+    /// DMG cartridge on a CGB, set with KEY0: DMG rendering colored with the CGB palettes
+    DmgCompatibility,
+}
+
+/// An OAM entry found on the current line by OAM search
+#[derive(Clone, Copy)]
+struct Sprite {
+    y: u8,
+    x: u8,
+    tile: u8,
+    /// Bit 7 - OBJ-to-BG Priority (0=OBJ Above BG, 1=OBJ Behind BG color 1-3)
+    /// Bit 6 - Y flip
+    /// Bit 5 - X flip
+    /// Bit 4 - Palette number (0=OBP0, 1=OBP1), DMG only
+    /// Bit 3 - Tile VRAM bank, CGB only
+    /// Bit 0-2 - Palette number (OBP0-7), CGB only
+    flags: u8,
+    /// Position in OAM, the first sprites have priority on CGB
+    index: u8,
+}
+
+/// A background or window pixel waiting in the FIFO
+#[derive(Clone, Copy)]
+struct BackgroundPixel {
+    color: u8,
+    /// Palette from the tile attributes, CGB only
+    palette: u8,
+    /// Over the sprites from the tile attributes, CGB only
+    priority: bool,
+}
+
+/// A sprite pixel waiting in the sprite FIFO to be mixed with the background
+#[derive(Clone, Copy)]
+struct SpritePixel {
+    color: u8,
+    /// OBP0 or OBP1 on DMG, one of the 8 sprite palettes on CGB
+    palette: u8,
+    behind_background: bool,
+    index: u8,
+}
+
 #[derive(PartialEq, Debug)]
 enum State {
     OAMSearch,
@@ -45,23 +113,61 @@ impl fmt::Display for State {
 struct PixelFetcher {
     tick: u16,
     state: FetcherState,
+    /// Fetching the window instead of the background
+    window: bool,
+    /// Tiles fetched since the start of the line or of the window
     tile_index: u8,
-    map_addr: u16,
-    tile_line: u8,
     tile_id: u8,
-    pixel_data: [u8; 8],
-    fifo: VecDeque<u8>,
+    /// Tile map attributes from VRAM bank 1, CGB only:
+    /// Bit 7 - BG-to-OAM Priority (0=Use OAM priority bit, 1=BG Priority)
+    /// Bit 6 - Vertical Flip
+    /// Bit 5 - Horizontal Flip
+    /// Bit 3 - Tile VRAM Bank number
+    /// Bit 0-2 - Background Palette number (BGP0-7)
+    tile_attributes: u8,
+    data_low: u8,
+    data_high: u8,
+    fifo: VecDeque<BackgroundPixel>,
 }
 
-pub struct Ppu<'a> {
-    screen: Screen<'a>,
-    vram: [u8; VRAM_SIZE],
+pub struct Ppu {
+    model: Model,
+    /// Shades (0-3) of the LCD pixels, SCREEN_WIDTH pixels per line. In CGB mode the
+    /// color numbers (0-3) before the palettes are applied.
+    frame_buffer: [u8; SCREEN_WIDTH * SCREEN_HEIGHT],
+    /// 15-bit colors of the LCD pixels with the CGB palettes, see get_color_frame_buffer
+    color_frame_buffer: Vec<u16>,
+    frame_completed: bool,
+    hblank_started: bool,
+    vblank_started: bool,
+    /// The first frame after the LCD is turned on is not shown
+    skip_frame: bool,
+    /// Clock cycles since the start of the current frame while the LCD is off
+    lcd_off_cycles: u32,
+    vram: [u8; 2 * VRAM_SIZE],
+    /// VBK - VRAM bank seen by the CPU, CGB only
+    vram_bank: u8,
     oam: [u8; OAM_SIZE],
     tick: u16,
     x: u8,
     y: u8,
     state: State,
     fetcher: PixelFetcher,
+    /// Sprites of the current line in OAM order, until they are fetched
+    line_sprites: Vec<Sprite>,
+    sprite_fifo: VecDeque<SpritePixel>,
+    /// Clock cycles pixel transfer is stalled for, by the first fetch and sprite fetches
+    stall_cycles: u8,
+    /// Pixels shifted out without being shown, SCX & 7 at the start of a line
+    discarded_pixels: u8,
+    /// Last background or window tile counted in a sprite fetch penalty
+    penalty_tile: Option<(bool, i16)>,
+    /// WY matched LY on a line of the current frame
+    window_y_reached: bool,
+    /// Line of the window drawn next, only counts the lines where the window is shown
+    window_line: u8,
+    /// The STAT interrupt is requested on the rising edge of this line
+    stat_line: bool,
     /// Bit 7 - LCD Display Enable             (0=Off, 1=On)
     /// Bit 6 - Window Tile Map Display Select (0=9800-9BFF, 1=9C00-9FFF)
     /// Bit 5 - Window Display Enable          (0=Off, 1=On)
@@ -80,27 +186,59 @@ pub struct Ppu<'a> {
     object_palette_1_data: u8,
     window_y_position: u8,
     window_x_position_minus_7: u8,
+    /// BCPS and OCPS - Palette RAM address (bits 0-5), incremented on writes to
+    /// BCPD and OCPD when bit 7 is set
+    bg_palette_index: u8,
+    object_palette_index: u8,
+    bg_palette_ram: [u8; PALETTE_RAM_SIZE],
+    object_palette_ram: [u8; PALETTE_RAM_SIZE],
+    /// OPRI - Bit 0: sprite priority by X like on DMG instead of by OAM position
+    object_priority_mode: u8,
     v_blank_interrupt: bool,
     stat_interrupt: bool,
+    /// VRAM and OAM are locked while the PPU uses them, can be turned off for debugging
+    access_restrictions: bool,
 }
 
-impl fmt::Display for Ppu<'_> {
+impl fmt::Display for Ppu {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "X:{}, Y:{}, state:{}", self.x, self.y, self.state)
     }
 }
 
-impl Ppu<'_> {
-    pub fn new(sdl_context: Rc<RefCell<sdl2::Sdl>>) -> Ppu<'static> {
-        let mut ppu = Ppu {
-            screen: Screen::new(sdl_context),
-            vram: [(); VRAM_SIZE].map(|_| 0),
+impl Default for Ppu {
+    fn default() -> Ppu {
+        Ppu::new()
+    }
+}
+
+impl Ppu {
+    pub fn new() -> Ppu {
+        Ppu {
+            model: Model::Dmg,
+            frame_buffer: [0; SCREEN_WIDTH * SCREEN_HEIGHT],
+            color_frame_buffer: vec![WHITE; SCREEN_WIDTH * SCREEN_HEIGHT],
+            frame_completed: false,
+            hblank_started: false,
+            vblank_started: false,
+            skip_frame: false,
+            lcd_off_cycles: 0,
+            vram: [(); 2 * VRAM_SIZE].map(|_| 0),
+            vram_bank: 0,
             oam: [0; OAM_SIZE],
             tick: 0,
             fetcher: PixelFetcher::default(),
+            line_sprites: Vec::with_capacity(MAX_LINE_SPRITES),
+            sprite_fifo: VecDeque::with_capacity(8),
+            stall_cycles: 0,
+            discarded_pixels: 0,
+            penalty_tile: None,
+            window_y_reached: false,
+            window_line: 0,
+            stat_line: false,
             x: 0,
             y: 0,
-            state: State::OAMSearch,
+            state: State::HBlank,
             lcd_control: 0,
             lcd_status: 0,
             scroll_x: 0,
@@ -111,11 +249,15 @@ impl Ppu<'_> {
             object_palette_1_data: 0,
             window_y_position: 0,
             window_x_position_minus_7: 0,
+            bg_palette_index: 0,
+            object_palette_index: 0,
+            bg_palette_ram: [0; PALETTE_RAM_SIZE],
+            object_palette_ram: [0; PALETTE_RAM_SIZE],
+            object_priority_mode: 0,
             v_blank_interrupt: false,
             stat_interrupt: false,
-        };
-        ppu.screen.start();
-        ppu
+            access_restrictions: true,
+        }
     }
 
     pub fn is_vblank_interrupted(&mut self) -> bool {
@@ -130,71 +272,396 @@ impl Ppu<'_> {
         value
     }
 
+    /// Same as is_vblank_interrupted without clearing the request
+    pub fn has_vblank_interrupt(&self) -> bool {
+        self.v_blank_interrupt
+    }
+
+    pub fn has_stat_interrupt(&self) -> bool {
+        self.stat_interrupt
+    }
+
+    /// True once per frame, when the PPU enters VBlank. While the LCD is off the frames
+    /// go on, blank, so that the screen keeps being refreshed.
+    pub fn is_frame_completed(&mut self) -> bool {
+        let value = self.frame_completed;
+        self.frame_completed = false;
+        value
+    }
+
+    pub fn get_frame_buffer(&self) -> &[u8] {
+        &self.frame_buffer
+    }
+
+    /// True once per visible line, when the PPU enters HBlank
+    pub fn is_hblank_started(&mut self) -> bool {
+        let value = self.hblank_started;
+        self.hblank_started = false;
+        value
+    }
+
+    /// True once per frame shown, when the PPU enters VBlank
+    pub fn is_vblank_started(&mut self) -> bool {
+        let value = self.vblank_started;
+        self.vblank_started = false;
+        value
+    }
+
+    /// 15-bit colors (bits 0-4 red, 5-9 green, 10-14 blue) of the last frame,
+    /// None on DMG where get_frame_buffer has the shades
+    pub fn get_color_frame_buffer(&self) -> Option<&[u16]> {
+        match self.model {
+            Model::Dmg => None,
+            _ => Some(&self.color_frame_buffer),
+        }
+    }
+
+    pub fn get_model(&self) -> Model {
+        self.model
+    }
+
+    /// 15-bit color of a CGB background palette (0-7)
+    pub fn get_bg_color(&self, palette: u8, color: u8) -> u16 {
+        get_color(&self.bg_palette_ram, palette, color)
+    }
+
+    /// 15-bit color of a CGB object palette (0-7)
+    pub fn get_object_color(&self, palette: u8, color: u8) -> u16 {
+        get_color(&self.object_palette_ram, palette, color)
+    }
+
+    pub fn set_model(&mut self, model: Model) {
+        self.model = model;
+        if model != Model::Cgb {
+            self.vram_bank = 0;
+        }
+    }
+
+    /// VRAM bank mapped at 8000-9fff for the CPU
+    pub fn get_vram_bank(&self) -> usize {
+        self.vram_bank as usize
+    }
+
+    pub fn get_vram_bank_count(&self) -> usize {
+        match self.model {
+            Model::Dmg => 1,
+            _ => 2,
+        }
+    }
+
+    /// BG palette RAM as set by the CGB boot ROM for CGB cartridges: all white
+    pub fn reset_palettes(&mut self) {
+        for color in self.bg_palette_ram.chunks_mut(2) {
+            color.copy_from_slice(&WHITE.to_le_bytes());
+        }
+        self.object_palette_ram = self.bg_palette_ram;
+    }
+
+    /// Colors of the DMG shades in DMG compatibility mode, BG palette 0 for BGP and OBJ
+    /// palettes 0 and 1 for OBP0 and OBP1
+    pub fn set_colorization(&mut self, colorization: &Colorization) {
+        let write = |palette_ram: &mut [u8], colors: &[u16; 4]| {
+            for (color, value) in palette_ram.chunks_mut(2).zip(colors) {
+                color.copy_from_slice(&value.to_le_bytes());
+            }
+        };
+        write(&mut self.bg_palette_ram[..8], &colorization.background);
+        write(&mut self.object_palette_ram[..8], &colorization.object_0);
+        write(&mut self.object_palette_ram[8..16], &colorization.object_1);
+    }
+
     // https://blog.tigris.fr/2019/09/15/writing-an-emulator-the-first-pixel/
+    // https://gbdev.io/pandocs/Rendering.html
     pub fn next(&mut self) {
+        if !self.is_lcd_display_enabled() {
+            self.lcd_off_cycles += 1;
+            if self.lcd_off_cycles == FRAME_CYCLES {
+                self.lcd_off_cycles = 0;
+                self.frame_completed = true;
+            }
+            return;
+        }
         self.tick += 1;
 
         match self.state {
             State::OAMSearch => {
-                if self.tick == 40 {
-                    self.state = State::PixelTransfer;
-                    self.x = 0;
-                    let tile_line = self.y % 8;
-                    let tile_map_base_addr = self.get_bg_tile_map_display_selected();
-                    let tile_map_row_addr = tile_map_base_addr + ((self.y as u16 / 8) * 32);
-                    self.fetcher_start(tile_map_row_addr, tile_line);
+                if self.tick.is_multiple_of(2) {
+                    self.search_oam(self.tick as usize / 2 - 1);
+                }
+                if self.tick == OAM_SEARCH_CYCLES {
+                    self.start_pixel_transfer();
                 }
             }
             State::PixelTransfer => {
-                self.fetcher_next();
-
-                if self.fetcher.fifo.len() > 0 {
-                    let pixel_color = self.fetcher.fifo.pop_front().unwrap();
-                    self.screen.set_pixel(self.x, self.y, pixel_color);
-                    self.x += 1;
-                }
-                if self.x == 160 {
+                self.pixel_transfer_next();
+                // Pixel transfer lasts from 172 to 289 clock cycles, HBlank gets the rest
+                if self.x as usize == SCREEN_WIDTH {
                     self.state = State::HBlank;
+                    self.hblank_started = true;
+                    if self.fetcher.window {
+                        self.window_line += 1;
+                    }
                 }
             }
             State::HBlank => {
-                if self.tick == 456 {
+                if self.tick == LINE_CYCLES {
                     self.y += 1;
                     self.tick = 0;
-                    if self.y == 144 {
+                    if self.y as usize == SCREEN_HEIGHT {
                         self.state = State::VBlank;
                         self.v_blank_interrupt = true;
+                        self.frame_completed = true;
+                        if self.skip_frame {
+                            self.skip_frame = false;
+                            self.frame_buffer.fill(0);
+                            self.color_frame_buffer.fill(WHITE);
+                        }
+                        self.vblank_started = true;
                     } else {
-                        self.state = State::OAMSearch;
+                        self.start_line();
                     }
                 }
             }
             State::VBlank => {
-                if self.tick == 456 {
+                if self.tick == LINE_CYCLES {
                     self.y += 1;
                     self.tick = 0;
+                    if self.y == LINES {
+                        self.start_frame();
+                    }
                 }
-                if self.y == 153 {
-                    self.state = State::OAMSearch;
-                    self.y = 0;
-                    println!("Updating screen");
-                    self.display_background_map();
-                    self.screen.present(self.scroll_x, self.scroll_y);
+            }
+        }
+        self.update_stat_line();
+    }
+
+    fn start_frame(&mut self) {
+        self.y = 0;
+        self.window_y_reached = false;
+        self.window_line = 0;
+        self.start_line();
+    }
+
+    /// OAM search of a new line, the window shows from the line where WY matches LY
+    fn start_line(&mut self) {
+        self.state = State::OAMSearch;
+        self.line_sprites.clear();
+        if self.y == self.window_y_position {
+            self.window_y_reached = true;
+        }
+    }
+
+    /// Keep the sprite of an OAM entry if it is on the current line
+    fn search_oam(&mut self, index: usize) {
+        if self.line_sprites.len() == MAX_LINE_SPRITES {
+            return;
+        }
+        let entry = &self.oam[index * 4..index * 4 + 4];
+        let sprite = Sprite {
+            y: entry[0],
+            x: entry[1],
+            tile: entry[2],
+            flags: entry[3],
+            index: index as u8,
+        };
+        // Sprite positions are offset by 16 lines, to hide them above the screen
+        let line = self.y as u16 + 16;
+        let top = sprite.y as u16;
+        if line >= top && line < top + self.get_sprite_height() as u16 {
+            self.line_sprites.push(sprite);
+        }
+    }
+
+    fn start_pixel_transfer(&mut self) {
+        self.state = State::PixelTransfer;
+        self.x = 0;
+        self.stall_cycles = FIRST_FETCH_CYCLES;
+        self.discarded_pixels = self.scroll_x & 0x7;
+        self.penalty_tile = None;
+        self.sprite_fifo.clear();
+        self.fetcher_start(false);
+    }
+
+    /// One clock cycle of pixel transfer: a pixel is shifted out to the LCD unless the
+    /// FIFO is empty or a fetch stalls it, then the fetcher goes on filling the FIFO
+    fn pixel_transfer_next(&mut self) {
+        if self.stall_cycles > 0 {
+            self.stall_cycles -= 1;
+            return;
+        }
+
+        if self.is_window_starting() {
+            // The background pixels are thrown away and the fetcher starts over
+            self.fetcher_start(true);
+            // The window is cut on the left when WX < 7
+            self.discarded_pixels = 7_u8.saturating_sub(self.window_x_position_minus_7);
+        }
+
+        if self.is_sprite_obj_enabled()
+            && self.discarded_pixels == 0
+            && !self.fetcher.fifo.is_empty()
+        {
+            let x = self.x + 8;
+            if let Some(index) = self.line_sprites.iter().position(|sprite| sprite.x <= x) {
+                let sprite = self.line_sprites.remove(index);
+                self.fetch_sprite(&sprite);
+                // This clock cycle is the first of the penalty
+                self.stall_cycles = self.get_sprite_penalty(&sprite) - 1;
+                return;
+            }
+        }
+
+        if let Some(pixel) = self.fetcher.fifo.pop_front() {
+            if self.discarded_pixels > 0 {
+                self.discarded_pixels -= 1;
+            } else {
+                let sprite_pixel = self.sprite_fifo.pop_front();
+                self.output_pixel(pixel, sprite_pixel);
+                self.x += 1;
+            }
+        }
+        self.fetcher_next();
+    }
+
+    fn is_window_starting(&self) -> bool {
+        !self.fetcher.window
+            && self.is_window_display_enabled()
+            && self.window_y_reached
+            && self.window_x_position_minus_7 <= 166
+            && self.x as u16 + 7 >= self.window_x_position_minus_7 as u16
+    }
+
+    /// Clock cycles of a sprite fetch, 6 plus the pixels of the background or window tile
+    /// under the left of the sprite that are right of it, minus 2. Only the first sprite
+    /// over a tile waits for it.
+    fn get_sprite_penalty(&mut self, sprite: &Sprite) -> u8 {
+        if sprite.x == 0 {
+            return SPRITE_FETCH_CYCLES + 5;
+        }
+        let left = if self.fetcher.window {
+            sprite.x as i16 - 1 - self.window_x_position_minus_7 as i16
+        } else {
+            sprite.x as i16 - 8 + self.scroll_x as i16
+        };
+        let tile = (self.fetcher.window, left.div_euclid(8));
+        if self.penalty_tile == Some(tile) {
+            return SPRITE_FETCH_CYCLES;
+        }
+        self.penalty_tile = Some(tile);
+        SPRITE_FETCH_CYCLES + (5 - left.rem_euclid(8)).max(0) as u8
+    }
+
+    /// Mix the line of a sprite in the sprite FIFO. Pixels already there belong to
+    /// sprites with a lower X or first in OAM and win over non-transparent ones,
+    /// unless sprites have priority by OAM position only like in CGB mode.
+    fn fetch_sprite(&mut self, sprite: &Sprite) {
+        let height = self.get_sprite_height();
+        let mut line = (self.y + 16).wrapping_sub(sprite.y) % height;
+        if sprite.flags & 0x40 != 0 {
+            line = height - 1 - line;
+        }
+        // 8x16 sprites use an even tile on top and the next one at the bottom
+        let tile = if height == 16 {
+            sprite.tile & 0xfe
+        } else {
+            sprite.tile
+        };
+        let (bank, palette) = if self.model == Model::Cgb {
+            ((sprite.flags >> 3 & 0x1) as usize, sprite.flags & 0x7)
+        } else {
+            (0, sprite.flags >> 4 & 0x1)
+        };
+        let address = bank * VRAM_SIZE + tile as usize * TILE_SIZE as usize + line as usize * 2;
+        let (low, high) = (self.vram[address], self.vram[address + 1]);
+        let by_index = self.model == Model::Cgb && self.object_priority_mode & 0x1 == 0;
+
+        // Pixels left of the screen are not shown
+        let hidden = (self.x + 8 - sprite.x) as usize;
+        for i in hidden..8 {
+            let bit = if sprite.flags & 0x20 != 0 { i } else { 7 - i };
+            let pixel = SpritePixel {
+                color: (high >> bit & 0x1) << 1 | (low >> bit & 0x1),
+                palette,
+                behind_background: sprite.flags & 0x80 != 0,
+                index: sprite.index,
+            };
+            match self.sprite_fifo.get_mut(i - hidden) {
+                Some(current) if current.color == 0 => *current = pixel,
+                Some(current) if by_index && pixel.color != 0 && pixel.index < current.index => {
+                    *current = pixel
                 }
+                Some(_) => (),
+                None => self.sprite_fifo.push_back(pixel),
+            }
+        }
+    }
+
+    /// Mix the background and sprite pixels at x. The sprite pixel shows unless it is
+    /// transparent or behind a background color other than 0.
+    fn output_pixel(&mut self, pixel: BackgroundPixel, sprite_pixel: Option<SpritePixel>) {
+        let offset = self.x as usize + self.y as usize * SCREEN_WIDTH;
+        if self.model == Model::Cgb {
+            // LCDC bit 0 takes the priority away from the background instead of hiding it
+            let sprite_pixel = sprite_pixel.filter(|sprite| {
+                sprite.color != 0
+                    && (!self.is_bg_enabled()
+                        || pixel.color == 0
+                        || !(pixel.priority || sprite.behind_background))
+            });
+            let (color, palette_ram, palette) = match sprite_pixel {
+                Some(sprite) => (sprite.color, &self.object_palette_ram, sprite.palette),
+                None => (pixel.color, &self.bg_palette_ram, pixel.palette),
+            };
+            self.frame_buffer[offset] = color;
+            self.color_frame_buffer[offset] = get_color(palette_ram, palette, color);
+            return;
+        }
+
+        // The background and the window are blank when disabled
+        let color = if self.is_bg_enabled() { pixel.color } else { 0 };
+        let sprite_pixel = sprite_pixel
+            .filter(|sprite| sprite.color != 0 && !(sprite.behind_background && color != 0));
+        let (shade, palette_ram, palette) = match sprite_pixel {
+            Some(sprite) => {
+                let palette = if sprite.palette == 1 {
+                    self.object_palette_1_data
+                } else {
+                    self.object_palette_0_data
+                };
+                (
+                    get_shade(palette, sprite.color),
+                    &self.object_palette_ram,
+                    sprite.palette,
+                )
             }
+            None => (
+                get_shade(self.bg_palette_data, color),
+                &self.bg_palette_ram,
+                0,
+            ),
+        };
+        self.frame_buffer[offset] = shade;
+        if self.model == Model::DmgCompatibility {
+            // The shades pick the colors of the first CGB palettes
+            self.color_frame_buffer[offset] = get_color(palette_ram, palette, shade);
         }
     }
 
-    fn fetcher_start(&mut self, map_addr: u16, tile_line: u8) {
+    fn fetcher_start(&mut self, window: bool) {
+        self.fetcher.window = window;
         self.fetcher.tile_index = 0;
-        self.fetcher.map_addr = map_addr;
-        self.fetcher.tile_line = tile_line;
         self.fetcher.tick = 0;
         self.fetcher.state = FetcherState::ReadTileID;
         self.fetcher.fifo.clear();
     }
 
     fn fetcher_next(&mut self) {
+        if let FetcherState::PushToFIFO = self.fetcher.state {
+            // Pushing is retried every clock cycle until the FIFO has room
+            self.fetcher_push();
+            return;
+        }
+
         // The Fetcher runs at half the speed of the PPU (every 2 clock cycles).
         self.fetcher.tick += 1;
         if self.fetcher.tick < 2 {
@@ -204,98 +671,160 @@ impl Ppu<'_> {
 
         match self.fetcher.state {
             FetcherState::ReadTileID => {
-                self.fetcher.tile_id =
-                    self.vram[self.fetcher.map_addr as usize + self.fetcher.tile_index as usize];
+                let address = self.get_fetcher_map_address();
+                self.fetcher.tile_id = self.vram[address];
+                self.fetcher.tile_attributes = match self.model {
+                    Model::Cgb => self.vram[VRAM_SIZE + address],
+                    _ => 0,
+                };
                 self.fetcher.state = FetcherState::ReadTileData0;
             }
 
             FetcherState::ReadTileData0 => {
-                let offset = self.get_bg_and_window_tile_data_selected()
-                    + self.fetcher.tile_id as u16 * TILE_SIZE;
-
-                let addr = offset + self.fetcher.tile_line as u16 * 2;
-
-                let data = self.vram[addr as usize];
-
-                for bitpos in 0..8 {
-                    self.fetcher.pixel_data[bitpos] = (data >> bitpos) & 0x01;
-                }
-
+                self.fetcher.data_low = self.vram[self.get_fetcher_data_address()];
                 self.fetcher.state = FetcherState::ReadTileData1;
             }
 
             FetcherState::ReadTileData1 => {
-                let offset = self.get_bg_and_window_tile_data_selected()
-                    + self.fetcher.tile_id as u16 * TILE_SIZE;
-
-                let addr = offset + self.fetcher.tile_line as u16 * 2;
-
-                let data = self.vram[addr as usize + 1];
-
-                for bitpos in 0..8 {
-                    self.fetcher.pixel_data[bitpos] = (data >> bitpos) & 0x01;
-                }
+                self.fetcher.data_high = self.vram[self.get_fetcher_data_address() + 1];
                 self.fetcher.state = FetcherState::PushToFIFO;
+                self.fetcher_push();
             }
 
-            FetcherState::PushToFIFO => {
-                for i in 0..8 {
-                    self.fetcher.fifo.push_back(self.fetcher.pixel_data[7 - i]);
-                }
-                self.fetcher.tile_index += 1;
-                self.fetcher.state = FetcherState::ReadTileID;
-            }
+            FetcherState::PushToFIFO => (),
         }
     }
 
-    fn display_background_map(&mut self) {
-        let base_address = self.get_bg_tile_map_display_selected() as usize;
-
-        for y in 0..32 {
-            for x in 0..32 {
-                let tile_number = self.vram[base_address + 32 * y + x] as usize;
-                let tile_data_base_address = self.get_bg_and_window_tile_data_selected() as usize;
-                let tile_data_address = tile_data_base_address + 16 * tile_number;
-                for x_tile in 0..2 {
-                    for y_tile in 0..8 {
-                        // let go trough tile
-                        let byte = self.vram[tile_data_address + y_tile * 2 + x_tile];
-                        for j in 0..4 {
-                            // let go trough bit pairs in byte
-                            let color = (byte >> ((j * 4) * 2)) & 0x03;
+    /// Push the 8 pixels of the fetched tile line once the FIFO holds 8 pixels or less
+    fn fetcher_push(&mut self) {
+        if self.fetcher.fifo.len() > 8 {
+            return;
+        }
+        let (low, high) = (self.fetcher.data_low, self.fetcher.data_high);
+        let attributes = self.fetcher.tile_attributes;
+        for i in 0..8 {
+            let bit = if attributes & 0x20 != 0 { i } else { 7 - i };
+            self.fetcher.fifo.push_back(BackgroundPixel {
+                color: (high >> bit & 0x1) << 1 | (low >> bit & 0x1),
+                palette: attributes & 0x7,
+                priority: attributes & 0x80 != 0,
+            });
+        }
+        self.fetcher.tile_index = self.fetcher.tile_index.wrapping_add(1);
+        self.fetcher.state = FetcherState::ReadTileID;
+    }
 
-                            let x_pos = x * 8 + x_tile * 2 + j;
-                            let y_pos = y * 8 + y_tile;
+    /// Tile map entry of the fetched tile, SCX and SCY are read again for every tile
+    fn get_fetcher_map_address(&self) -> usize {
+        let (map, column, row) = if self.fetcher.window {
+            (
+                self.get_window_tile_map_display_selected(),
+                self.fetcher.tile_index,
+                self.window_line,
+            )
+        } else {
+            (
+                self.get_bg_tile_map_display_selected(),
+                (self.scroll_x >> 3).wrapping_add(self.fetcher.tile_index),
+                self.y.wrapping_add(self.scroll_y),
+            )
+        };
+        map as usize + row as usize / 8 * 32 + (column & 0x1f) as usize
+    }
 
-                            self.screen
-                                .set_pixel(x_pos as u8, y_pos as u8, (color % 4) as u8);
-                        }
-                    }
-                }
-            }
+    /// Low byte of the fetched tile line
+    fn get_fetcher_data_address(&self) -> usize {
+        let attributes = self.fetcher.tile_attributes;
+        let mut line = if self.fetcher.window {
+            self.window_line
+        } else {
+            self.y.wrapping_add(self.scroll_y)
+        } % 8;
+        if attributes & 0x40 != 0 {
+            line = 7 - line;
         }
+        let bank = (attributes >> 3 & 0x1) as usize;
+        bank * VRAM_SIZE + self.get_tile_data_address(self.fetcher.tile_id) + line as usize * 2
+    }
+
+    pub fn set_access_restrictions(&mut self, enabled: bool) {
+        self.access_restrictions = enabled;
     }
 
+    /// VRAM is locked during pixel transfer, unless the LCD is off
+    fn is_vram_accessible(&self) -> bool {
+        !self.access_restrictions
+            || !self.is_lcd_display_enabled()
+            || self.state != State::PixelTransfer
+    }
+
+    /// OAM is locked during OAM search and pixel transfer, unless the LCD is off
+    fn is_oam_accessible(&self) -> bool {
+        !self.access_restrictions
+            || !self.is_lcd_display_enabled()
+            || !matches!(self.state, State::OAMSearch | State::PixelTransfer)
+    }
+
+    /// CPU read from the bank selected in VBK, 0xff while VRAM is locked
     pub fn read_vram(&self, address: usize) -> u8 {
-        self.vram[address]
+        if self.is_vram_accessible() {
+            self.vram[self.get_vram_bank() * VRAM_SIZE + address]
+        } else {
+            0xff
+        }
     }
 
+    /// CPU write to the bank selected in VBK, dropped while VRAM is locked
     pub fn write_vram(&mut self, address: usize, value: u8) {
-        self.vram[address] = value;
+        if self.is_vram_accessible() {
+            self.vram[self.get_vram_bank() * VRAM_SIZE + address] = value;
+        }
     }
 
+    /// CPU read, 0xff while OAM is locked
     pub fn read_oam(&self, address: usize) -> u8 {
-        self.oam[address]
+        if self.is_oam_accessible() {
+            self.oam[address]
+        } else {
+            0xff
+        }
     }
 
+    /// CPU write, dropped while OAM is locked
     pub fn write_oam(&mut self, address: usize, value: u8) {
+        if self.is_oam_accessible() {
+            self.oam[address] = value;
+        }
+    }
+
+    /// Read regardless of the mode, for debugging
+    pub fn peek_vram(&self, bank: usize, address: usize) -> u8 {
+        self.vram[bank * VRAM_SIZE + address]
+    }
+
+    /// Write regardless of the mode, for debugging
+    pub fn poke_vram(&mut self, bank: usize, address: usize, value: u8) {
+        self.vram[bank * VRAM_SIZE + address] = value;
+    }
+
+    /// Read regardless of the mode, for debugging
+    pub fn peek_oam(&self, address: usize) -> u8 {
+        self.oam[address]
+    }
+
+    /// Write regardless of the mode, for debugging and OAM DMA
+    pub fn poke_oam(&mut self, address: usize, value: u8) {
         self.oam[address] = value;
     }
 
     pub fn read_registers(&self, address: u16) -> u8 {
         match address {
             0xff40 => self.lcd_control,
-            0xff41 => self.lcd_status,
+            0xff41 => {
+                0x80 | self.lcd_status
+                    | (self.get_lyc_ly_coincidence() as u8) << 2
+                    | self.get_state()
+            }
             0xff42 => self.scroll_y,
             0xff43 => self.scroll_x,
             0xff44 => self.y,
@@ -305,35 +834,130 @@ impl Ppu<'_> {
             0xff49 => self.object_palette_1_data,
             0xff4a => self.window_y_position,
             0xff4b => self.window_x_position_minus_7,
+            0xff4f => 0xfe | self.vram_bank,
+            0xff68 => 0x40 | self.bg_palette_index,
+            0xff69 => self.read_palette_data(&self.bg_palette_ram, self.bg_palette_index),
+            0xff6a => 0x40 | self.object_palette_index,
+            0xff6b => self.read_palette_data(&self.object_palette_ram, self.object_palette_index),
+            0xff6c => 0xfe | self.object_priority_mode,
             _ => panic!("Not a PPU register address or write-only"),
         }
     }
 
     pub fn write_registers(&mut self, address: u16, value: u8) {
         match address {
-            0xff40 => self.lcd_control = value,
+            0xff40 => self.set_lcd_control(value),
             0xff41 => {
-                self.lcd_status =
-                    (value & 0xfb) | (self.get_lyc_ly_coincidence() as u8) << 2 | self.get_state()
+                // The mode and the coincidence flag are read only
+                self.lcd_status = value & 0x78;
+                self.update_stat_line();
             }
             0xff42 => self.scroll_y = value,
             0xff43 => self.scroll_x = value,
-            0xff45 => self.lyc = value,
+            0xff45 => {
+                self.lyc = value;
+                self.update_stat_line();
+            }
             0xff47 => self.bg_palette_data = value,
             0xff48 => self.object_palette_0_data = value,
             0xff49 => self.object_palette_1_data = value,
             0xff4a => self.window_y_position = value,
             0xff4b => self.window_x_position_minus_7 = value,
+            0xff4f => self.vram_bank = value & 0x1,
+            0xff68 => self.bg_palette_index = value & 0xbf,
+            0xff69 => {
+                if self.is_palette_ram_accessible() {
+                    self.bg_palette_ram[(self.bg_palette_index & 0x3f) as usize] = value;
+                }
+                self.bg_palette_index = get_next_palette_index(self.bg_palette_index);
+            }
+            0xff6a => self.object_palette_index = value & 0xbf,
+            0xff6b => {
+                if self.is_palette_ram_accessible() {
+                    self.object_palette_ram[(self.object_palette_index & 0x3f) as usize] = value;
+                }
+                self.object_palette_index = get_next_palette_index(self.object_palette_index);
+            }
+            0xff6c => self.object_priority_mode = value & 0x1,
             _ => panic!("Not a PPU register address or read-only"),
         }
     }
 
-    fn get_lyc_ly_coincidence(&self) -> bool {
-        if (self.lcd_status >> 6) & 0x1 == 0x1 {
-            self.lyc == self.y
+    /// Registers set without their side effects, for debugging tools: no STAT interrupt,
+    /// palette RAM written even when locked and without incrementing the index. LCDC
+    /// writes turning the LCD on or off are ignored.
+    pub fn poke_registers(&mut self, address: u16, value: u8) {
+        match address {
+            0xff40 if (value ^ self.lcd_control) & 0x80 == 0 => self.lcd_control = value,
+            0xff40 => (),
+            0xff41 => self.lcd_status = value & 0x78,
+            0xff45 => self.lyc = value,
+            0xff69 => self.bg_palette_ram[(self.bg_palette_index & 0x3f) as usize] = value,
+            0xff6b => self.object_palette_ram[(self.object_palette_index & 0x3f) as usize] = value,
+            _ => self.write_registers(address, value),
+        }
+    }
+
+    /// Palette RAM is locked during pixel transfer like VRAM
+    fn is_palette_ram_accessible(&self) -> bool {
+        self.is_vram_accessible()
+    }
+
+    /// BCPD or OCPD, 0xff while palette RAM is locked
+    fn read_palette_data(&self, palette_ram: &[u8; PALETTE_RAM_SIZE], index: u8) -> u8 {
+        if self.is_palette_ram_accessible() {
+            palette_ram[(index & 0x3f) as usize]
         } else {
-            self.lyc != self.y
+            0xff
+        }
+    }
+
+    /// Turning the LCD off stops the PPU at the start of line 0 in mode 0 with a blank
+    /// screen, turning it on restarts it from there
+    fn set_lcd_control(&mut self, value: u8) {
+        let was_enabled = self.is_lcd_display_enabled();
+        self.lcd_control = value;
+        match (was_enabled, self.is_lcd_display_enabled()) {
+            (true, false) => {
+                self.y = 0;
+                self.x = 0;
+                self.tick = 0;
+                self.state = State::HBlank;
+                self.lcd_off_cycles = 0;
+                self.stat_line = false;
+                self.frame_buffer.fill(0);
+                self.color_frame_buffer.fill(WHITE);
+                self.frame_completed = true;
+            }
+            (false, true) => {
+                self.start_frame();
+                self.skip_frame = true;
+            }
+            _ => (),
+        }
+    }
+
+    fn get_lyc_ly_coincidence(&self) -> bool {
+        self.lyc == self.y
+    }
+
+    /// The STAT interrupt is requested when one of the sources selected in STAT becomes
+    /// active while none was, so sources active back to back only request it once
+    fn update_stat_line(&mut self) {
+        if !self.is_lcd_display_enabled() {
+            return;
         }
+        let line = (self.lcd_status & 0x40 != 0 && self.get_lyc_ly_coincidence())
+            || match self.state {
+                State::HBlank => self.lcd_status & 0x08 != 0,
+                State::VBlank => self.lcd_status & 0x10 != 0,
+                State::OAMSearch => self.lcd_status & 0x20 != 0,
+                State::PixelTransfer => false,
+            };
+        if line && !self.stat_line {
+            self.stat_interrupt = true;
+        }
+        self.stat_line = line;
     }
 
     fn get_state(&self) -> u8 {
@@ -345,10 +969,6 @@ impl Ppu<'_> {
         }
     }
 
-    pub fn set_oam(&mut self, data: [u8; OAM_SIZE]) {
-        self.oam = data.clone();
-    }
-
     fn is_lcd_display_enabled(&self) -> bool {
         (self.lcd_control >> 7) == 0x01
     }
@@ -365,11 +985,14 @@ impl Ppu<'_> {
         (self.lcd_control >> 5) & 0x1 == 0x1
     }
 
-    fn get_bg_and_window_tile_data_selected(&self) -> u16 {
+    /// Tile data of a background or window tile, the 8000 addressing mode uses unsigned
+    /// tile numbers and the 8800 one signed tile numbers from 9000
+    fn get_tile_data_address(&self, tile_id: u8) -> usize {
         if (self.lcd_control >> 4) & 0x1 == 0x1 {
-            0x8000 - 0x8000 // VRAM Offset substracted
+            tile_id as usize * TILE_SIZE as usize
         } else {
-            0x8800 - 0x8000 // VRAM Offset substracted
+            // VRAM Offset substracted
+            (0x1000 + tile_id as i8 as i32 * TILE_SIZE as i32) as usize
         }
     }
 
@@ -389,6 +1012,13 @@ impl Ppu<'_> {
         }
     }
 
+    fn get_sprite_height(&self) -> u8 {
+        match self.get_sprite_obj_size() {
+            SpriteSize::Size8x8 => 8,
+            SpriteSize::Size8x16 => 16,
+        }
+    }
+
     fn is_sprite_obj_enabled(&self) -> bool {
         (self.lcd_control >> 1) & 0x1 == 0x1
     }
@@ -397,3 +1027,24 @@ impl Ppu<'_> {
         self.lcd_control & 0x1 == 0x1
     }
 }
+
+/// Shade (0-3) of a color number in BGP, OBP0 or OBP1
+fn get_shade(palette: u8, color: u8) -> u8 {
+    (palette >> (color * 2)) & 0x3
+}
+
+/// 15-bit color of a color number in one of the 8 palettes of a palette RAM
+fn get_color(palette_ram: &[u8; PALETTE_RAM_SIZE], palette: u8, color: u8) -> u16 {
+    let offset = (palette * 8 + color * 2) as usize;
+    u16::from_le_bytes([palette_ram[offset], palette_ram[offset + 1]]) & 0x7fff
+}
+
+/// BCPS or OCPS after an access to BCPD or OCPD, the address wraps around when
+/// auto-increment is on
+fn get_next_palette_index(index: u8) -> u8 {
+    if index & 0x80 != 0 {
+        0x80 | (index + 1) & 0x3f
+    } else {
+        index
+    }
+}
@@ -1,3 +1,6 @@
+use super::bus::sgb::{BORDER_HEIGHT, BORDER_WIDTH};
+use super::palette::{self, ColorCorrection, Rgb};
+use super::ppu::{SCREEN_HEIGHT, SCREEN_WIDTH};
 use sdl2::pixels::{Color, Palette, PixelFormatEnum};
 use sdl2::render::Canvas;
 use sdl2::surface::Surface;
@@ -5,14 +8,19 @@ use sdl2::video::Window;
 use std::cell::RefCell;
 use std::rc::Rc;
 
-pub const SCREEN_WIDTH: u32 = 256;
-pub const SCREEN_HEIGHT: u32 = 256;
+const WINDOW_WIDTH: u32 = SCREEN_WIDTH as u32;
+const WINDOW_HEIGHT: u32 = SCREEN_HEIGHT as u32;
 
 pub const TEXTURE_ASPECT_RATIO: f64 = SCREEN_WIDTH as f64 / SCREEN_HEIGHT as f64;
 
 pub struct Screen<'a> {
     canvas: Canvas<Window>,
     surface: Surface<'a>,
+    /// Frames in CGB mode are drawn in true colors
+    color_surface: Surface<'a>,
+    /// Super Game Boy frames, with the border
+    border_surface: Surface<'a>,
+    color_correction: ColorCorrection,
     g_scaling_mode: ScalingMode,
 }
 
@@ -20,7 +28,7 @@ impl Screen<'_> {
     pub fn new(sdl_context: Rc<RefCell<sdl2::Sdl>>) -> Screen<'static> {
         let _video_subsystem = sdl_context.borrow_mut().video().unwrap();
         let _window = _video_subsystem
-            .window("Window", SCREEN_WIDTH, SCREEN_HEIGHT)
+            .window("Window", WINDOW_WIDTH, WINDOW_HEIGHT)
             .opengl() // this line DOES NOT enable opengl, but allows you to create/get an OpenGL context from your window.
             .build()
             .unwrap();
@@ -31,23 +39,42 @@ impl Screen<'_> {
             .unwrap();
 
         let mut _surface =
-            Surface::new(SCREEN_WIDTH, SCREEN_HEIGHT, PixelFormatEnum::Index8).unwrap();
-        let palette = Palette::with_colors(&PALETTE).unwrap();
-        _surface.set_palette(&palette).unwrap();
+            Surface::new(WINDOW_WIDTH, WINDOW_HEIGHT, PixelFormatEnum::Index8).unwrap();
+        let color_surface =
+            Surface::new(WINDOW_WIDTH, WINDOW_HEIGHT, PixelFormatEnum::RGB24).unwrap();
+        let border_surface = Surface::new(
+            BORDER_WIDTH as u32,
+            BORDER_HEIGHT as u32,
+            PixelFormatEnum::RGB24,
+        )
+        .unwrap();
 
         _canvas
             .window_mut()
-            .set_size(
-                (3 * SCREEN_WIDTH).try_into().unwrap(),
-                (3 * SCREEN_HEIGHT).try_into().unwrap(),
-            )
+            .set_size(3 * WINDOW_WIDTH, 3 * WINDOW_HEIGHT)
             .unwrap();
 
-        Screen {
+        let mut screen = Screen {
             canvas: _canvas,
             surface: _surface,
+            color_surface,
+            border_surface,
+            color_correction: ColorCorrection::default(),
             g_scaling_mode: ScalingMode::ScalingModeAspectFit,
-        }
+        };
+        screen.set_dmg_palette(&palette::GRAYSCALE);
+        screen
+    }
+
+    /// Colors of the 4 shades of DMG frames
+    pub fn set_dmg_palette(&mut self, colors: &[Rgb; 4]) {
+        let colors = colors.map(|[red, green, blue]| Color::RGB(red, green, blue));
+        let palette = Palette::with_colors(&colors).unwrap();
+        self.surface.set_palette(&palette).unwrap();
+    }
+
+    pub fn set_color_correction(&mut self, color_correction: ColorCorrection) {
+        self.color_correction = color_correction;
     }
 
     /// Find SDL GL Driver to initiate SDL window
@@ -63,30 +90,57 @@ impl Screen<'_> {
     /// Start the Screen component
     pub fn start(&mut self) {
         self.canvas.clear();
-        self.present(0, 0);
+        self.present(&[0; SCREEN_WIDTH * SCREEN_HEIGHT]);
     }
 
-    pub fn set_pixel(&mut self, x: u8, y: u8, color: u8) {
-        let address = x as usize + y as usize * SCREEN_WIDTH as usize;
-        self.surface.without_lock_mut().unwrap()[address] = color;
+    /// Refresh the windows with a frame of SCREEN_WIDTH x SCREEN_HEIGHT shades
+    pub fn present(&mut self, frame_buffer: &[u8]) {
+        self.surface
+            .without_lock_mut()
+            .unwrap()
+            .copy_from_slice(frame_buffer);
+        Screen::show(&mut self.canvas, &self.surface);
     }
 
-    pub fn print_window_square(&mut self, x: u8, y: u8) {
-        let rectangle = sdl2::rect::Rect::new(x as i32 - 1, y as i32 - 1, 162 * 3, 144 * 3);
-        self.canvas.draw_rect(rectangle).unwrap();
+    /// Refresh the windows with a frame of SCREEN_WIDTH x SCREEN_HEIGHT 15-bit colors
+    pub fn present_colors(&mut self, frame_buffer: &[u16]) {
+        Screen::copy_colors(&mut self.color_surface, frame_buffer, self.color_correction);
+        Screen::show(&mut self.canvas, &self.color_surface);
     }
 
-    /// Refresh the windows with the buffered canvas
-    pub fn present(&mut self, x: u8, y: u8) {
-        self.canvas.clear();
-        let creator = self.canvas.texture_creator();
-        let texture = self.surface.as_texture(&creator).unwrap();
-
+    /// Make room for the Super Game Boy border
+    pub fn show_border(&mut self) {
         self.canvas
-            .copy(&texture, None, self.canvas.viewport())
+            .window_mut()
+            .set_size(3 * BORDER_WIDTH as u32, 3 * BORDER_HEIGHT as u32)
             .unwrap();
-        self.print_window_square(x, y);
-        self.canvas.present();
+    }
+
+    /// Refresh the windows with a Super Game Boy frame of BORDER_WIDTH x BORDER_HEIGHT
+    /// 15-bit colors. The SGB shows on a TV, its colors are not corrected.
+    pub fn present_border(&mut self, frame_buffer: &[u16]) {
+        Screen::copy_colors(&mut self.border_surface, frame_buffer, ColorCorrection::Raw);
+        Screen::show(&mut self.canvas, &self.border_surface);
+    }
+
+    fn copy_colors(surface: &mut Surface, frame_buffer: &[u16], correction: ColorCorrection) {
+        let (width, pitch) = (surface.width() as usize, surface.pitch() as usize);
+        let pixels = surface.without_lock_mut().unwrap();
+        for (y, line) in frame_buffer.chunks(width).enumerate() {
+            for (x, color) in line.iter().enumerate() {
+                let offset = y * pitch + x * 3;
+                pixels[offset..offset + 3].copy_from_slice(&palette::to_rgb(*color, correction));
+            }
+        }
+    }
+
+    fn show(canvas: &mut Canvas<Window>, surface: &Surface) {
+        canvas.clear();
+        let creator = canvas.texture_creator();
+        let texture = surface.as_texture(&creator).unwrap();
+
+        canvas.copy(&texture, None, canvas.viewport()).unwrap();
+        canvas.present();
     }
 
     pub fn update_window_viewport(&mut self) {
@@ -178,14 +232,6 @@ impl Screen<'_> {
     }
 }
 
-/// NES color palette
-const PALETTE: [Color; 4] = [
-    Color::RGB(0xff, 0xff, 0xff),
-    Color::RGB(0x80, 0x80, 0x80),
-    Color::RGB(0x40, 0x40, 0x40),
-    Color::RGB(0x05, 0x05, 0x05),
-];
-
 #[derive(PartialEq, Clone, Copy)]
 enum ScalingMode {
     ScalingModeAspectFit,
@@ -0,0 +1,333 @@
+//! Execution trace, one line per instruction in the gameboy-doctor format
+//! (https://github.com/robert/gameboy-doctor) used by most reference logs:
+//!
+//! A:00 F:11 B:22 C:33 D:44 E:55 H:66 L:77 SP:8888 PC:9999 PCMEM:AA,BB,CC,DD
+
+use super::bus::MemoryBus;
+use super::cpu::Cpu;
+use super::disassembler::{self, Symbols};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::rc::Rc;
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum TraceFormat {
+    /// Exactly the gameboy-doctor fields, to diff against reference logs
+    Doctor,
+    /// Doctor fields followed by the ROM bank, the clock cycles elapsed and the disassembly
+    Detailed,
+}
+
+#[derive(Default, Clone)]
+pub struct TraceFilter {
+    /// Only instructions in this address range
+    pub pc_range: Option<RangeInclusive<u16>>,
+    /// Only instructions of this ROM bank, 0000-3fff being bank 0
+    pub rom_bank: Option<u16>,
+    /// Number of instructions executed before tracing starts
+    pub skip: u64,
+    /// Maximum number of lines written
+    pub limit: Option<u64>,
+}
+
+pub struct Tracer {
+    output: Box<dyn Write>,
+    format: TraceFormat,
+    filter: TraceFilter,
+    executed: u64,
+    written: u64,
+}
+
+impl Tracer {
+    pub fn new(output: Box<dyn Write>, format: TraceFormat, filter: TraceFilter) -> Tracer {
+        Tracer {
+            output,
+            format,
+            filter,
+            executed: 0,
+            written: 0,
+        }
+    }
+
+    pub fn create(path: &Path, format: TraceFormat, filter: TraceFilter) -> io::Result<Tracer> {
+        let file = File::create(path)?;
+        Ok(Tracer::new(Box::new(BufWriter::new(file)), format, filter))
+    }
+
+    /// Called before each instruction is executed
+    pub fn trace<B: MemoryBus + ?Sized>(&mut self, cpu: &mut Cpu<B>) {
+        self.executed += 1;
+        if self.executed <= self.filter.skip || self.is_done() {
+            return;
+        }
+        let pc = cpu.registers.pc;
+        if let Some(pc_range) = &self.filter.pc_range {
+            if !pc_range.contains(&pc) {
+                return;
+            }
+        }
+        let bank = rom_bank_at(cpu, pc);
+        if self.filter.rom_bank.is_some() && self.filter.rom_bank != bank {
+            return;
+        }
+
+        let mut line = format_state(cpu);
+        if self.format == TraceFormat::Detailed {
+            let bus = &cpu.bus;
+            let instruction = disassembler::disassemble(
+                pc,
+                bank.unwrap_or_default(),
+                &Symbols::default(),
+                |address| bus.peek(address),
+            );
+            line += &format!(
+                " BANK:{:02X} CY:{} | {}",
+                bank.unwrap_or_default(),
+                cpu.get_total_cycles(),
+                instruction.text
+            );
+        }
+        if let Err(error) = writeln!(self.output, "{}", line) {
+            println!("Unable to write the trace, stopping it: {}", error);
+            self.filter.limit = Some(self.written);
+            return;
+        }
+        self.written += 1;
+    }
+
+    /// True once the line limit is reached
+    pub fn is_done(&self) -> bool {
+        self.filter.limit.is_some_and(|limit| self.written >= limit)
+    }
+}
+
+/// ROM bank of the instruction at pc, None outside of ROM
+pub(crate) fn rom_bank_at<B: MemoryBus + ?Sized>(cpu: &Cpu<B>, pc: u16) -> Option<u16> {
+    match pc {
+        0x0000..=0x3fff => Some(0),
+        0x4000..=0x7fff => Some(cpu.bus.get_rom_bank()),
+        _ => None,
+    }
+}
+
+/// CPU state in the gameboy-doctor format
+pub fn format_state<B: MemoryBus + ?Sized>(cpu: &Cpu<B>) -> String {
+    let pc = cpu.registers.pc;
+    let memory: Vec<String> = (0..4)
+        .map(|offset| format!("{:02X}", cpu.bus.peek(pc.wrapping_add(offset))))
+        .collect();
+    format!(
+        "A:{:02X} F:{:02X} B:{:02X} C:{:02X} D:{:02X} E:{:02X} H:{:02X} L:{:02X} SP:{:04X} PC:{:04X} PCMEM:{}",
+        cpu.registers.a,
+        cpu.flags.get_flags(),
+        cpu.registers.b,
+        cpu.registers.c,
+        cpu.registers.d,
+        cpu.registers.e,
+        cpu.registers.h,
+        cpu.registers.l,
+        cpu.registers.sp,
+        pc,
+        memory.join(",")
+    )
+}
+
+/// Trace lines kept in memory, to be checked while the emulation runs
+#[derive(Clone, Default)]
+pub struct TraceBuffer {
+    content: Rc<RefCell<Vec<u8>>>,
+}
+
+impl TraceBuffer {
+    /// Complete lines written since last call
+    pub fn take_lines(&self) -> Vec<String> {
+        let mut content = self.content.borrow_mut();
+        let Some(end) = content.iter().rposition(|&byte| byte == b'\n') else {
+            return vec![];
+        };
+        let lines: Vec<u8> = content.drain(..=end).collect();
+        String::from_utf8_lossy(&lines)
+            .lines()
+            .map(String::from)
+            .collect()
+    }
+}
+
+impl Write for TraceBuffer {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        self.content.borrow_mut().extend_from_slice(data);
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// "KEY:VALUE" fields of a trace line, the disassembly of the detailed format is left out
+pub struct TraceFields {
+    fields: Vec<(String, String)>,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct FieldDifference {
+    pub field: String,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl fmt::Display for FieldDifference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} expected {} got {}",
+            self.field, self.expected, self.actual
+        )?;
+        // Name the flags, F alone is hard to read
+        if self.field == "F" {
+            if let (Ok(expected), Ok(actual)) = (
+                u8::from_str_radix(&self.expected, 16),
+                u8::from_str_radix(&self.actual, 16),
+            ) {
+                write!(f, " ({} / {})", flag_names(expected), flag_names(actual))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn flag_names(flags: u8) -> String {
+    ['Z', 'N', 'H', 'C']
+        .iter()
+        .enumerate()
+        .map(|(index, name)| {
+            if flags & (0x80 >> index) != 0 {
+                *name
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+impl TraceFields {
+    pub fn parse(line: &str) -> TraceFields {
+        let state = line.split(" | ").next().unwrap_or_default();
+        TraceFields {
+            fields: state
+                .split_whitespace()
+                .filter_map(|field| field.split_once(':'))
+                .map(|(key, value)| (key.to_uppercase(), value.to_uppercase()))
+                .collect(),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Differences on the fields present in both lines, the expected ones first
+    pub fn compare(&self, actual: &TraceFields) -> Vec<FieldDifference> {
+        self.fields
+            .iter()
+            .filter_map(|(key, expected)| match actual.get(key) {
+                Some(value) if value != expected => Some(FieldDifference {
+                    field: key.clone(),
+                    expected: expected.clone(),
+                    actual: value.to_string(),
+                }),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// First reference line differing from the execution
+#[derive(Debug)]
+pub struct TraceDivergence {
+    /// Index of the reference line, 0 for the first one
+    pub line_index: usize,
+    pub expected: String,
+    pub actual: String,
+    pub differences: Vec<FieldDifference>,
+    /// Matching lines executed right before, oldest first
+    pub history: Vec<String>,
+}
+
+/// Execution trace lines checked one by one against a reference trace.
+///
+/// Comparison starts when PC first reaches the PC of the first reference line, lines
+/// traced before are skipped.
+pub struct TraceComparison {
+    reference: Vec<String>,
+    first_pc: String,
+    started: bool,
+    matched: usize,
+    /// Number of matching lines kept for the divergence
+    context: usize,
+    history: VecDeque<String>,
+}
+
+impl TraceComparison {
+    /// None if the first reference line has no PC to start from
+    pub fn new(reference: Vec<String>, context: usize) -> Option<TraceComparison> {
+        let first_pc = TraceFields::parse(reference.first()?)
+            .get("PC")?
+            .to_string();
+        Some(TraceComparison {
+            reference,
+            first_pc,
+            started: false,
+            matched: 0,
+            context,
+            history: VecDeque::with_capacity(context + 1),
+        })
+    }
+
+    /// Compare the next executed line, lines past the end of the reference are ignored
+    pub fn check(&mut self, line: String) -> Option<TraceDivergence> {
+        let actual = TraceFields::parse(&line);
+        if !self.started {
+            if actual.get("PC") != Some(self.first_pc.as_str()) {
+                return None;
+            }
+            self.started = true;
+        }
+        let expected = self.reference.get(self.matched)?;
+        let differences = TraceFields::parse(expected).compare(&actual);
+        if !differences.is_empty() {
+            return Some(TraceDivergence {
+                line_index: self.matched,
+                expected: expected.clone(),
+                actual: line,
+                differences,
+                history: self.history.iter().cloned().collect(),
+            });
+        }
+        if self.history.len() == self.context {
+            self.history.pop_front();
+        }
+        if self.context > 0 {
+            self.history.push_back(line);
+        }
+        self.matched += 1;
+        None
+    }
+
+    pub fn get_matched(&self) -> usize {
+        self.matched
+    }
+
+    /// True once every reference line matched
+    pub fn is_complete(&self) -> bool {
+        self.matched == self.reference.len()
+    }
+}
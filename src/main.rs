@@ -1,11 +1,62 @@
-mod gameboy;
-
-use gameboy::GameBoy;
+use gb_emu::gameboy::debug_view::oam::OamViewer;
+use gb_emu::gameboy::debug_view::tile_map::TileMapViewer;
+use gb_emu::gameboy::debug_view::tiles::TileViewer;
+use gb_emu::gameboy::debug_view::DebugView;
+use gb_emu::gameboy::debugger::{parse_number, Debugger};
+use gb_emu::gameboy::disassembler::{self, Symbols};
+use gb_emu::gameboy::gdb::GdbStub;
+use gb_emu::gameboy::palette::{self, ColorCorrection, DmgPalette};
+use gb_emu::gameboy::trace::{
+    TraceBuffer, TraceComparison, TraceDivergence, TraceFields, TraceFilter, TraceFormat, Tracer,
+};
+use gb_emu::gameboy::{GameBoy, ScreenshotOptions};
 extern crate argparse;
-use argparse::{ArgumentParser, Store};
+use argparse::{ArgumentParser, Store, StoreOption, StoreTrue};
+use std::path::{Path, PathBuf};
+
+const ROM_BANK_SIZE: usize = 0x4000;
+
+/// Steps without any instruction executed before giving up on a trace diff (halted or locked CPU)
+const TRACE_DIFF_IDLE_STEPS: u32 = 10_000_000;
 
 fn main() {
+    let mut args: Vec<String> = std::env::args().collect();
+    let subcommand: Option<fn(Vec<String>)> = match args.get(1).map(String::as_str) {
+        Some("disasm") => Some(disasm),
+        Some("trace-diff") => Some(trace_diff),
+        Some("dump-views") => Some(dump_views),
+        _ => None,
+    };
+    if let Some(subcommand) = subcommand {
+        let name = args.remove(1);
+        args[0] += &format!(" {}", name);
+        subcommand(args);
+        return;
+    }
+
     let mut rom_file: String = String::new();
+    let mut printer_dir: Option<String> = None;
+    let mut trace_file: Option<String> = None;
+    let mut trace_detailed = false;
+    let mut trace_from: Option<String> = None;
+    let mut trace_to: Option<String> = None;
+    let mut trace_bank: Option<u16> = None;
+    let mut trace_skip: u64 = 0;
+    let mut trace_limit: Option<u64> = None;
+    let mut debug = false;
+    let mut symbol_file: Option<String> = None;
+    let mut gdb_port: Option<u16> = None;
+    let mut no_access_restrictions = false;
+    let mut palette_name = String::from("grayscale");
+    let mut palette_file: Option<String> = None;
+    let mut color_correction = false;
+    let mut colorize = false;
+    let mut sgb = false;
+    let mut screenshot_dir = String::from(".");
+    let mut screenshot_options = ScreenshotOptions::default();
+    let mut dump_dir: Option<String> = None;
+    let mut dump_interval: u64 = 1;
+    let mut frame_limit: u64 = 600;
     {
         // For debugging only
 
@@ -13,11 +64,511 @@ fn main() {
 
         // this block limits scope of borrows by ap.refer() method
         let mut ap = ArgumentParser::new();
-        ap.set_description("Yet another GB Emulator in Rust");
+        ap.set_description(
+            "Yet another GB Emulator in Rust. Subcommands: `disasm rom_file` to disassemble a ROM, \
+            `trace-diff rom_file reference_trace` to compare the execution with a reference trace, \
+            `dump-views rom_file output_dir` to save the tiles, tile maps and OAM as PNG. \
+            Press D to break into the debugger, M to open the memory viewer, T the tiles, \
+            B the tile maps, O the OAM and S to save a screenshot.",
+        );
         ap.refer(&mut rom_file)
             .add_argument("rom_file", Store, "File path to ROM File");
+        ap.refer(&mut printer_dir).add_option(
+            &["--printer"],
+            StoreOption,
+            "Connect a Game Boy Printer saving printouts as PNG in this directory",
+        );
+        ap.refer(&mut trace_file).add_option(
+            &["--trace"],
+            StoreOption,
+            "Write an execution trace in the gameboy-doctor format to this file",
+        );
+        ap.refer(&mut trace_detailed).add_option(
+            &["--trace-detailed"],
+            StoreTrue,
+            "Add the ROM bank, clock cycles and disassembly to each trace line",
+        );
+        ap.refer(&mut trace_from).add_option(
+            &["--trace-from"],
+            StoreOption,
+            "Only trace instructions from this address",
+        );
+        ap.refer(&mut trace_to).add_option(
+            &["--trace-to"],
+            StoreOption,
+            "Only trace instructions up to this address",
+        );
+        ap.refer(&mut trace_bank).add_option(
+            &["--trace-bank"],
+            StoreOption,
+            "Only trace instructions of this ROM bank",
+        );
+        ap.refer(&mut trace_skip).add_option(
+            &["--trace-skip"],
+            Store,
+            "Number of instructions executed before tracing starts",
+        );
+        ap.refer(&mut trace_limit).add_option(
+            &["--trace-limit"],
+            StoreOption,
+            "Maximum number of instructions traced",
+        );
+        ap.refer(&mut debug).add_option(
+            &["--debug"],
+            StoreTrue,
+            "Start in the debugger, before the first instruction",
+        );
+        ap.refer(&mut symbol_file).add_option(
+            &["--sym"],
+            StoreOption,
+            "RGBDS symbol file for the debugger, the ROM file with a .sym extension by default",
+        );
+        ap.refer(&mut gdb_port).add_option(
+            &["--gdb"],
+            StoreOption,
+            "Wait for a GDB client on this local TCP port before starting",
+        );
+        ap.refer(&mut no_access_restrictions).add_option(
+            &["--no-access-restrictions"],
+            StoreTrue,
+            "Let the CPU access VRAM and OAM whatever the PPU mode, for debugging",
+        );
+        ap.refer(&mut palette_name).add_option(
+            &["--palette"],
+            Store,
+            "Colors of DMG games: grayscale (default), pea-soup, pocket or a palette of --palettes",
+        );
+        ap.refer(&mut palette_file).add_option(
+            &["--palettes"],
+            StoreOption,
+            "File of palettes, one `name: RRGGBB RRGGBB RRGGBB RRGGBB` per line from the lightest shade",
+        );
+        ap.refer(&mut color_correction).add_option(
+            &["--color-correction"],
+            StoreTrue,
+            "Show CGB colors as the LCD does instead of scaling them as is",
+        );
+        ap.refer(&mut colorize).add_option(
+            &["--colorize"],
+            StoreTrue,
+            "Run DMG games on a CGB, colored by title like the CGB boot ROM does",
+        );
+        ap.refer(&mut sgb).add_option(
+            &["--sgb"],
+            StoreTrue,
+            "Run games with Super Game Boy functions on a SGB, with its palettes and border",
+        );
+        ap.refer(&mut screenshot_dir).add_option(
+            &["--screenshot-dir"],
+            Store,
+            "Directory of the screenshots saved with the S key, the current one by default",
+        );
+        ap.refer(&mut screenshot_options.border).add_option(
+            &["--screenshot-border"],
+            StoreTrue,
+            "Include the SGB border in screenshots and dumped frames",
+        );
+        ap.refer(&mut screenshot_options.scale).add_option(
+            &["--screenshot-scale"],
+            Store,
+            "Integer scale of screenshots and dumped frames, 1 by default",
+        );
+        ap.refer(&mut dump_dir).add_option(
+            &["--dump-frames"],
+            StoreOption,
+            "Run without window and save frames as PNG in this directory",
+        );
+        ap.refer(&mut dump_interval).add_option(
+            &["--frames"],
+            Store,
+            "With --dump-frames, save every Nth frame, every frame by default",
+        );
+        ap.refer(&mut frame_limit).add_option(
+            &["--frame-limit"],
+            Store,
+            "With --dump-frames, stop after this number of frames, 600 by default",
+        );
         ap.parse_args_or_exit();
     }
-    let mut gb = GameBoy::new();
+    let mut gb = if dump_dir.is_some() {
+        GameBoy::headless()
+    } else {
+        GameBoy::new()
+    };
+    gb.set_access_restrictions(!no_access_restrictions);
+    gb.set_dmg_palette(find_palette(&palette_name, &palette_file));
+    if color_correction {
+        gb.set_color_correction(ColorCorrection::Lcd);
+    }
+    gb.set_colorization(colorize);
+    gb.set_sgb(sgb);
+    if let Some(dump_dir) = dump_dir {
+        dump_frames(
+            gb,
+            rom_file,
+            Path::new(&dump_dir),
+            dump_interval.max(1),
+            frame_limit,
+            screenshot_options,
+        );
+        return;
+    }
+    gb.set_screenshots(PathBuf::from(screenshot_dir), screenshot_options);
+    let mut debugger = Debugger::new(load_symbols(&rom_file, &symbol_file));
+    if debug {
+        debugger.request_break();
+    }
+    gb.attach_debugger(debugger);
+    if let Some(port) = gdb_port {
+        let mut gdb = GdbStub::listen(port).unwrap_or_else(|error| {
+            eprintln!("Unable to listen on port {}: {}", port, error);
+            std::process::exit(1);
+        });
+        println!("Waiting for GDB on localhost:{}", gdb.port());
+        if let Err(error) = gdb.accept() {
+            eprintln!("Unable to accept GDB: {}", error);
+            std::process::exit(1);
+        }
+        gb.attach_gdb(gdb);
+    }
+    if let Some(printer_dir) = printer_dir {
+        gb.connect_printer(PathBuf::from(printer_dir));
+    }
+    if let Some(trace_file) = trace_file {
+        let filter = TraceFilter {
+            pc_range: if trace_from.is_some() || trace_to.is_some() {
+                Some(parse_address(&trace_from, 0x0000)..=parse_address(&trace_to, 0xffff))
+            } else {
+                None
+            },
+            rom_bank: trace_bank,
+            skip: trace_skip,
+            limit: trace_limit,
+        };
+        let format = if trace_detailed {
+            TraceFormat::Detailed
+        } else {
+            TraceFormat::Doctor
+        };
+        match Tracer::create(Path::new(&trace_file), format, filter) {
+            Ok(tracer) => gb.set_tracer(tracer),
+            Err(error) => {
+                eprintln!("Unable to create {}: {}", trace_file, error);
+                std::process::exit(1);
+            }
+        }
+    }
     gb.start(rom_file);
 }
+
+/// Symbols of the given file, or of the .sym file next to the ROM if any. Exits if the
+/// given file can't be read.
+fn load_symbols(rom_file: &str, symbol_file: &Option<String>) -> Symbols {
+    match symbol_file {
+        Some(symbol_file) => Symbols::load(Path::new(symbol_file)).unwrap_or_else(|error| {
+            eprintln!("Unable to read {}: {}", symbol_file, error);
+            std::process::exit(1);
+        }),
+        None => Symbols::load(&Path::new(rom_file).with_extension("sym")).unwrap_or_default(),
+    }
+}
+
+/// Colors of a built-in palette or of one in the palette file. Exits if the file can't be
+/// read or there is no such palette.
+fn find_palette(name: &str, palette_file: &Option<String>) -> [palette::Rgb; 4] {
+    let mut palettes = palette::get_builtin_palettes();
+    if let Some(palette_file) = palette_file {
+        match palette::load_palettes(Path::new(palette_file)) {
+            Ok(custom) => palettes.extend(custom),
+            Err(error) => {
+                eprintln!("Unable to read {}: {}", palette_file, error);
+                std::process::exit(1);
+            }
+        }
+    }
+    // Later palettes override the built-in ones
+    match palettes.iter().rev().find(|palette| palette.name == name) {
+        Some(DmgPalette { colors, .. }) => *colors,
+        None => {
+            let names: Vec<&str> = palettes
+                .iter()
+                .map(|palette| palette.name.as_str())
+                .collect();
+            eprintln!("Unknown palette {}, available: {}", name, names.join(", "));
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Exits if a frame can't be saved
+fn dump_frames(
+    mut gb: GameBoy,
+    rom_file: String,
+    output_dir: &Path,
+    interval: u64,
+    frame_limit: u64,
+    options: ScreenshotOptions,
+) {
+    gb.load(rom_file);
+    match gb.dump_frames(output_dir, interval, frame_limit, options) {
+        Ok(saved) => println!("Saved {} frames in {}", saved, output_dir.display()),
+        Err(error) => {
+            eprintln!(
+                "Unable to save the frames in {}: {}",
+                output_dir.display(),
+                error
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Exits on invalid addresses
+fn parse_address(text: &Option<String>, default: u16) -> u16 {
+    match text {
+        None => default,
+        Some(text) => parse_number(text).unwrap_or_else(|| {
+            eprintln!("Invalid address {}", text);
+            std::process::exit(1);
+        }),
+    }
+}
+
+fn disasm(args: Vec<String>) {
+    let mut rom_file: String = String::new();
+    let mut bank: u16 = 0;
+    let mut from: Option<String> = None;
+    let mut to: Option<String> = None;
+    let mut symbol_file: Option<String> = None;
+    {
+        let mut ap = ArgumentParser::new();
+        ap.set_description("Disassemble a ROM bank in RGBDS syntax");
+        ap.refer(&mut rom_file)
+            .add_argument("rom_file", Store, "File path to ROM File")
+            .required();
+        ap.refer(&mut bank)
+            .add_option(&["--bank"], Store, "ROM bank, 0 by default");
+        ap.refer(&mut from).add_option(
+            &["--from"],
+            StoreOption,
+            "First address, start of the bank by default",
+        );
+        ap.refer(&mut to).add_option(
+            &["--to"],
+            StoreOption,
+            "Last address, end of the bank by default",
+        );
+        ap.refer(&mut symbol_file).add_option(
+            &["--sym"],
+            StoreOption,
+            "RGBDS symbol file, the ROM file with a .sym extension by default",
+        );
+        if let Err(code) = ap.parse(args, &mut std::io::stdout(), &mut std::io::stderr()) {
+            std::process::exit(code);
+        }
+    }
+
+    let rom = match std::fs::read(&rom_file) {
+        Ok(rom) => rom,
+        Err(error) => {
+            eprintln!("Unable to read {}: {}", rom_file, error);
+            std::process::exit(1);
+        }
+    };
+    let symbols = load_symbols(&rom_file, &symbol_file);
+
+    let (bank_start, bank_end) = if bank == 0 {
+        (0x0000, 0x3fff)
+    } else {
+        (0x4000, 0x7fff)
+    };
+    let from = parse_address(&from, bank_start);
+    let to = parse_address(&to, bank_end);
+
+    // Addresses are mapped as if the bank was in the switchable area
+    let read = |address: u16| {
+        let offset = if address < 0x4000 {
+            address as usize
+        } else {
+            bank as usize * ROM_BANK_SIZE + (address as usize - ROM_BANK_SIZE)
+        };
+        rom.get(offset).copied().unwrap_or(0xff)
+    };
+
+    let mut address = from as u32;
+    while address <= to as u32 {
+        if let Some(label) = symbols.get(bank, address as u16) {
+            println!("{}:", label);
+        }
+        let instruction = disassembler::disassemble(address as u16, bank, &symbols, read);
+        println!("{:02x}:{}", bank, instruction);
+        address += instruction.bytes.len() as u32;
+    }
+}
+
+fn trace_diff(args: Vec<String>) {
+    let mut rom_file: String = String::new();
+    let mut reference_file: String = String::new();
+    let mut context: usize = 10;
+    {
+        let mut ap = ArgumentParser::new();
+        ap.set_description(
+            "Run a ROM headlessly and stop at the first difference with a reference trace, \
+            in the gameboy-doctor format or the detailed format of --trace-detailed. \
+            Comparison starts when PC first reaches the PC of the first reference line.",
+        );
+        ap.refer(&mut rom_file)
+            .add_argument("rom_file", Store, "File path to ROM File")
+            .required();
+        ap.refer(&mut reference_file)
+            .add_argument("reference_trace", Store, "Reference trace file")
+            .required();
+        ap.refer(&mut context).add_option(
+            &["--context"],
+            Store,
+            "Number of instructions shown before the divergence, 10 by default",
+        );
+        if let Err(code) = ap.parse(args, &mut std::io::stdout(), &mut std::io::stderr()) {
+            std::process::exit(code);
+        }
+    }
+
+    let reference: Vec<String> = match std::fs::read_to_string(&reference_file) {
+        Ok(content) => content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(String::from)
+            .collect(),
+        Err(error) => {
+            eprintln!("Unable to read {}: {}", reference_file, error);
+            std::process::exit(1);
+        }
+    };
+    let Some(mut comparison) = TraceComparison::new(reference, context) else {
+        eprintln!("No PC found in the first line of {}", reference_file);
+        std::process::exit(1);
+    };
+
+    let mut gb = GameBoy::headless();
+    gb.load(rom_file);
+    let buffer = TraceBuffer::default();
+    gb.set_tracer(Tracer::new(
+        Box::new(buffer.clone()),
+        TraceFormat::Detailed,
+        TraceFilter::default(),
+    ));
+
+    let mut idle_steps = 0;
+    while !comparison.is_complete() {
+        gb.step();
+        if let Some(fault) = gb.take_cpu_fault() {
+            println!(
+                "{} after {} matching lines",
+                fault,
+                comparison.get_matched()
+            );
+            std::process::exit(1);
+        }
+        let lines = buffer.take_lines();
+        if lines.is_empty() {
+            idle_steps += 1;
+            if idle_steps == TRACE_DIFF_IDLE_STEPS {
+                println!(
+                    "No instruction executed for {} steps after {} matching lines",
+                    idle_steps,
+                    comparison.get_matched()
+                );
+                std::process::exit(1);
+            }
+            continue;
+        }
+        idle_steps = 0;
+
+        for line in lines {
+            if let Some(divergence) = comparison.check(line) {
+                print_divergence(&mut gb, &divergence);
+                std::process::exit(1);
+            }
+        }
+    }
+    println!("All {} reference lines matched", comparison.get_matched());
+}
+
+fn dump_views(args: Vec<String>) {
+    let mut rom_file: String = String::new();
+    let mut output_dir: String = String::new();
+    let mut frames: u32 = 60;
+    {
+        let mut ap = ArgumentParser::new();
+        ap.set_description(
+            "Run a ROM headlessly for a number of frames, then save the tiles, tile maps and OAM \
+            views as tiles.png, tile_maps.png and oam.png",
+        );
+        ap.refer(&mut rom_file)
+            .add_argument("rom_file", Store, "File path to ROM File")
+            .required();
+        ap.refer(&mut output_dir)
+            .add_argument("output_dir", Store, "Directory of the PNG files")
+            .required();
+        ap.refer(&mut frames).add_option(
+            &["--frames"],
+            Store,
+            "Number of frames run before saving, 60 by default",
+        );
+        if let Err(code) = ap.parse(args, &mut std::io::stdout(), &mut std::io::stderr()) {
+            std::process::exit(code);
+        }
+    }
+
+    let mut gb = GameBoy::headless();
+    gb.load(rom_file);
+    for _ in 0..frames {
+        gb.run_frame();
+    }
+    let views: [(&str, Box<dyn DebugView>); 3] = [
+        ("tiles.png", Box::new(TileViewer::new())),
+        ("tile_maps.png", Box::new(TileMapViewer::new())),
+        ("oam.png", Box::new(OamViewer::new())),
+    ];
+    for (name, view) in views {
+        let path = Path::new(&output_dir).join(name);
+        match view.render(gb.get_bus()).write_png(&path) {
+            Ok(()) => println!("Saved {}", path.display()),
+            Err(error) => {
+                eprintln!("Unable to save {}: {}", path.display(), error);
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
+fn print_divergence(gb: &mut GameBoy, divergence: &TraceDivergence) {
+    println!(
+        "First divergence at reference line {}",
+        divergence.line_index + 1
+    );
+    println!("  expected: {}", divergence.expected);
+    println!("  actual:   {}", divergence.actual);
+    for difference in &divergence.differences {
+        println!("  {}", difference);
+    }
+
+    println!("\nLast {} instructions:", divergence.history.len());
+    for line in &divergence.history {
+        println!("  {}", line);
+    }
+
+    println!("\nDisassembly at PC:");
+    let symbols = Symbols::default();
+    // The diverging instruction has already been executed
+    let mut address = TraceFields::parse(&divergence.actual)
+        .get("PC")
+        .and_then(|pc| u16::from_str_radix(pc, 16).ok())
+        .unwrap_or(gb.get_registers().pc);
+    for index in 0..5 {
+        let instruction = gb.disassemble(address, &symbols);
+        let marker = if index == 0 { "=>" } else { "  " };
+        println!("{} {}", marker, instruction);
+        address = address.wrapping_add(instruction.bytes.len() as u16);
+    }
+}
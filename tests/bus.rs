@@ -0,0 +1,224 @@
+//! Memory map: debug accesses through peek and poke, OAM DMA
+
+use gb_emu::gameboy::bus::{Bus, MemoryBanks, MemoryBus};
+use gb_emu::gameboy::cpu::Cpu;
+use gb_emu::gameboy::debugger::{WatchKind, Watchpoint};
+
+#[test]
+fn poke_modifies_rom_in_place() {
+    let mut bus = Bus::new();
+    bus.poke(0x0200, 0x12);
+    assert_eq!(bus.peek(0x0200), 0x12);
+    // Seen by the cartridge controller, not stored
+    bus.write_8(0x0200, 0x34);
+    assert_eq!(bus.read_8(0x0200), 0x12);
+}
+
+#[test]
+fn poke_has_no_side_effects() {
+    let mut bus = Bus::new();
+    for offset in 0..0xa0 {
+        bus.write_8(0xc000 + offset, 0x55);
+    }
+    // DMA is not started
+    bus.poke(0xff46, 0xc0);
+    assert_eq!(bus.peek(0xfe00), 0x00);
+    bus.poke(0xff0f, 0x04);
+    assert_eq!(bus.peek(0xff0f), 0xe4);
+    // LY is read only
+    bus.poke(0xff44, 0x42);
+    assert_eq!(bus.peek(0xff44), 0x00);
+}
+
+#[test]
+fn peek_shows_requested_interrupts_without_acknowledging_them() {
+    let mut bus = Bus::new();
+    bus.write_8(0xff06, 0x00);
+    bus.write_8(0xff05, 0xff);
+    bus.write_8(0xff07, 0x05);
+    for _ in 0..64 {
+        bus.tick();
+    }
+    assert_eq!(bus.peek(0xff0f), 0xe4);
+    assert_eq!(bus.peek(0xff0f), 0xe4);
+    assert_eq!(bus.get_pending_interrupts(), 0x00);
+    bus.write_8(0xffff, 0x04);
+    assert_eq!(bus.get_pending_interrupts(), 0x04);
+}
+
+#[test]
+fn missing_banks_are_open_bus() {
+    let mut bus = Bus::new();
+    bus.write_8(0x8000, 0x11);
+    bus.write_8(0xc000, 0x22);
+    bus.write_8(0xd000, 0x33);
+    let banks = bus.get_banks();
+    assert_eq!(bus.peek_banked(0x8000, banks), 0x11);
+    assert_eq!(
+        bus.peek_banked(0x8000, MemoryBanks { vram: 1, ..banks }),
+        0xff
+    );
+    // Bank 0 is always mapped at c000-cfff
+    let wram = MemoryBanks { wram: 2, ..banks };
+    assert_eq!(bus.peek_banked(0xc000, wram), 0x22);
+    assert_eq!(bus.peek_banked(0xd000, wram), 0xff);
+    assert_eq!(bus.peek_banked(0xf000, wram), 0xff);
+    bus.poke_banked(0xd000, 0x44, wram);
+    assert_eq!(bus.peek(0xd000), 0x33);
+    assert_eq!(bus.peek(0xf000), 0x33);
+}
+
+#[test]
+fn peek_and_poke_ignore_watchpoints() {
+    let mut bus = Bus::new();
+    bus.watchpoints.add(Watchpoint {
+        id: 1,
+        range: 0xc000..=0xc0ff,
+        kind: WatchKind::Access,
+    });
+    bus.poke(0xc010, 0x01);
+    assert_eq!(bus.peek(0xc010), 0x01);
+    assert!(bus.watchpoints.take_hit().is_none());
+    bus.read_8(0xc010);
+    assert!(bus.watchpoints.take_hit().is_some());
+}
+
+#[test]
+fn poked_registers_keep_their_actions_off() {
+    let mut bus = Bus::new();
+    // No serial transfer started, the data stays and no interrupt is requested
+    bus.poke(0xff01, 0x42);
+    bus.poke(0xff02, 0x81);
+    for _ in 0..2 * 4096 {
+        bus.tick();
+    }
+    assert_eq!(bus.peek(0xff02), 0xff);
+    assert_eq!(bus.peek(0xff01), 0x42);
+    assert_eq!(bus.peek(0xff0f) & 0x08, 0x00);
+
+    // DIV takes the value instead of being reset
+    bus.poke(0xff04, 0x12);
+    assert_eq!(bus.peek(0xff04), 0x12);
+
+    // The LCD is not turned off, other LCDC bits change
+    bus.write_8(0xff40, 0x91);
+    bus.poke(0xff40, 0x11);
+    assert_eq!(bus.peek(0xff40), 0x91);
+    bus.poke(0xff40, 0x93);
+    assert_eq!(bus.peek(0xff40), 0x93);
+}
+
+#[test]
+fn stop_ignores_watchpoints() {
+    let mut bus = Bus::new();
+    // STOP
+    bus.write_8(0xc000, 0x10);
+    bus.write_8(0xc001, 0x00);
+    for _ in 0..1024 {
+        bus.tick();
+    }
+    assert_eq!(bus.peek(0xff04), 4);
+    bus.watchpoints.add(Watchpoint {
+        id: 1,
+        range: 0xff04..=0xff0f,
+        kind: WatchKind::Access,
+    });
+    let mut cpu = Cpu::new(bus);
+    cpu.registers.pc = 0xc000;
+    cpu.next();
+    assert!(cpu.is_stopped);
+    assert_eq!(cpu.bus.peek(0xff04), 0);
+    for _ in 0..10 {
+        cpu.next();
+    }
+    assert!(cpu.is_stopped);
+
+    // A joypad interrupt wakes the CPU up, even if not enabled in IE
+    cpu.bus.poke(0xff0f, 0x10);
+    cpu.next();
+    assert!(!cpu.is_stopped);
+    assert!(cpu.bus.watchpoints.take_hit().is_none());
+}
+
+#[test]
+fn stop_halts_the_timer_and_the_lcd() {
+    let mut bus = Bus::new();
+    // STOP
+    bus.write_8(0xc000, 0x10);
+    bus.write_8(0xc001, 0x00);
+    bus.write_8(0xff40, 0x91);
+    // TIMA counting every 16 clock cycles
+    bus.write_8(0xff07, 0x05);
+    let mut cpu = Cpu::new(bus);
+    cpu.registers.pc = 0xc000;
+    cpu.next();
+    assert!(cpu.is_stopped);
+    let (tima, line) = (cpu.bus.peek(0xff05), cpu.bus.peek(0xff44));
+    for _ in 0..10_000 {
+        cpu.next();
+    }
+    assert!(cpu.is_stopped);
+    assert_eq!(cpu.bus.peek(0xff04), 0);
+    assert_eq!(cpu.bus.peek(0xff05), tima);
+    assert_eq!(cpu.bus.peek(0xff44), line);
+
+    cpu.bus.controller.press_a();
+    cpu.next();
+    assert!(!cpu.is_stopped);
+}
+
+/// Clock cycles of a DMA transfer, the start delay included
+const DMA_CYCLES: usize = 4 * (1 + 160);
+
+fn tick(bus: &mut Bus, cycles: usize) {
+    for _ in 0..cycles {
+        bus.tick();
+    }
+}
+
+#[test]
+fn dma_copies_one_byte_per_m_cycle() {
+    let mut bus = Bus::new();
+    for offset in 0..0xa0 {
+        bus.write_8(0xc100 + offset, offset as u8 + 1);
+    }
+    bus.write_8(0xff46, 0xc1);
+    assert_eq!(bus.read_8(0xff46), 0xc1);
+
+    // Start delay then 10 bytes
+    tick(&mut bus, 4 * 11);
+    assert_eq!(bus.peek(0xfe09), 0x0a);
+    assert_eq!(bus.peek(0xfe0a), 0x00);
+    tick(&mut bus, DMA_CYCLES - 4 * 11);
+    assert_eq!(bus.peek(0xfe9f), 0xa0);
+    assert_eq!(bus.read_8(0xfe00), 0x01);
+}
+
+#[test]
+fn dma_restricts_the_cpu_to_hram() {
+    let mut bus = Bus::new();
+    bus.write_8(0xc000, 0x42);
+    bus.write_8(0xff80, 0x24);
+    bus.write_8(0xff46, 0xc0);
+    tick(&mut bus, 4);
+
+    assert_eq!(bus.read_8(0xc000), 0xff);
+    assert_eq!(bus.read_8(0xfe00), 0xff);
+    bus.write_8(0xc000, 0x00);
+    assert_eq!(bus.read_8(0xff80), 0x24);
+    bus.write_8(0xff81, 0x18);
+    assert_eq!(bus.read_8(0xff81), 0x18);
+
+    tick(&mut bus, DMA_CYCLES);
+    assert_eq!(bus.read_8(0xc000), 0x42);
+    assert_eq!(bus.read_8(0xfe00), 0x42);
+}
+
+#[test]
+fn dma_from_echo_area_reads_work_ram() {
+    let mut bus = Bus::new();
+    bus.write_8(0xde00, 0x5a);
+    bus.write_8(0xff46, 0xfe);
+    tick(&mut bus, DMA_CYCLES);
+    assert_eq!(bus.peek(0xfe00), 0x5a);
+}
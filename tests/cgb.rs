@@ -0,0 +1,271 @@
+//! CGB mode seen from the memory bus
+
+mod common;
+
+use common::{run_until_line, run_until_mode, write_color, BLUE, GREEN, RED};
+use gb_emu::gameboy::bus::{Bus, MemoryBanks, MemoryBus};
+use gb_emu::gameboy::cpu::Cpu;
+use gb_emu::gameboy::ppu::Model;
+
+fn cgb_bus() -> Bus {
+    let mut bus = Bus::new();
+    bus.set_model(Model::Cgb);
+    bus
+}
+
+#[test]
+fn vram_and_wram_banks() {
+    let mut bus = cgb_bus();
+    bus.write_8(0x8000, 0x11);
+    bus.write_8(0xff4f, 0x01);
+    assert_eq!(bus.read_8(0xff4f), 0xff);
+    assert_eq!(bus.read_8(0x8000), 0x00);
+    bus.write_8(0x8000, 0x22);
+    bus.write_8(0xff4f, 0x00);
+    assert_eq!(bus.read_8(0x8000), 0x11);
+
+    // SVBK 0 selects bank 1
+    bus.write_8(0xd000, 0x33);
+    bus.write_8(0xff70, 0x01);
+    assert_eq!(bus.read_8(0xd000), 0x33);
+    bus.write_8(0xff70, 0x07);
+    assert_eq!(bus.read_8(0xff70), 0xff);
+    assert_eq!(bus.read_8(0xd000), 0x00);
+    bus.write_8(0xf000, 0x44);
+    assert_eq!(bus.read_8(0xd000), 0x44);
+
+    let banks = bus.get_banks();
+    assert_eq!((banks.vram, banks.wram), (0, 7));
+    assert_eq!(
+        bus.peek_banked(0x8000, MemoryBanks { vram: 1, ..banks }),
+        0x22
+    );
+    assert_eq!(
+        bus.peek_banked(0xd000, MemoryBanks { wram: 1, ..banks }),
+        0x33
+    );
+    assert_eq!(bus.get_vram_bank_count(), 2);
+    assert_eq!(bus.get_wram_bank_count(), 8);
+}
+
+#[test]
+fn banking_registers_are_ignored_on_dmg() {
+    let mut bus = Bus::new();
+    bus.write_8(0x8000, 0x11);
+    bus.write_8(0xd000, 0x22);
+    bus.write_8(0xff4f, 0x01);
+    bus.write_8(0xff70, 0x03);
+    assert_eq!(bus.read_8(0x8000), 0x11);
+    assert_eq!(bus.read_8(0xd000), 0x22);
+    assert_eq!(bus.get_banks().wram, 1);
+    assert_eq!(bus.get_vram_bank_count(), 1);
+    assert_eq!(bus.ppu.get_color_frame_buffer(), None);
+}
+
+#[test]
+fn palette_ram_auto_increment() {
+    let mut bus = cgb_bus();
+    bus.write_8(0xff68, 0x80 | 0x3e);
+    bus.write_8(0xff69, 0x12);
+    bus.write_8(0xff69, 0x34);
+    bus.write_8(0xff69, 0x56);
+    // The address wraps around, bit 6 reads 1
+    assert_eq!(bus.read_8(0xff68), 0x80 | 0x40 | 0x01);
+    bus.write_8(0xff68, 0x3f);
+    assert_eq!(bus.read_8(0xff69), 0x34);
+    bus.write_8(0xff68, 0x00);
+    assert_eq!(bus.read_8(0xff69), 0x56);
+
+    bus.write_8(0xff6a, 0x02);
+    bus.write_8(0xff6b, 0x78);
+    bus.write_8(0xff6b, 0x9a);
+    assert_eq!(bus.read_8(0xff6a), 0x42);
+    assert_eq!(bus.read_8(0xff6b), 0x9a);
+}
+
+/// Tile with a left column of color 3 at address in a VRAM bank
+fn write_left_column_tile(bus: &mut Bus, bank: u8, address: u16) {
+    bus.write_8(0xff4f, bank);
+    for line in 0..8 {
+        bus.write_8(address + line * 2, 0x80);
+        bus.write_8(address + line * 2 + 1, 0x80);
+    }
+    bus.write_8(0xff4f, 0);
+}
+
+/// Tile of color 1 at address in VRAM bank 0
+fn write_color_1_tile(bus: &mut Bus, address: u16) {
+    for line in 0..8 {
+        bus.write_8(address + line * 2, 0xff);
+    }
+}
+
+#[test]
+fn background_attributes_and_sprite_priority() {
+    let mut bus = cgb_bus();
+    write_color(&mut bus, 0xff68, 0, 0, BLUE);
+    write_color(&mut bus, 0xff68, 0, 1, RED);
+    write_color(&mut bus, 0xff68, 1, 3, RED);
+    write_color(&mut bus, 0xff6a, 0, 1, GREEN);
+    write_color(&mut bus, 0xff6a, 1, 1, BLUE);
+    write_left_column_tile(&mut bus, 1, 0x8010);
+    write_color_1_tile(&mut bus, 0x8020);
+    // Tile 1 from bank 1 with palette 1, flipped horizontally
+    bus.write_8(0x9800, 0x01);
+    // Tile 2 over the sprites
+    bus.write_8(0x9804, 0x02);
+    bus.write_8(0xff4f, 0x01);
+    bus.write_8(0x9800, 0x29);
+    bus.write_8(0x9804, 0x80);
+    bus.write_8(0xff4f, 0x00);
+
+    // Sprite 0 is over sprite 1 even with a higher X
+    let sprites = [
+        (16, 8 + 20, 2, 0x00),
+        (16, 8 + 16, 2, 0x01),
+        (16, 8 + 32, 2, 0x01),
+    ];
+    for (index, sprite) in sprites.iter().enumerate() {
+        let address = 0xfe00 + index as u16 * 4;
+        bus.write_8(address, sprite.0);
+        bus.write_8(address + 1, sprite.1);
+        bus.write_8(address + 2, sprite.2);
+        bus.write_8(address + 3, sprite.3);
+    }
+    bus.write_8(0xff40, 0x93);
+    run_until_line(&mut bus, 1);
+
+    let colors = bus.ppu.get_color_frame_buffer().unwrap();
+    assert_eq!(colors[7], RED);
+    assert_eq!(colors[8], BLUE);
+    assert_eq!(colors[17], BLUE);
+    assert_eq!(colors[21], GREEN);
+    assert_eq!(colors[32], RED);
+    // Color numbers are kept in the frame buffer
+    assert_eq!(bus.ppu.get_frame_buffer()[7], 3);
+
+    // Priority by X like on DMG
+    bus.write_8(0xff6c, 0x01);
+    run_until_line(&mut bus, 0);
+    run_until_line(&mut bus, 1);
+    assert_eq!(bus.ppu.get_color_frame_buffer().unwrap()[21], BLUE);
+}
+
+#[test]
+fn key0_selects_dmg_compatibility_until_the_boot_rom_is_unmapped() {
+    let mut bus = cgb_bus();
+    bus.write_8(0xff4c, 0x04);
+    assert_eq!(bus.get_model(), Model::DmgCompatibility);
+    bus.write_8(0xff4f, 0x01);
+    assert_eq!(bus.get_banks().vram, 0);
+    bus.write_8(0xff50, 0x01);
+    bus.write_8(0xff4c, 0x80);
+    assert_eq!(bus.get_model(), Model::DmgCompatibility);
+
+    // Shades pick the colors of the first palette
+    write_color(&mut bus, 0xff68, 0, 2, GREEN);
+    write_color_1_tile(&mut bus, 0x8000);
+    bus.write_8(0xff47, 0b11_10_10_00);
+    bus.write_8(0xff40, 0x91);
+    run_until_line(&mut bus, 1);
+    assert_eq!(bus.ppu.get_frame_buffer()[0], 2);
+    assert_eq!(bus.ppu.get_color_frame_buffer().unwrap()[0], GREEN);
+}
+
+#[test]
+fn stop_switches_to_double_speed() {
+    let mut bus = cgb_bus();
+    // LD A,1; LDH (KEY1),A; STOP
+    let program = [0x3e, 0x01, 0xe0, 0x4d, 0x10, 0x00];
+    for (offset, byte) in program.iter().enumerate() {
+        bus.write_8(0xc000 + offset as u16, *byte);
+    }
+    bus.write_8(0xff40, 0x91);
+    let mut cpu = Cpu::new(bus);
+    cpu.registers.pc = 0xc000;
+    for _ in 0..3 {
+        cpu.next();
+    }
+    assert!(!cpu.is_stopped);
+    assert_eq!(cpu.registers.pc, 0xc006);
+    assert!(cpu.bus.is_double_speed());
+    assert_eq!(cpu.bus.read_8(0xff4d), 0xfe);
+
+    // The PPU keeps its speed, a line lasts twice as many CPU clock cycles
+    run_until_line(&mut cpu.bus, 1);
+    for _ in 0..2 * 456 - 1 {
+        cpu.bus.tick();
+    }
+    assert_eq!(cpu.bus.peek(0xff44), 1);
+    cpu.bus.tick();
+    assert_eq!(cpu.bus.peek(0xff44), 2);
+}
+
+#[test]
+fn no_speed_switch_on_dmg() {
+    let mut bus = Bus::new();
+    bus.write_8(0xff4d, 0x01);
+    assert!(!bus.switch_speed());
+    assert!(!bus.is_double_speed());
+}
+
+#[test]
+fn general_purpose_vram_dma_halts_the_cpu() {
+    let mut bus = cgb_bus();
+    for offset in 0..0x20 {
+        bus.write_8(0xc000 + offset, offset as u8 + 1);
+    }
+    bus.write_8(0xff4f, 0x01);
+    // The lower 4 bits of the addresses and the upper 3 of the destination are ignored
+    bus.write_8(0xff51, 0xc0);
+    bus.write_8(0xff52, 0x0f);
+    bus.write_8(0xff53, 0xe0);
+    bus.write_8(0xff54, 0x1f);
+    bus.write_8(0xff55, 0x01);
+    assert!(bus.is_cpu_halted());
+    assert_eq!(bus.read_8(0xff55), 0x01);
+
+    // 2 bytes per M-cycle
+    let mut cycles = 0;
+    while bus.is_cpu_halted() {
+        bus.tick();
+        cycles += 1;
+    }
+    assert_eq!(cycles, 64);
+    assert_eq!(bus.read_8(0xff55), 0xff);
+    assert_eq!(bus.read_8(0x8010), 0x01);
+    assert_eq!(bus.read_8(0x802f), 0x20);
+    bus.write_8(0xff4f, 0x00);
+    assert_eq!(bus.read_8(0x8010), 0x00);
+}
+
+#[test]
+fn hblank_vram_dma_copies_a_block_per_line() {
+    let mut bus = cgb_bus();
+    for offset in 0..0x30 {
+        bus.write_8(0xc000 + offset, 0xaa);
+    }
+    bus.write_8(0xff51, 0xc0);
+    bus.write_8(0xff52, 0x00);
+    bus.write_8(0xff53, 0x00);
+    bus.write_8(0xff54, 0x00);
+    bus.write_8(0xff40, 0x91);
+    bus.write_8(0xff55, 0x82);
+    assert_eq!(bus.read_8(0xff55), 0x02);
+    assert!(!bus.is_cpu_halted());
+
+    run_until_mode(&mut bus, 0);
+    assert!(bus.is_cpu_halted());
+    while bus.is_cpu_halted() {
+        bus.tick();
+    }
+    assert_eq!(bus.read_8(0xff55), 0x01);
+    assert_eq!(bus.peek(0x800f), 0xaa);
+    assert_eq!(bus.peek(0x8010), 0x00);
+
+    // Cancelled before the next line
+    bus.write_8(0xff55, 0x00);
+    assert_eq!(bus.read_8(0xff55), 0x81);
+    run_until_line(&mut bus, 3);
+    assert_eq!(bus.peek(0x8010), 0x00);
+}
@@ -0,0 +1,39 @@
+//! Helpers shared by the integration tests, each test only uses some of them
+#![allow(dead_code)]
+
+use gb_emu::gameboy::bus::{Bus, MemoryBus};
+use gb_emu::gameboy::ppu::FRAME_CYCLES;
+
+/// 15-bit CGB and SGB colors
+pub const RED: u16 = 0x001f;
+pub const GREEN: u16 = 0x03e0;
+pub const BLUE: u16 = 0x7c00;
+
+/// Tick until LY reaches the line, one clock cycle at a time
+pub fn run_until_line(bus: &mut Bus, line: u8) {
+    for _ in 0..FRAME_CYCLES {
+        if bus.peek(0xff44) == line {
+            return;
+        }
+        bus.tick();
+    }
+    panic!("Line {} never reached", line);
+}
+
+/// Tick until STAT reports the mode, one clock cycle at a time
+pub fn run_until_mode(bus: &mut Bus, mode: u8) {
+    for _ in 0..FRAME_CYCLES {
+        if bus.peek(0xff41) & 0x3 == mode {
+            return;
+        }
+        bus.tick();
+    }
+    panic!("Mode {} never reached", mode);
+}
+
+/// Set a color through BCPS/BCPD (0xff68) or OCPS/OCPD (0xff6a)
+pub fn write_color(bus: &mut Bus, index_register: u16, palette: u8, color: u8, value: u16) {
+    bus.write_8(index_register, 0x80 | (palette * 8 + color * 2));
+    bus.write_8(index_register + 1, value as u8);
+    bus.write_8(index_register + 1, (value >> 8) as u8);
+}
@@ -0,0 +1,325 @@
+//! Runs public domain test ROMs headlessly and summarizes the results.
+//!
+//! ROMs are not distributed with the emulator, put them in tests/roms (or point
+//! GB_TEST_ROMS to another directory) following this layout:
+//!
+//! - mooneye/**/*.gb : mooneye-test-suite, result read from the registers after LD B,B
+//! - blargg/**/*.gb : blargg's tests, result read from the serial output
+//! - dmg-acid2/dmg-acid2.gb and dmg-acid2/dmg-acid2.png : frame compared to the reference
+//!
+//! The test is ignored by default since it needs the ROMs, opt in with
+//! `GB_TEST_ROMS=<directory> cargo test --test conformance -- --ignored --nocapture` to
+//! see the summary table, which is also saved in conformance.md under the cargo target
+//! directory.
+
+use gb_emu::gameboy::bus::serial::SerialDevice;
+use gb_emu::gameboy::ppu::{SCREEN_HEIGHT, SCREEN_WIDTH};
+use gb_emu::gameboy::GameBoy;
+use std::cell::RefCell;
+use std::fmt;
+use std::fs::File;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// ROMs, relative to the ROM directory, that passed once and must not regress
+const KNOWN_PASSING: &[&str] = &[];
+
+const MOONEYE_MAX_FRAMES: u32 = 600;
+const BLARGG_MAX_FRAMES: u32 = 3600;
+const ACID2_MAX_FRAMES: u32 = 120;
+
+/// Registers set by mooneye tests on success, following the Fibonacci sequence
+const MOONEYE_PASS_SIGNATURE: [u8; 6] = [3, 5, 8, 13, 21, 34];
+
+/// Shades in the dmg-acid2 reference image, from white to black
+const REFERENCE_SHADES: [u8; 4] = [0xff, 0xaa, 0x55, 0x00];
+
+#[derive(Clone, Copy)]
+enum Suite {
+    Mooneye,
+    Blargg,
+    DmgAcid2,
+}
+
+impl fmt::Display for Suite {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Suite::Mooneye => write!(f, "mooneye"),
+            Suite::Blargg => write!(f, "blargg"),
+            Suite::DmgAcid2 => write!(f, "dmg-acid2"),
+        }
+    }
+}
+
+enum Outcome {
+    Pass,
+    Fail(String),
+    Timeout,
+    Crash(String),
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Pass => write!(f, "pass"),
+            Outcome::Fail(_) => write!(f, "FAIL"),
+            Outcome::Timeout => write!(f, "TIMEOUT"),
+            Outcome::Crash(_) => write!(f, "CRASH"),
+        }
+    }
+}
+
+impl Outcome {
+    fn details(&self) -> &str {
+        match self {
+            Outcome::Fail(details) | Outcome::Crash(details) => details,
+            _ => "",
+        }
+    }
+}
+
+struct TestRom {
+    suite: Suite,
+    name: String,
+    path: PathBuf,
+}
+
+/// Link port device keeping everything the ROM sends
+#[derive(Clone, Default)]
+struct SerialCapture {
+    output: Rc<RefCell<Vec<u8>>>,
+}
+
+impl SerialDevice for SerialCapture {
+    fn exchange(&mut self, value: u8) -> u8 {
+        self.output.borrow_mut().push(value);
+        0xff
+    }
+}
+
+fn rom_directory() -> PathBuf {
+    match std::env::var("GB_TEST_ROMS") {
+        Ok(directory) => PathBuf::from(directory),
+        Err(_) => Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("roms"),
+    }
+}
+
+fn find_roms(directory: &Path, roms: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            find_roms(&path, roms);
+        } else if path.extension().is_some_and(|extension| extension == "gb") {
+            roms.push(path);
+        }
+    }
+}
+
+fn collect_test_roms(rom_directory: &Path) -> Vec<TestRom> {
+    let mut test_roms = vec![];
+    for (suite, subdirectory) in [
+        (Suite::Mooneye, "mooneye"),
+        (Suite::Blargg, "blargg"),
+        (Suite::DmgAcid2, "dmg-acid2"),
+    ] {
+        let mut roms = vec![];
+        find_roms(&rom_directory.join(subdirectory), &mut roms);
+        roms.sort();
+        for path in roms {
+            let name = path
+                .strip_prefix(rom_directory)
+                .unwrap()
+                .to_string_lossy()
+                .replace('\\', "/");
+            test_roms.push(TestRom { suite, name, path });
+        }
+    }
+    test_roms
+}
+
+fn run_mooneye(gameboy: &mut GameBoy) -> Outcome {
+    for _ in 0..MOONEYE_MAX_FRAMES {
+        gameboy.run_frame();
+        if let Some(fault) = gameboy.take_cpu_fault() {
+            return Outcome::Crash(fault.to_string());
+        }
+        if gameboy.is_breakpoint_hit() {
+            let registers = gameboy.get_registers();
+            let signature = [
+                registers.b,
+                registers.c,
+                registers.d,
+                registers.e,
+                registers.h,
+                registers.l,
+            ];
+            if signature == MOONEYE_PASS_SIGNATURE {
+                return Outcome::Pass;
+            }
+            return Outcome::Fail(format!(
+                "B:{:02x} C:{:02x} D:{:02x} E:{:02x} H:{:02x} L:{:02x}",
+                signature[0], signature[1], signature[2], signature[3], signature[4], signature[5]
+            ));
+        }
+    }
+    Outcome::Timeout
+}
+
+fn run_blargg(gameboy: &mut GameBoy) -> Outcome {
+    let capture = SerialCapture::default();
+    gameboy.connect_serial(Box::new(capture.clone()));
+    for _ in 0..BLARGG_MAX_FRAMES {
+        gameboy.run_frame();
+        if let Some(fault) = gameboy.take_cpu_fault() {
+            return Outcome::Crash(fault.to_string());
+        }
+        let output = String::from_utf8_lossy(&capture.output.borrow()).to_string();
+        if output.contains("Passed") {
+            return Outcome::Pass;
+        }
+        if output.contains("Failed") {
+            return Outcome::Fail(output.split_whitespace().collect::<Vec<_>>().join(" "));
+        }
+    }
+    Outcome::Timeout
+}
+
+/// Reference image pixels converted to shades (0-3)
+fn load_reference(path: &Path) -> Result<Vec<u8>, String> {
+    let file = File::open(path).map_err(|error| format!("{}: {}", path.display(), error))?;
+    let decoder = png::Decoder::new(file);
+    let mut reader = decoder.read_info().map_err(|error| error.to_string())?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader
+        .next_frame(&mut buffer)
+        .map_err(|error| error.to_string())?;
+    if info.width as usize != SCREEN_WIDTH || info.height as usize != SCREEN_HEIGHT {
+        return Err(format!("unexpected size {}x{}", info.width, info.height));
+    }
+    if info.bit_depth != png::BitDepth::Eight {
+        return Err(format!("unsupported bit depth {:?}", info.bit_depth));
+    }
+    let channels = info.color_type.samples();
+    Ok(buffer[..info.buffer_size()]
+        .chunks(channels)
+        .map(|pixel| {
+            // Use the first channel only, the reference image is gray
+            REFERENCE_SHADES
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, shade)| (**shade as i16 - pixel[0] as i16).abs())
+                .map(|(index, _)| index as u8)
+                .unwrap()
+        })
+        .collect())
+}
+
+fn run_dmg_acid2(gameboy: &mut GameBoy, rom_path: &Path) -> Outcome {
+    let reference = match load_reference(&rom_path.with_extension("png")) {
+        Ok(reference) => reference,
+        Err(error) => return Outcome::Crash(format!("reference image: {}", error)),
+    };
+    for _ in 0..ACID2_MAX_FRAMES {
+        gameboy.run_frame();
+        if let Some(fault) = gameboy.take_cpu_fault() {
+            return Outcome::Crash(fault.to_string());
+        }
+        if gameboy.is_breakpoint_hit() {
+            // Let the last frame be fully drawn
+            gameboy.run_frame();
+            let differences = gameboy
+                .get_frame_buffer()
+                .iter()
+                .zip(reference.iter())
+                .filter(|(pixel, expected)| pixel != expected)
+                .count();
+            if differences == 0 {
+                return Outcome::Pass;
+            }
+            return Outcome::Fail(format!("{} pixels differ", differences));
+        }
+    }
+    Outcome::Timeout
+}
+
+fn run_test_rom(test_rom: &TestRom) -> Outcome {
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut gameboy = GameBoy::headless();
+        gameboy.load(test_rom.path.to_string_lossy().to_string());
+        match test_rom.suite {
+            Suite::Mooneye => run_mooneye(&mut gameboy),
+            Suite::Blargg => run_blargg(&mut gameboy),
+            Suite::DmgAcid2 => run_dmg_acid2(&mut gameboy, &test_rom.path),
+        }
+    }));
+    match result {
+        Ok(outcome) => outcome,
+        Err(error) => {
+            let message = if let Some(message) = error.downcast_ref::<String>() {
+                message.clone()
+            } else if let Some(message) = error.downcast_ref::<&str>() {
+                message.to_string()
+            } else {
+                String::from("panic")
+            };
+            Outcome::Crash(message)
+        }
+    }
+}
+
+fn summary(results: &[(&TestRom, Outcome)]) -> String {
+    let passed = results
+        .iter()
+        .filter(|(_, outcome)| matches!(outcome, Outcome::Pass))
+        .count();
+    let mut table = String::from("| Suite | ROM | Result | Details |\n|---|---|---|---|\n");
+    for (test_rom, outcome) in results {
+        table += &format!(
+            "| {} | {} | {} | {} |\n",
+            test_rom.suite,
+            test_rom.name,
+            outcome,
+            outcome.details().replace('|', "\\|")
+        );
+    }
+    table += &format!("\n{}/{} passed\n", passed, results.len());
+    table
+}
+
+#[test]
+#[ignore = "needs the test ROMs, see the module documentation"]
+fn conformance() {
+    let rom_directory = rom_directory();
+    let test_roms = collect_test_roms(&rom_directory);
+    assert!(
+        !test_roms.is_empty(),
+        "No test ROM found in {}",
+        rom_directory.display()
+    );
+
+    let results: Vec<(&TestRom, Outcome)> = test_roms
+        .iter()
+        .map(|test_rom| (test_rom, run_test_rom(test_rom)))
+        .collect();
+
+    let table = summary(&results);
+    println!("{}", table);
+    let report = Path::new(env!("CARGO_TARGET_TMPDIR")).join("conformance.md");
+    std::fs::write(&report, &table).unwrap();
+    println!("Summary saved in {}", report.display());
+
+    let regressions: Vec<&str> = results
+        .iter()
+        .filter(|(test_rom, outcome)| {
+            KNOWN_PASSING.contains(&test_rom.name.as_str()) && !matches!(outcome, Outcome::Pass)
+        })
+        .map(|(test_rom, _)| test_rom.name.as_str())
+        .collect();
+    assert!(regressions.is_empty(), "Regressions: {:?}", regressions);
+}
@@ -0,0 +1,141 @@
+//! Small Game Boy programs run on a flat memory bus
+
+use gb_emu::gameboy::bus::{FlatBus, MemoryBus};
+use gb_emu::gameboy::cpu::Cpu;
+
+const START_ADDRESS: u16 = 0x0100;
+
+/// Load the program at START_ADDRESS and run it until PC reaches its end
+fn run(program: &[u8]) -> Cpu<FlatBus> {
+    let mut bus = FlatBus::new();
+    bus.load(START_ADDRESS, program);
+    let mut cpu = Cpu::new(bus);
+    cpu.registers.pc = START_ADDRESS;
+    cpu.registers.sp = 0xfffe;
+    while cpu.registers.pc < START_ADDRESS + program.len() as u16 {
+        cpu.execute();
+    }
+    cpu
+}
+
+#[test]
+fn load_and_add() {
+    // ld a,$05 ; ld b,$03 ; add a,b ; ld [$c000],a
+    let mut cpu = run(&[0x3e, 0x05, 0x06, 0x03, 0x80, 0xea, 0x00, 0xc0]);
+    assert_eq!(cpu.registers.a, 0x08);
+    assert_eq!(cpu.bus.read_8(0xc000), 0x08);
+    assert!(!cpu.flags.zero);
+    assert!(!cpu.flags.carry);
+}
+
+#[test]
+fn counting_loop() {
+    // ld b,$0a ; xor a ; loop: inc a ; dec b ; jr nz,loop
+    let cpu = run(&[0x06, 0x0a, 0xaf, 0x3c, 0x05, 0x20, 0xfc]);
+    assert_eq!(cpu.registers.a, 0x0a);
+    assert_eq!(cpu.registers.b, 0x00);
+    assert!(cpu.flags.zero);
+}
+
+#[test]
+fn bus_ticked_for_every_cycle() {
+    // ld a,$05 (8) ; ld [$c000],a (16) ; swap a (8) ; ld hl,$c000 (12) ; bit 0,[hl] (12)
+    let cpu = run(&[
+        0x3e, 0x05, 0xea, 0x00, 0xc0, 0xcb, 0x37, 0x21, 0x00, 0xc0, 0xcb, 0x46,
+    ]);
+    assert_eq!(cpu.bus.get_cycles(), 56);
+    assert_eq!(cpu.registers.a, 0x50);
+    assert!(!cpu.flags.zero);
+}
+
+#[test]
+fn bcd_addition() {
+    // ld a,$45 ; add a,$38 ; daa
+    let cpu = run(&[0x3e, 0x45, 0xc6, 0x38, 0x27]);
+    assert_eq!(cpu.registers.a, 0x83);
+    assert!(!cpu.flags.carry);
+
+    // ld a,$99 ; add a,$01 ; daa
+    let cpu = run(&[0x3e, 0x99, 0xc6, 0x01, 0x27]);
+    assert_eq!(cpu.registers.a, 0x00);
+    assert!(cpu.flags.zero);
+    assert!(cpu.flags.carry);
+}
+
+#[test]
+fn halt_bug() {
+    // di ; ld a,$01 ; ld [IE],a ; ld [IF],a ; halt ; inc a
+    let cpu = run(&[
+        0xf3, 0x3e, 0x01, 0xea, 0xff, 0xff, 0xea, 0x0f, 0xff, 0x76, 0x3c,
+    ]);
+    assert!(!cpu.is_halted);
+    // The byte following HALT is read twice
+    assert_eq!(cpu.registers.a, 0x03);
+}
+
+#[test]
+fn illegal_opcode_locks_up() {
+    // nop ; illegal $d3 ; inc a
+    let mut bus = FlatBus::new();
+    bus.load(START_ADDRESS, &[0x00, 0xd3, 0x3c]);
+    let mut cpu = Cpu::new(bus);
+    cpu.registers.pc = START_ADDRESS;
+    for _ in 0..10 {
+        cpu.next();
+    }
+    assert!(cpu.is_locked);
+    assert_eq!(cpu.registers.a, 0x00);
+
+    let fault = cpu.take_fault().expect("no fault reported");
+    assert_eq!(fault.pc, START_ADDRESS + 1);
+    assert_eq!(fault.opcode, 0xd3);
+    assert_eq!(fault.trace, vec![START_ADDRESS, START_ADDRESS + 1]);
+    assert!(cpu.take_fault().is_none());
+}
+
+#[test]
+fn push_and_pop_below_sp() {
+    // ld a,$1f ; ld [IE],a ; ld bc,$1234 ; push bc ; pop de
+    let mut cpu = run(&[0x3e, 0x1f, 0xea, 0xff, 0xff, 0x01, 0x34, 0x12, 0xc5, 0xd1]);
+    assert_eq!(cpu.registers.get_de(), 0x1234);
+    assert_eq!(cpu.registers.sp, 0xfffe);
+    // The high byte goes right below SP, IE above it is left alone
+    assert_eq!(cpu.bus.read_8(0xfffd), 0x12);
+    assert_eq!(cpu.bus.read_8(0xfffc), 0x34);
+    assert_eq!(cpu.bus.read_8(0xffff), 0x1f);
+}
+
+#[test]
+fn interrupt_dispatch_pushes_pc() {
+    // ld a,$04 ; ld [IE],a ; ld [IF],a ; ei ; nop
+    let mut bus = FlatBus::new();
+    bus.load(
+        START_ADDRESS,
+        &[0x3e, 0x04, 0xea, 0xff, 0xff, 0xea, 0x0f, 0xff, 0xfb, 0x00],
+    );
+    let mut cpu = Cpu::new(bus);
+    cpu.set_interrupt_master_enabled(false);
+    cpu.registers.pc = START_ADDRESS;
+    cpu.registers.sp = 0xfffe;
+    while cpu.registers.pc != 0x0050 {
+        cpu.next();
+    }
+    // The interrupt is taken after the instruction following EI
+    assert_eq!(cpu.registers.sp, 0xfffc);
+    assert_eq!(cpu.bus.read_8(0xfffd), 0x01);
+    assert_eq!(cpu.bus.read_8(0xfffc), 0x0a);
+    assert_eq!(cpu.bus.read_8(0xffff), 0x04);
+}
+
+#[test]
+fn call_pushes_the_address_after_its_operand() {
+    // call $0104 ; nop ; sub: nop
+    let mut cpu = run(&[0xcd, 0x04, 0x01, 0x00, 0x00]);
+    assert_eq!(cpu.registers.sp, 0xfffc);
+    assert_eq!(cpu.bus.read_8(0xfffd), 0x01);
+    assert_eq!(cpu.bus.read_8(0xfffc), 0x03);
+    assert_eq!(
+        cpu.call_stack.last().unwrap().return_address,
+        START_ADDRESS + 3
+    );
+}
@@ -0,0 +1,177 @@
+//! Tile, tile map and OAM views rendered without a window
+
+mod common;
+
+use common::{write_color, BLUE, GREEN, RED};
+use gb_emu::gameboy::bus::{Bus, MemoryBus};
+use gb_emu::gameboy::debug_view::oam::{OamViewer, TRANSPARENT};
+use gb_emu::gameboy::debug_view::tile_map::{TileMapViewer, VIEWPORT, WINDOW};
+use gb_emu::gameboy::debug_view::tiles::TileViewer;
+use gb_emu::gameboy::debug_view::{DebugView, Palette, SHADES};
+use gb_emu::gameboy::palette::{to_rgb, ColorCorrection};
+use gb_emu::gameboy::ppu::Model;
+use sdl2::keyboard::Keycode;
+
+fn cgb_bus() -> Bus {
+    let mut bus = Bus::new();
+    bus.set_model(Model::Cgb);
+    bus
+}
+
+/// Tile whose left column has color 3 and the others color 0
+fn write_left_column_tile(bus: &mut Bus, address: u16) {
+    for line in 0..8 {
+        bus.write_8(address + line * 2, 0x80);
+        bus.write_8(address + line * 2 + 1, 0x80);
+    }
+}
+
+#[test]
+fn tiles_with_palette() {
+    let mut bus = Bus::new();
+    bus.write_8(0xff47, 0b11_10_01_00);
+    bus.write_8(0xff48, 0b00_00_00_00);
+    write_left_column_tile(&mut bus, 0x8010);
+    let mut viewer = TileViewer::new();
+
+    // Tile 1 is the second cell of the first row, after the header and the grid
+    let (x, y) = (2 + 8 * 2 + 1, 2 * 12 + 1);
+    let image = viewer.render(&bus);
+    assert_eq!(image.get_pixel(x, y), SHADES[3]);
+    assert_eq!(image.get_pixel(x + 2, y), SHADES[0]);
+
+    viewer.handle_key(Keycode::P, false, &mut bus);
+    let image = viewer.render(&bus);
+    assert_eq!(image.get_pixel(x, y), SHADES[0]);
+
+    viewer.handle_key(Keycode::Down, false, &mut bus);
+    viewer.handle_key(Keycode::Left, false, &mut bus);
+    assert_eq!(viewer.get_cursor(), 15);
+}
+
+#[test]
+fn tile_maps_with_viewport_and_window() {
+    let mut bus = Bus::new();
+    bus.write_8(0xff47, 0b11_10_01_00);
+    // 8000 addressing, window on the 9c00 map
+    bus.write_8(0xff40, 0b1111_0001);
+    bus.write_8(0xff43, 16);
+    bus.write_8(0xff42, 8);
+    bus.write_8(0xff4b, 7 + 80);
+    bus.write_8(0xff4a, 100);
+    write_left_column_tile(&mut bus, 0x8010);
+    bus.write_8(0x9800 + 32 + 4, 0x01);
+
+    let image = TileMapViewer::new().render(&bus);
+    let top = 2 * 12;
+    // Tile at column 4 and row 1 of the map
+    assert_eq!(image.get_pixel(4 * 8, top + 9), SHADES[3]);
+    assert_eq!(image.get_pixel(4 * 8 + 1, top + 9), SHADES[0]);
+    // Viewport from (16, 8) to (175, 151)
+    assert_eq!(image.get_pixel(16, top + 8), VIEWPORT);
+    assert_eq!(image.get_pixel(175, top + 151), VIEWPORT);
+    assert_ne!(image.get_pixel(176, top + 8), VIEWPORT);
+    // 80x44 pixels of the window are shown
+    let left = 256 + 8;
+    assert_eq!(image.get_pixel(left + 79, top), WINDOW);
+    assert_eq!(image.get_pixel(left, top + 43), WINDOW);
+    assert_ne!(image.get_pixel(left + 80, top), WINDOW);
+}
+
+#[test]
+fn sprites_with_flips() {
+    let mut bus = Bus::new();
+    bus.write_8(0xff49, 0b11_10_01_00);
+    write_left_column_tile(&mut bus, 0x8020);
+    // Sprite 1, tile 2 with OBP1 flipped horizontally
+    bus.write_8(0xfe04, 16);
+    bus.write_8(0xfe05, 8);
+    bus.write_8(0xfe06, 0x02);
+    bus.write_8(0xfe07, 0b0011_0000);
+
+    let image = OamViewer::new().render(&bus);
+    let (x, y) = OamViewer::get_row_position(1);
+    let (x, y) = (x + 4, y + 2);
+    assert_eq!(image.get_pixel(x, y), TRANSPARENT);
+    assert_eq!(image.get_pixel(x + 7 * 2, y), SHADES[3]);
+}
+
+#[test]
+fn cgb_tile_map_attributes() {
+    let mut bus = cgb_bus();
+    write_color(&mut bus, 0xff68, 2, 0, BLUE);
+    write_color(&mut bus, 0xff68, 2, 3, RED);
+    bus.write_8(0x9800 + 32 + 4, 0x01);
+    // Palette 2, tile data in bank 1 and flipped horizontally
+    bus.write_8(0xff4f, 0x01);
+    write_left_column_tile(&mut bus, 0x8010);
+    bus.write_8(0x9800 + 32 + 4, 0b0010_1010);
+    bus.write_8(0xff40, 0b1001_0001);
+
+    let image = TileMapViewer::new().render(&bus);
+    let top = 2 * 12;
+    assert_eq!(
+        image.get_pixel(4 * 8 + 7, top + 9),
+        to_rgb(RED, ColorCorrection::Raw)
+    );
+    assert_eq!(
+        image.get_pixel(4 * 8, top + 9),
+        to_rgb(BLUE, ColorCorrection::Raw)
+    );
+}
+
+#[test]
+fn cgb_sprite_bank_and_palette() {
+    let mut bus = cgb_bus();
+    write_color(&mut bus, 0xff6a, 3, 3, GREEN);
+    bus.write_8(0xff4f, 0x01);
+    write_left_column_tile(&mut bus, 0x8020);
+    // Sprite 1, tile 2 from bank 1 with OBJ palette 3
+    bus.write_8(0xfe04, 16);
+    bus.write_8(0xfe05, 8);
+    bus.write_8(0xfe06, 0x02);
+    bus.write_8(0xfe07, 0b0000_1011);
+
+    let image = OamViewer::new().render(&bus);
+    let (x, y) = OamViewer::get_row_position(1);
+    let (x, y) = (x + 4, y + 2);
+    assert_eq!(image.get_pixel(x, y), to_rgb(GREEN, ColorCorrection::Raw));
+    assert_eq!(image.get_pixel(x + 2, y), TRANSPARENT);
+}
+
+#[test]
+fn cgb_palettes_follow_raw() {
+    let mut bus = cgb_bus();
+    let mut viewer = TileViewer::new();
+    for _ in 0..4 {
+        viewer.handle_key(Keycode::P, false, &mut bus);
+    }
+    assert_eq!(viewer.get_palette(), Palette::Bg(0));
+    for _ in 0..8 {
+        viewer.handle_key(Keycode::P, false, &mut bus);
+    }
+    assert_eq!(viewer.get_palette(), Palette::Obj(0));
+    for _ in 0..8 {
+        viewer.handle_key(Keycode::P, false, &mut bus);
+    }
+    assert_eq!(viewer.get_palette(), Palette::Raw);
+}
+
+#[test]
+fn render_to_png() {
+    let bus = Bus::new();
+    let image = OamViewer::new().render(&bus);
+    let path = std::env::temp_dir().join(format!("gb_emu_oam_{}.png", std::process::id()));
+    image.write_png(&path).unwrap();
+
+    let decoder = png::Decoder::new(std::fs::File::open(&path).unwrap());
+    let mut reader = decoder.read_info().unwrap();
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut pixels).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(
+        (info.width as usize, info.height as usize),
+        (image.width, image.height)
+    );
+    assert_eq!(pixels[..info.buffer_size()], image.pixels[..]);
+}
@@ -0,0 +1,173 @@
+//! Debugger commands on a program running in work RAM
+
+use gb_emu::gameboy::bus::{Bus, MemoryBus};
+use gb_emu::gameboy::cpu::Cpu;
+use gb_emu::gameboy::debugger::{Debugger, DebuggerAction};
+use gb_emu::gameboy::disassembler::Symbols;
+
+const START_ADDRESS: u16 = 0xc000;
+const MAX_STEPS: usize = 10_000;
+
+/// c000: ld a,$00 ; call $c010 ; ld [$c100],a ; jr @
+/// c010: inc a ; call $c020 ; ret
+/// c020: inc a ; ret
+const PROGRAM: [(u16, &[u8]); 3] = [
+    (
+        0xc000,
+        &[0x3e, 0x00, 0xcd, 0x10, 0xc0, 0xea, 0x00, 0xc1, 0x18, 0xfe],
+    ),
+    (0xc010, &[0x3c, 0xcd, 0x20, 0xc0, 0xc9]),
+    (0xc020, &[0x3c, 0xc9]),
+];
+
+fn load(program: &[(u16, &[u8])]) -> Cpu<Bus> {
+    let mut cpu = Cpu::new(Bus::new());
+    for (address, code) in program {
+        for (offset, value) in code.iter().enumerate() {
+            cpu.bus.write_8(address + offset as u16, *value);
+        }
+    }
+    cpu.registers.pc = START_ADDRESS;
+    cpu.registers.sp = 0xdffe;
+    cpu
+}
+
+fn command(debugger: &mut Debugger, cpu: &mut Cpu<Bus>, line: &str) -> String {
+    let mut out = Vec::new();
+    debugger.execute(cpu, line, &mut out);
+    String::from_utf8(out).unwrap()
+}
+
+/// Resume with the command and run until the debugger stops, returns the reason
+fn resume(debugger: &mut Debugger, cpu: &mut Cpu<Bus>, line: &str) -> String {
+    let mut out = Vec::new();
+    assert_eq!(
+        debugger.execute(cpu, line, &mut out),
+        DebuggerAction::Resume
+    );
+    for _ in 0..MAX_STEPS {
+        cpu.next();
+        if let Some(reason) = debugger.check(cpu) {
+            return reason;
+        }
+    }
+    panic!("{} did not stop", line);
+}
+
+#[test]
+fn breakpoint_and_backtrace() {
+    let mut cpu = load(&PROGRAM);
+    let mut debugger = Debugger::new(Symbols::default());
+    assert_eq!(
+        command(&mut debugger, &mut cpu, "break $c020"),
+        "Breakpoint 1: $c020\n"
+    );
+
+    assert_eq!(
+        resume(&mut debugger, &mut cpu, "continue"),
+        "Breakpoint 1: $c020"
+    );
+    assert_eq!(cpu.registers.pc, 0xc020);
+    assert_eq!(
+        command(&mut debugger, &mut cpu, "bt"),
+        "#0  $c020 in $c020\n#1  $c014 in $c010\n#2  $c005\n"
+    );
+
+    assert_eq!(resume(&mut debugger, &mut cpu, "finish"), "");
+    assert_eq!(cpu.registers.pc, 0xc014);
+    assert_eq!(resume(&mut debugger, &mut cpu, "finish"), "");
+    assert_eq!(cpu.registers.pc, 0xc005);
+    assert_eq!(cpu.registers.a, 2);
+    assert!(cpu.call_stack.is_empty());
+    assert_eq!(
+        command(&mut debugger, &mut cpu, "finish"),
+        "Not in a subroutine\n"
+    );
+}
+
+#[test]
+fn conditional_breakpoint() {
+    // ld b,$00 ; loop: inc b ; jr loop
+    let mut cpu = load(&[(START_ADDRESS, &[0x06, 0x00, 0x04, 0x18, 0xfd])]);
+    let mut debugger = Debugger::new(Symbols::default());
+    assert_eq!(
+        command(&mut debugger, &mut cpu, "b $c002 if b==5"),
+        "Breakpoint 1: $c002 if b == $05\n"
+    );
+    assert_eq!(
+        resume(&mut debugger, &mut cpu, "c"),
+        "Breakpoint 1: $c002 if b == $05"
+    );
+    assert_eq!(cpu.registers.b, 5);
+    assert_eq!(
+        command(&mut debugger, &mut cpu, "b $c002 if x == 1"),
+        "Unknown register x\n"
+    );
+}
+
+#[test]
+fn watchpoints() {
+    let mut cpu = load(&PROGRAM);
+    let mut debugger = Debugger::new(Symbols::default());
+    command(&mut debugger, &mut cpu, "rwatch $c021");
+    command(&mut debugger, &mut cpu, "watch $c100 $c1ff");
+    assert_eq!(
+        command(&mut debugger, &mut cpu, "info"),
+        "1   watch   read $c021\n2   watch   write $c100-$c1ff\n"
+    );
+
+    // Fetching the RET opcode is a read
+    assert_eq!(
+        resume(&mut debugger, &mut cpu, "c"),
+        "Watchpoint 1: read $c021 = $c9"
+    );
+    command(&mut debugger, &mut cpu, "delete 1");
+    assert_eq!(
+        resume(&mut debugger, &mut cpu, "c"),
+        "Watchpoint 2: write $c100 = $02"
+    );
+    assert_eq!(cpu.registers.pc, 0xc008);
+}
+
+#[test]
+fn step_and_next() {
+    let mut cpu = load(&PROGRAM);
+    let mut debugger = Debugger::new(Symbols::default());
+    assert_eq!(resume(&mut debugger, &mut cpu, "step"), "");
+    assert_eq!(cpu.registers.pc, 0xc002);
+    assert_eq!(resume(&mut debugger, &mut cpu, "next"), "");
+    assert_eq!(cpu.registers.pc, 0xc005);
+    assert_eq!(cpu.registers.a, 2);
+
+    let mut cpu = load(&PROGRAM);
+    assert_eq!(resume(&mut debugger, &mut cpu, "s 2"), "");
+    assert_eq!(cpu.registers.pc, 0xc010);
+    // An empty line repeats the last command
+    assert_eq!(resume(&mut debugger, &mut cpu, ""), "");
+    assert_eq!(cpu.registers.pc, 0xc020);
+    assert_eq!(cpu.call_stack.len(), 2);
+}
+
+#[test]
+fn edit_registers_and_memory() {
+    let mut cpu = load(&PROGRAM);
+    let mut debugger = Debugger::new(Symbols::parse("00:c010 Increment\n"));
+    command(&mut debugger, &mut cpu, "set a $42");
+    command(&mut debugger, &mut cpu, "set hl 0x1234");
+    assert_eq!(cpu.registers.a, 0x42);
+    assert_eq!(cpu.registers.get_hl(), 0x1234);
+    assert_eq!(
+        command(&mut debugger, &mut cpu, "set b 256"),
+        "256 does not fit in b\n"
+    );
+
+    command(&mut debugger, &mut cpu, "write $c100 1 $ff");
+    assert_eq!(
+        command(&mut debugger, &mut cpu, "x $c100 3"),
+        "c100  01 ff 00\n"
+    );
+    assert_eq!(
+        command(&mut debugger, &mut cpu, "disas Increment 1"),
+        "Increment:\n   c010  3c        inc a\n"
+    );
+}
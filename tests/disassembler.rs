@@ -0,0 +1,93 @@
+use gb_emu::gameboy::disassembler::{disassemble, Symbols};
+
+fn disassemble_bytes(address: u16, bytes: &[u8], symbols: &Symbols) -> String {
+    let instruction = disassemble(address, 1, symbols, |read_address| {
+        bytes
+            .get(read_address.wrapping_sub(address) as usize)
+            .copied()
+            .unwrap_or(0)
+    });
+    assert_eq!(instruction.bytes.len(), bytes.len(), "{}", instruction.text);
+    instruction.text
+}
+
+#[test]
+fn operands() {
+    let symbols = Symbols::default();
+    let cases: &[(&[u8], &str)] = &[
+        (&[0x00], "nop"),
+        (&[0x2a], "ld a,[hl+]"),
+        (&[0x32], "ld [hl-],a"),
+        (&[0x06, 0x12], "ld b,$12"),
+        (&[0x21, 0x34, 0x12], "ld hl,$1234"),
+        (&[0xea, 0x00, 0xc0], "ld [$c000],a"),
+        (&[0x08, 0x00, 0xc0], "ld [$c000],sp"),
+        (&[0xe0, 0x40], "ldh [$ff40],a"),
+        (&[0xf0, 0x44], "ldh a,[$ff44]"),
+        (&[0xe2], "ldh [c],a"),
+        (&[0xf8, 0xfe], "ld hl,sp-2"),
+        (&[0xe8, 0x05], "add sp,5"),
+        (&[0xc3, 0x50, 0x01], "jp $0150"),
+        (&[0xc4, 0x00, 0x40], "call nz,$4000"),
+        (&[0xd8], "ret c"),
+        (&[0xff], "rst $38"),
+        (&[0x10, 0x00], "stop"),
+        (&[0xd3], "db $d3"),
+        (&[0xcb, 0x7c], "bit 7,h"),
+        (&[0xcb, 0x36], "swap [hl]"),
+    ];
+    for (bytes, expected) in cases {
+        assert_eq!(disassemble_bytes(0x0150, bytes, &symbols), *expected);
+    }
+}
+
+#[test]
+fn relative_jumps() {
+    let symbols = Symbols::default();
+    // Relative to the address following the instruction
+    assert_eq!(
+        disassemble_bytes(0x0150, &[0x18, 0x00], &symbols),
+        "jr $0152"
+    );
+    assert_eq!(
+        disassemble_bytes(0x0152, &[0x20, 0xfc], &symbols),
+        "jr nz,$0150"
+    );
+}
+
+#[test]
+fn labels() {
+    let symbols = Symbols::parse(
+        "; File generated by rgblink\n\
+         00:0150 Main\n\
+         01:4000 BankOneEntry\n\
+         02:4000 BankTwoEntry\n\
+         00:c000 wCounter\n\
+         00:ff80 hVBlankFlag ; comment\n",
+    );
+    assert_eq!(symbols.get(0, 0x0150), Some("Main"));
+    assert_eq!(symbols.get(2, 0x0150), Some("Main"));
+    assert_eq!(symbols.get(2, 0x4000), Some("BankTwoEntry"));
+    assert_eq!(symbols.get(3, 0x4000), None);
+
+    assert_eq!(
+        disassemble_bytes(0x0200, &[0xc3, 0x50, 0x01], &symbols),
+        "jp Main"
+    );
+    assert_eq!(
+        disassemble_bytes(0x0200, &[0xcd, 0x00, 0x40], &symbols),
+        "call BankOneEntry"
+    );
+    assert_eq!(
+        disassemble_bytes(0x0200, &[0xfa, 0x00, 0xc0], &symbols),
+        "ld a,[wCounter]"
+    );
+    assert_eq!(
+        disassemble_bytes(0x0200, &[0xe0, 0x80], &symbols),
+        "ldh [hVBlankFlag],a"
+    );
+    assert_eq!(
+        disassemble_bytes(0x0152, &[0x18, 0xfc], &symbols),
+        "jr Main"
+    );
+}
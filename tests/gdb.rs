@@ -0,0 +1,132 @@
+//! GDB remote serial protocol stub driven by a minimal client
+
+use gb_emu::gameboy::bus::{Bus, MemoryBus};
+use gb_emu::gameboy::cpu::Cpu;
+use gb_emu::gameboy::debugger::DebuggerAction;
+use gb_emu::gameboy::gdb::GdbStub;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::thread;
+
+const START_ADDRESS: u16 = 0xc000;
+const MAX_STEPS: usize = 1_000_000;
+
+/// ld a,$00 ; call $c010 ; ld [$c100],a ; jr @
+/// c010: inc a ; ret
+const PROGRAM: [(u16, &[u8]); 2] = [
+    (
+        0xc000,
+        &[0x3e, 0x00, 0xcd, 0x10, 0xc0, 0xea, 0x00, 0xc1, 0x18, 0xfe],
+    ),
+    (0xc010, &[0x3c, 0xc9]),
+];
+
+struct Client {
+    stream: TcpStream,
+}
+
+impl Client {
+    fn read_byte(&mut self) -> u8 {
+        let mut byte = [0];
+        self.stream.read_exact(&mut byte).unwrap();
+        byte[0]
+    }
+
+    /// Send a packet and return the reply content, resuming packets get it once execution stops
+    fn request(&mut self, data: &str) -> String {
+        let checksum = data.bytes().fold(0_u8, |sum, byte| sum.wrapping_add(byte));
+        write!(self.stream, "${}#{:02x}", data, checksum).unwrap();
+        assert_eq!(self.read_byte(), b'+');
+        self.reply()
+    }
+
+    fn reply(&mut self) -> String {
+        while self.read_byte() != b'$' {}
+        let mut reply = Vec::new();
+        loop {
+            match self.read_byte() {
+                b'#' => break,
+                byte => reply.push(byte),
+            }
+        }
+        self.read_byte();
+        self.read_byte();
+        self.stream.write_all(b"+").unwrap();
+        String::from_utf8(reply).unwrap()
+    }
+}
+
+/// Run the program with the stub until the client kills it, the client runs on its own thread
+fn run<F: FnOnce(&mut Client) + Send + 'static>(session: F) -> Cpu<Bus> {
+    let mut cpu = Cpu::new(Bus::new());
+    for (address, code) in PROGRAM {
+        for (offset, value) in code.iter().enumerate() {
+            cpu.bus.write_8(address + offset as u16, *value);
+        }
+    }
+    cpu.registers.pc = START_ADDRESS;
+    cpu.registers.sp = 0xdffe;
+
+    let mut stub = GdbStub::listen(0).unwrap();
+    let port = stub.port();
+    let client = thread::spawn(move || {
+        let mut client = Client {
+            stream: TcpStream::connect(("127.0.0.1", port)).unwrap(),
+        };
+        session(&mut client);
+        // Kill has no reply
+        write!(client.stream, "$k#6b").unwrap();
+    });
+    stub.accept().unwrap();
+    // The stub detaches and lets the CPU run if the client panics
+    for _ in 0..MAX_STEPS {
+        if stub.check(&mut cpu) == DebuggerAction::Quit {
+            client.join().unwrap();
+            return cpu;
+        }
+        cpu.next();
+    }
+    client.join().unwrap();
+    panic!("the client did not kill the target");
+}
+
+#[test]
+fn registers_and_memory() {
+    let cpu = run(|client| {
+        assert!(client
+            .request("qSupported:swbreak+")
+            .contains("qXfer:features:read+"));
+        let target = client.request("qXfer:features:read:target.xml:0,1000");
+        assert!(target.starts_with("l<?xml"));
+        assert!(target.contains("<reg name=\"pc\""));
+        assert_eq!(client.request("?"), "S05");
+        // AF BC DE HL SP PC, little endian
+        assert_eq!(client.request("g"), "0000000000000000fedf00c0");
+        assert_eq!(client.request("p5"), "00c0");
+        assert_eq!(client.request("P2=3412"), "OK");
+        assert_eq!(client.request("mc000,3"), "3e00cd");
+        assert_eq!(client.request("Mc200,2:abcd"), "OK");
+        assert_eq!(client.request("mc200,2"), "abcd");
+        assert_eq!(client.request("zz"), "E01");
+    });
+    assert_eq!(cpu.registers.get_de(), 0x1234);
+    assert_eq!(cpu.registers.sp, 0xdffe);
+    assert_eq!(cpu.registers.pc, START_ADDRESS);
+}
+
+#[test]
+fn breakpoints_and_stepping() {
+    let cpu = run(|client| {
+        assert_eq!(client.request("Z0,c010,1"), "OK");
+        assert_eq!(client.request("c"), "S05");
+        assert_eq!(client.request("p5"), "10c0");
+        assert_eq!(client.request("z0,c010,1"), "OK");
+        assert_eq!(client.request("s"), "S05");
+        assert_eq!(client.request("p5"), "11c0");
+        assert_eq!(client.request("Z2,c100,1"), "OK");
+        assert_eq!(client.request("c"), "T05watch:c100;");
+        assert_eq!(client.request("p5"), "08c0");
+    });
+    assert_eq!(cpu.registers.a, 1);
+    assert_eq!(cpu.bus.watchpoints.iter().count(), 1);
+}
@@ -0,0 +1,114 @@
+//! Runs every opcode on a flat bus and checks the M-cycles it ticks and the bytes it
+//! takes against the metadata generated from ressources/instruction_set.json
+
+use gb_emu::gameboy::bus::FlatBus;
+use gb_emu::gameboy::cpu::cb_instructions::CB_INSTRUCTION_TABLE;
+use gb_emu::gameboy::cpu::instructions::INSTRUCTION_TABLE;
+use gb_emu::gameboy::cpu::metadata::{
+    InstructionMetadata, CB_INSTRUCTION_METADATA, INSTRUCTION_METADATA,
+};
+use gb_emu::gameboy::cpu::Cpu;
+
+const START_ADDRESS: u16 = 0xc000;
+
+/// Opcodes locking the CPU up, HALT and STOP which do not return to the next opcode
+const SKIPPED_OPCODES: [u8; 13] = [
+    0x10, 0x76, 0xd3, 0xdb, 0xdd, 0xe3, 0xe4, 0xeb, 0xec, 0xed, 0xf4, 0xfc, 0xfd,
+];
+
+const FLAG_ZERO: u8 = 0x80;
+const FLAG_CARRY: u8 = 0x10;
+
+/// Clock cycles ticked on the bus and bytes taken by one instruction, run with the
+/// given flags and zeroed operands
+fn run(instruction: &[u8], flags: u8) -> (u64, u16) {
+    let mut bus = FlatBus::new();
+    bus.load(START_ADDRESS, instruction);
+    let mut cpu = Cpu::new(bus);
+    cpu.registers.pc = START_ADDRESS;
+    cpu.registers.sp = 0xdff0;
+    cpu.flags.set_flags(flags);
+    cpu.execute();
+    (
+        cpu.bus.get_cycles(),
+        cpu.registers.pc.wrapping_sub(START_ADDRESS),
+    )
+}
+
+/// Flags making a JR/JP/CALL/RET condition true then false, None for other instructions
+fn get_condition_flags(metadata: &InstructionMetadata) -> Option<(u8, u8)> {
+    let mnemonic = metadata.mnemonic.to_string();
+    if !["JR", "JP", "CALL", "RET"].contains(&mnemonic.as_str()) {
+        return None;
+    }
+    match metadata.operands.first()?.name {
+        "NZ" => Some((0, FLAG_ZERO)),
+        "Z" => Some((FLAG_ZERO, 0)),
+        "NC" => Some((0, FLAG_CARRY)),
+        "C" => Some((FLAG_CARRY, 0)),
+        _ => None,
+    }
+}
+
+fn is_jump(metadata: &InstructionMetadata) -> bool {
+    let mnemonic = metadata.mnemonic.to_string();
+    ["JR", "JP", "CALL", "RET", "RETI", "RST"].contains(&mnemonic.as_str())
+}
+
+#[test]
+fn unprefixed_instructions() {
+    for (index, (operation, metadata)) in INSTRUCTION_TABLE
+        .iter()
+        .zip(&INSTRUCTION_METADATA)
+        .enumerate()
+    {
+        let opcode = index as u8;
+        assert_eq!(
+            operation.opcode, opcode,
+            "table out of order at {:02x}",
+            index
+        );
+        assert_eq!(metadata.opcode, opcode);
+        if opcode == 0xcb || SKIPPED_OPCODES.contains(&opcode) {
+            continue;
+        }
+        let context = format!("{:02x} {}", opcode, metadata);
+        match get_condition_flags(metadata) {
+            Some((taken, not_taken)) => {
+                let (cycles, _) = run(&[opcode], taken);
+                assert_eq!(cycles, metadata.cycles[0] as u64, "{} taken", context);
+                let (cycles, length) = run(&[opcode], not_taken);
+                assert_eq!(cycles, metadata.cycles[1] as u64, "{} not taken", context);
+                assert_eq!(length, metadata.length, "{}", context);
+            }
+            None => {
+                let (cycles, length) = run(&[opcode], 0);
+                assert_eq!(cycles, metadata.cycles[0] as u64, "{}", context);
+                if !is_jump(metadata) {
+                    assert_eq!(length, metadata.length, "{}", context);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn cb_prefixed_instructions() {
+    for (index, (operation, metadata)) in CB_INSTRUCTION_TABLE
+        .iter()
+        .zip(&CB_INSTRUCTION_METADATA)
+        .enumerate()
+    {
+        let opcode = index as u8;
+        assert_eq!(
+            operation.opcode, opcode,
+            "table out of order at {:02x}",
+            index
+        );
+        assert_eq!(metadata.opcode, opcode);
+        let context = format!("cb {:02x} {}", opcode, metadata);
+        let (cycles, length) = run(&[0xcb, opcode], 0);
+        assert_eq!(cycles, metadata.cycles[0] as u64, "{}", context);
+        assert_eq!(length, metadata.length, "{}", context);
+    }
+}
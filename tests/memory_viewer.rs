@@ -0,0 +1,90 @@
+//! Memory viewer rendered without a window
+
+use gb_emu::gameboy::bus::{Bus, MemoryBus};
+use gb_emu::gameboy::debug_view::memory::{MemoryViewer, CHANGED, CURSOR};
+use gb_emu::gameboy::debug_view::{DebugView, Image, Rgb, CHAR_HEIGHT, CHAR_WIDTH};
+use sdl2::keyboard::Keycode;
+
+fn type_keys(viewer: &mut MemoryViewer, bus: &mut Bus, keys: &[Keycode]) {
+    for key in keys {
+        viewer.handle_key(*key, false, bus);
+    }
+}
+
+#[test]
+fn peek_has_no_side_effects() {
+    let mut bus = Bus::new();
+    bus.write_8(0xc000, 0x42);
+    assert_eq!(bus.peek(0xc000), 0x42);
+    assert_eq!(bus.peek(0xe000), 0x42);
+    // No cartridge loaded
+    assert_eq!(bus.peek(0x4000), 0xff);
+    assert_eq!(bus.peek(0xa000), 0xff);
+    assert_eq!(bus.peek(0xff0f), 0xe0);
+}
+
+#[test]
+fn navigate_and_edit() {
+    let mut bus = Bus::new();
+    let mut viewer = MemoryViewer::new();
+
+    // ROM0, ROMX, VRAM, SRAM then WRAM
+    type_keys(
+        &mut viewer,
+        &mut bus,
+        &[Keycode::Tab, Keycode::Tab, Keycode::Tab, Keycode::Tab],
+    );
+    assert_eq!(viewer.get_cursor(), 0xc000);
+    type_keys(
+        &mut viewer,
+        &mut bus,
+        &[Keycode::Right, Keycode::A, Keycode::Num5, Keycode::Num0],
+    );
+    assert_eq!(bus.peek(0xc001), 0xa5);
+    assert_eq!(viewer.get_cursor(), 0xc002);
+    viewer.handle_key(Keycode::Tab, true, &mut bus);
+    assert_eq!(viewer.get_cursor(), 0xc000);
+    viewer.handle_key(Keycode::Tab, true, &mut bus);
+    assert_eq!(viewer.get_cursor(), 0xa000);
+
+    // VRAM at the end of the previous page
+    viewer.handle_key(Keycode::PageUp, false, &mut bus);
+    type_keys(&mut viewer, &mut bus, &[Keycode::Num1, Keycode::Num2]);
+    assert_eq!(bus.peek(0x9e00), 0x12);
+    type_keys(
+        &mut viewer,
+        &mut bus,
+        &[Keycode::Tab, Keycode::Tab, Keycode::Tab],
+    );
+    assert_eq!(viewer.get_cursor(), 0xe000);
+}
+
+/// True if any pixel of the byte at column of the first row of bytes has this color
+fn has_color(image: &Image, column: usize, color: Rgb) -> bool {
+    let (x, y) = ((10 + column * 3) * CHAR_WIDTH, 2 * CHAR_HEIGHT);
+    (0..CHAR_WIDTH * 2)
+        .flat_map(|dx| (0..CHAR_HEIGHT).map(move |dy| (dx, dy)))
+        .any(|(dx, dy)| image.get_pixel(x + dx, y + dy) == color)
+}
+
+#[test]
+fn changes_are_highlighted() {
+    let mut bus = Bus::new();
+    let mut viewer = MemoryViewer::new();
+    for _ in 0..4 {
+        viewer.handle_key(Keycode::Tab, false, &mut bus);
+    }
+    viewer.update(&bus);
+    assert!(!has_color(&viewer.render(&bus), 1, CHANGED));
+
+    bus.write_8(0xc001, 0x10);
+    viewer.update(&bus);
+    let image = viewer.render(&bus);
+    assert!(has_color(&image, 0, CURSOR));
+    assert!(!has_color(&image, 0, CHANGED));
+    assert!(has_color(&image, 1, CHANGED));
+
+    // Only changes between the last two updates are shown
+    viewer.update(&bus);
+    assert!(!has_color(&viewer.render(&bus), 1, CHANGED));
+}
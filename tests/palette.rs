@@ -0,0 +1,103 @@
+//! DMG palettes, CGB colorization of DMG cartridges and color correction
+
+mod common;
+
+use common::run_until_line;
+use gb_emu::gameboy::bus::{Bus, MemoryBus};
+use gb_emu::gameboy::palette::{
+    get_colorization, parse_palettes, to_rgb, ColorCorrection, DEFAULT_COLORIZATION,
+};
+use gb_emu::gameboy::ppu::Model;
+
+fn title(text: &str) -> [u8; 16] {
+    let mut title = [0; 16];
+    title[..text.len()].copy_from_slice(text.as_bytes());
+    title
+}
+
+#[test]
+fn palette_file() {
+    let text = "# Comment\n\
+                \n\
+                sgb: #f7e7c6 #d68e49 #a63725 #331e50\n\
+                gray : ffffff aaaaaa 555555 000000\n";
+    let palettes = parse_palettes(text).unwrap();
+    assert_eq!(palettes.len(), 2);
+    assert_eq!(palettes[0].name, "sgb");
+    assert_eq!(palettes[0].colors[0], [0xf7, 0xe7, 0xc6]);
+    assert_eq!(palettes[1].name, "gray");
+    assert_eq!(palettes[1].colors[3], [0, 0, 0]);
+
+    assert_eq!(
+        parse_palettes("green: 000000 111111 222222").unwrap_err(),
+        "line 1: expected 4 colors, found 3"
+    );
+    assert_eq!(
+        parse_palettes("\nbad: 000000 111111 222222 12345g").unwrap_err(),
+        "line 2: invalid color 12345g"
+    );
+    assert!(parse_palettes("ffffff aaaaaa 555555 000000").is_err());
+}
+
+#[test]
+fn color_correction() {
+    assert_eq!(to_rgb(0x7fff, ColorCorrection::Raw), [0xff, 0xff, 0xff]);
+    assert_eq!(to_rgb(0x001f, ColorCorrection::Raw), [0xff, 0x00, 0x00]);
+    assert_eq!(to_rgb(0x0000, ColorCorrection::Lcd), [0x00, 0x00, 0x00]);
+    // White is dimmed, red bleeds in the other channels
+    assert_eq!(to_rgb(0x7fff, ColorCorrection::Lcd), [240, 240, 240]);
+    assert_eq!(to_rgb(0x001f, ColorCorrection::Lcd), [201, 0, 46]);
+}
+
+#[test]
+fn colorization_by_title() {
+    let red = get_colorization(&title("POKEMON RED"), true);
+    let blue = get_colorization(&title("POKEMON BLUE"), true);
+    assert_eq!(red.background[1], 0x421f);
+    assert_eq!(blue.background[1], 0x7e8c);
+    assert_eq!(red.object_1, blue.object_0);
+    // Only Nintendo titles are looked up
+    assert_eq!(
+        get_colorization(&title("POKEMON RED"), false),
+        DEFAULT_COLORIZATION
+    );
+    assert_eq!(
+        get_colorization(&title("UNKNOWN"), true),
+        DEFAULT_COLORIZATION
+    );
+}
+
+#[test]
+fn colorization_of_titles_sharing_a_checksum() {
+    // Both titles sum to 0x61, the boot ROM tells them apart by their 4th letter
+    let blue = get_colorization(&title("POKEMON BLUE"), true);
+    let vegas = get_colorization(&title("VEGAS STAKES"), true);
+    assert_eq!(blue.background, [0x7fff, 0x7e8c, 0x7c00, 0x0000]);
+    assert_eq!(vegas.background, [0x7fff, 0x1bef, 0x0200, 0x0000]);
+    assert_eq!(blue.object_0, vegas.object_0);
+    // Same checksum, another 4th letter matches neither entry
+    assert_eq!(
+        get_colorization(&title("POKXMON BLBE"), true),
+        DEFAULT_COLORIZATION
+    );
+    // Some combinations start in the middle of a boot ROM palette
+    let mario = get_colorization(&title("SUPER MARIOLAND"), true);
+    assert_eq!(mario.object_0, [0x0000, 0x7fff, 0x421f, 0x1cf2]);
+}
+
+#[test]
+fn colorization_in_dmg_compatibility_mode() {
+    let mut bus = Bus::new();
+    bus.set_model(Model::DmgCompatibility);
+    bus.skip_cgb_boot_rom();
+    // Shade 1 of an empty cartridge, with the default colors
+    for line in 0..8 {
+        bus.write_8(0x8000 + line * 2, 0xff);
+    }
+    bus.write_8(0xff47, 0b11_10_01_00);
+    run_until_line(&mut bus, 1);
+    assert_eq!(
+        bus.ppu.get_color_frame_buffer().unwrap()[0],
+        DEFAULT_COLORIZATION.background[1]
+    );
+}
@@ -0,0 +1,251 @@
+//! PPU behaviour seen from the memory bus
+
+mod common;
+
+use common::{run_until_line, run_until_mode};
+use gb_emu::gameboy::bus::{Bus, MemoryBus};
+use gb_emu::gameboy::ppu::FRAME_CYCLES;
+
+#[test]
+fn vram_and_oam_are_locked_by_mode() {
+    let mut bus = Bus::new();
+    bus.write_8(0x8000, 0x11);
+    bus.write_8(0xfe00, 0x22);
+    bus.write_8(0xff40, 0x91);
+
+    run_until_mode(&mut bus, 2);
+    assert_eq!(bus.read_8(0x8000), 0x11);
+    assert_eq!(bus.read_8(0xfe00), 0xff);
+    bus.write_8(0xfe00, 0x33);
+    assert_eq!(bus.peek(0xfe00), 0x22);
+
+    run_until_mode(&mut bus, 3);
+    assert_eq!(bus.read_8(0x8000), 0xff);
+    assert_eq!(bus.read_8(0xfe00), 0xff);
+    bus.write_8(0x8000, 0x44);
+    assert_eq!(bus.peek(0x8000), 0x11);
+    // Debug accesses ignore the locks
+    bus.poke(0x8000, 0x55);
+    assert_eq!(bus.peek(0x8000), 0x55);
+
+    bus.ppu.set_access_restrictions(false);
+    assert_eq!(bus.read_8(0xfe00), 0x22);
+    bus.write_8(0x8000, 0x66);
+    assert_eq!(bus.read_8(0x8000), 0x66);
+
+    run_until_mode(&mut bus, 0);
+    bus.ppu.set_access_restrictions(true);
+    assert_eq!(bus.read_8(0x8000), 0x66);
+    assert_eq!(bus.read_8(0xfe00), 0x22);
+}
+
+#[test]
+fn lcd_off_lifts_the_locks() {
+    let mut bus = Bus::new();
+    bus.write_8(0xff40, 0x91);
+    run_until_mode(&mut bus, 3);
+    bus.write_8(0xff40, 0x11);
+    bus.write_8(0x8000, 0x12);
+    bus.write_8(0xfe00, 0x34);
+    assert_eq!(bus.read_8(0x8000), 0x12);
+    assert_eq!(bus.read_8(0xfe00), 0x34);
+}
+
+/// Tick until the PPU completes a frame, returns the clock cycles elapsed
+fn run_frame(bus: &mut Bus) -> u32 {
+    for cycles in 1..=2 * FRAME_CYCLES {
+        bus.tick();
+        if bus.ppu.is_frame_completed() {
+            return cycles;
+        }
+    }
+    panic!("No frame completed");
+}
+
+/// Black tiles everywhere with the identity palette
+fn fill_screen_black(bus: &mut Bus) {
+    for address in 0x8000..0x8010 {
+        bus.write_8(address, 0xff);
+    }
+    bus.write_8(0xff47, 0b11_10_01_00);
+}
+
+#[test]
+fn lcd_off_stops_the_ppu() {
+    let mut bus = Bus::new();
+    bus.write_8(0xff40, 0x91);
+    run_until_mode(&mut bus, 3);
+    for _ in 0..456 * 3 {
+        bus.tick();
+    }
+    assert_ne!(bus.peek(0xff44), 0);
+
+    bus.write_8(0xff40, 0x11);
+    assert_eq!(bus.peek(0xff44), 0);
+    assert_eq!(bus.peek(0xff41) & 0x3, 0);
+    // A blank frame is shown right away
+    assert!(bus.ppu.is_frame_completed());
+    assert!(bus.ppu.get_frame_buffer().iter().all(|shade| *shade == 0));
+
+    bus.read_8(0xff0f);
+    bus.write_8(0xff0f, 0x00);
+    // Frames go on without interrupts
+    assert_eq!(run_frame(&mut bus), FRAME_CYCLES);
+    assert_eq!(bus.peek(0xff44), 0);
+    assert_eq!(bus.read_8(0xff0f) & 0x03, 0x00);
+}
+
+#[test]
+fn first_frame_after_lcd_on_is_blank() {
+    let mut bus = Bus::new();
+    fill_screen_black(&mut bus);
+    bus.write_8(0xff40, 0x91);
+    run_frame(&mut bus);
+    assert!(bus.ppu.get_frame_buffer().iter().all(|shade| *shade == 0));
+    run_frame(&mut bus);
+    assert!(bus.ppu.get_frame_buffer().iter().all(|shade| *shade == 3));
+}
+
+/// Clock cycles of the next pixel transfer
+fn run_pixel_transfer(bus: &mut Bus) -> u32 {
+    run_until_mode(bus, 3);
+    let mut cycles = 0;
+    while bus.peek(0xff41) & 0x3 == 3 {
+        bus.tick();
+        cycles += 1;
+    }
+    cycles
+}
+
+fn write_sprite(bus: &mut Bus, index: u16, y: u8, x: u8, tile: u8, flags: u8) {
+    for (offset, value) in [y, x, tile, flags].into_iter().enumerate() {
+        bus.write_8(0xfe00 + index * 4 + offset as u16, value);
+    }
+}
+
+#[test]
+fn pixel_transfer_length() {
+    let mut bus = Bus::new();
+    bus.write_8(0xff40, 0x93);
+    assert_eq!(run_pixel_transfer(&mut bus), 172);
+    // SCX & 7 pixels are thrown away
+    bus.write_8(0xff43, 0x0b);
+    assert_eq!(run_pixel_transfer(&mut bus), 175);
+    bus.write_8(0xff43, 0);
+
+    // Window from the middle of the line
+    bus.write_8(0xff4a, 0);
+    bus.write_8(0xff4b, 7 + 80);
+    bus.write_8(0xff40, 0xb3);
+    assert_eq!(run_pixel_transfer(&mut bus), 178);
+}
+
+#[test]
+fn sprite_fetch_penalties() {
+    let mut bus = Bus::new();
+    // Lines 0-7: a sprite aligned on a background tile, lines 8-15 one 5 pixels right
+    // of it, lines 16-23 two on the same tile and lines 24-31 one at X = 0
+    write_sprite(&mut bus, 0, 16, 8, 0, 0);
+    write_sprite(&mut bus, 1, 16 + 8, 8 + 5, 0, 0);
+    write_sprite(&mut bus, 2, 16 + 16, 8, 0, 0);
+    write_sprite(&mut bus, 3, 16 + 16, 8 + 2, 0, 0);
+    write_sprite(&mut bus, 4, 16 + 24, 0, 0, 0);
+    bus.write_8(0xff40, 0x93);
+
+    assert_eq!(run_pixel_transfer(&mut bus), 172 + 11);
+    run_until_line(&mut bus, 8);
+    assert_eq!(run_pixel_transfer(&mut bus), 172 + 6);
+    run_until_line(&mut bus, 16);
+    assert_eq!(run_pixel_transfer(&mut bus), 172 + 11 + 6);
+    run_until_line(&mut bus, 24);
+    assert_eq!(run_pixel_transfer(&mut bus), 172 + 11);
+    // Sprites are not fetched when disabled
+    run_until_line(&mut bus, 25);
+    bus.write_8(0xff40, 0x91);
+    assert_eq!(run_pixel_transfer(&mut bus), 172);
+}
+
+#[test]
+fn stat_interrupt_on_mode_and_lyc() {
+    let mut bus = Bus::new();
+    bus.write_8(0xff40, 0x91);
+    bus.write_8(0xff41, 0x08);
+    run_until_mode(&mut bus, 3);
+    bus.write_8(0xff0f, 0x00);
+    run_until_mode(&mut bus, 0);
+    assert_eq!(bus.read_8(0xff0f) & 0x02, 0x02);
+
+    bus.write_8(0xff41, 0x40);
+    bus.write_8(0xff45, 10);
+    run_until_line(&mut bus, 9);
+    bus.write_8(0xff0f, 0x00);
+    run_until_line(&mut bus, 10);
+    assert_eq!(bus.read_8(0xff0f) & 0x02, 0x02);
+    // The line stays high until LY changes, no new interrupt
+    bus.write_8(0xff0f, 0x00);
+    bus.write_8(0xff41, 0x48);
+    run_until_mode(&mut bus, 0);
+    assert_eq!(bus.read_8(0xff0f) & 0x02, 0x00);
+}
+
+/// Background of color 1 and tile 1 with a left column of color 3
+fn write_sprite_tiles(bus: &mut Bus) {
+    for line in 0..8 {
+        bus.write_8(0x8000 + line * 2, 0xff);
+        bus.write_8(0x8010 + line * 2, 0x80);
+        bus.write_8(0x8010 + line * 2 + 1, 0x80);
+    }
+    bus.write_8(0xff47, 0b11_10_01_00);
+    bus.write_8(0xff48, 0b11_10_01_00);
+    bus.write_8(0xff49, 0b00_00_00_00);
+}
+
+#[test]
+fn sprites_over_the_background() {
+    let mut bus = Bus::new();
+    write_sprite_tiles(&mut bus);
+    // Line 4: in front of the background, behind it and on top of another sprite with
+    // a higher X, flipped horizontally
+    write_sprite(&mut bus, 0, 16 + 4, 8 + 10, 1, 0x00);
+    write_sprite(&mut bus, 1, 16 + 4, 8 + 20, 1, 0x80);
+    write_sprite(&mut bus, 2, 16 + 4, 8 + 30, 1, 0x10);
+    write_sprite(&mut bus, 3, 16 + 4, 8 + 23, 1, 0x20);
+    // Line 20: only the first 10 sprites show
+    for index in 0..11 {
+        write_sprite(&mut bus, 10 + index, 16 + 20, 8 + index as u8 * 10, 1, 0);
+    }
+    bus.write_8(0xff40, 0x93);
+    run_until_line(&mut bus, 21);
+
+    let frame = bus.ppu.get_frame_buffer();
+    let line = &frame[4 * 160..5 * 160];
+    assert_eq!(line[10], 3);
+    assert_eq!(line[11], 1);
+    assert_eq!(line[20], 1);
+    assert_eq!(line[30], 3);
+    assert_eq!(line[30 + 7], 1);
+    assert_eq!(&frame[3 * 160..4 * 160], &[1; 160]);
+    let line = &frame[20 * 160..21 * 160];
+    assert_eq!(line[90], 3);
+    assert_eq!(line[100], 1);
+}
+
+#[test]
+fn palette_written_during_pixel_transfer() {
+    let mut bus = Bus::new();
+    write_sprite_tiles(&mut bus);
+    bus.write_8(0xff40, 0x91);
+    run_until_line(&mut bus, 10);
+    run_until_mode(&mut bus, 3);
+    for _ in 0..80 {
+        bus.tick();
+    }
+    bus.write_8(0xff47, 0b11_10_11_00);
+    run_until_mode(&mut bus, 0);
+
+    let line = &bus.ppu.get_frame_buffer()[10 * 160..11 * 160];
+    assert_eq!(line[0], 1);
+    assert_eq!(line[159], 3);
+    let changed = line.iter().position(|shade| *shade == 3).unwrap();
+    assert!((60..80).contains(&changed));
+}
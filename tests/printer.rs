@@ -0,0 +1,200 @@
+//! Game Boy Printer driven packet by packet through its link port
+
+use gb_emu::gameboy::bus::printer::{Printer, PRINT_WIDTH};
+use gb_emu::gameboy::bus::serial::SerialDevice;
+use std::path::{Path, PathBuf};
+
+const INIT: u8 = 0x01;
+const PRINT: u8 = 0x02;
+const DATA: u8 = 0x04;
+const STATUS: u8 = 0x0f;
+
+const CHECKSUM_ERROR: u8 = 0x01;
+const BUSY: u8 = 0x02;
+const IMAGE_DATA_FULL: u8 = 0x04;
+const UNPROCESSED_DATA: u8 = 0x08;
+
+/// One sheet with the default palette, one margin line after printing
+const PRINT_AND_FEED: [u8; 4] = [0x01, 0x01, 0xe4, 0x40];
+
+/// Empty output directory for one test
+fn output_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("gb_emu_printer_{}_{}", test, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn get_pages(dir: &Path) -> Vec<PathBuf> {
+    let mut pages: Vec<PathBuf> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    pages.sort();
+    pages
+}
+
+/// Size and grayscale pixels of a printed page
+fn read_page(path: &Path) -> (u32, u32, Vec<u8>) {
+    let decoder = png::Decoder::new(std::fs::File::open(path).unwrap());
+    let mut reader = decoder.read_info().unwrap();
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut pixels).unwrap();
+    pixels.truncate(info.buffer_size());
+    (info.width, info.height, pixels)
+}
+
+/// Send a packet with the given checksum, return the keep alive and status bytes
+fn send_packet_with_checksum(
+    printer: &mut Printer,
+    command: u8,
+    compression: u8,
+    data: &[u8],
+    checksum: u16,
+) -> (u8, u8) {
+    let length = data.len() as u16;
+    let mut bytes = vec![0x88, 0x33, command, compression];
+    bytes.extend_from_slice(&length.to_le_bytes());
+    bytes.extend_from_slice(data);
+    bytes.extend_from_slice(&checksum.to_le_bytes());
+    for byte in bytes {
+        assert_eq!(printer.exchange(byte), 0x00);
+    }
+    (printer.exchange(0x00), printer.exchange(0x00))
+}
+
+/// Send a packet and return the status the printer answers with
+fn send_packet(printer: &mut Printer, command: u8, compression: u8, data: &[u8]) -> u8 {
+    let length = data.len() as u16;
+    let checksum = [command, compression, length as u8, (length >> 8) as u8]
+        .iter()
+        .chain(data)
+        .fold(0u16, |sum, &byte| sum.wrapping_add(byte as u16));
+    let (keep_alive, status) =
+        send_packet_with_checksum(printer, command, compression, data, checksum);
+    assert_eq!(keep_alive, 0x81);
+    status
+}
+
+/// Two rows of 20 tiles, the first one black and the second one white
+fn black_then_white_rows() -> Vec<u8> {
+    let mut data = vec![0xff; 320];
+    data.extend_from_slice(&[0x00; 320]);
+    data
+}
+
+/// The page printed from black_then_white_rows
+fn assert_black_then_white_page(path: &Path) {
+    let (width, height, pixels) = read_page(path);
+    assert_eq!((width as usize, height), (PRINT_WIDTH, 16));
+    let (black, white) = pixels.split_at(8 * PRINT_WIDTH);
+    assert!(black.iter().all(|&pixel| pixel == 0x00));
+    assert!(white.iter().all(|&pixel| pixel == 0xff));
+}
+
+#[test]
+fn init_data_and_print() {
+    let dir = output_dir("print");
+    let mut printer = Printer::new(dir.clone());
+    assert_eq!(send_packet(&mut printer, INIT, 0, &[]), 0x00);
+    assert_eq!(
+        send_packet(&mut printer, DATA, 0, &black_then_white_rows()),
+        UNPROCESSED_DATA | IMAGE_DATA_FULL
+    );
+    // An empty data packet ends the transfer
+    assert_eq!(
+        send_packet(&mut printer, DATA, 0, &[]),
+        UNPROCESSED_DATA | IMAGE_DATA_FULL
+    );
+    assert_eq!(send_packet(&mut printer, PRINT, 0, &PRINT_AND_FEED), BUSY);
+
+    let pages = get_pages(&dir);
+    assert_eq!(pages.len(), 1);
+    assert_black_then_white_page(&pages[0]);
+    drop(printer);
+    assert_eq!(get_pages(&dir).len(), 1);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn compressed_data() {
+    let dir = output_dir("compressed");
+    let mut printer = Printer::new(dir.clone());
+    // 129 + 129 + 62 bytes of 0xff, 4 bytes copied as is then 129 + 129 + 58 bytes of 0x00
+    let data = [
+        0xff, 0xff, 0xff, 0xff, 0xbc, 0xff, 0x03, 0x00, 0x00, 0x00, 0x00, 0xff, 0x00, 0xff, 0x00,
+        0xb8, 0x00,
+    ];
+    assert_eq!(
+        send_packet(&mut printer, DATA, 1, &data),
+        UNPROCESSED_DATA | IMAGE_DATA_FULL
+    );
+    send_packet(&mut printer, PRINT, 0, &PRINT_AND_FEED);
+
+    let pages = get_pages(&dir);
+    assert_eq!(pages.len(), 1);
+    assert_black_then_white_page(&pages[0]);
+    drop(printer);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn bad_checksum() {
+    let dir = output_dir("checksum");
+    let mut printer = Printer::new(dir.clone());
+    let (keep_alive, status) =
+        send_packet_with_checksum(&mut printer, DATA, 0, &[0x12, 0x34], 0x0000);
+    assert_eq!(keep_alive, 0x81);
+    assert_eq!(status, CHECKSUM_ERROR);
+    // The data is dropped and the error cleared by the next valid packet
+    assert_eq!(send_packet(&mut printer, STATUS, 0, &[]), 0x00);
+    drop(printer);
+    assert!(get_pages(&dir).is_empty());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn busy_while_printing() {
+    let dir = output_dir("busy");
+    let mut printer = Printer::new(dir.clone());
+    send_packet(&mut printer, DATA, 0, &black_then_white_rows());
+    assert_eq!(send_packet(&mut printer, PRINT, 0, &PRINT_AND_FEED), BUSY);
+    for _ in 0..3 {
+        assert_eq!(send_packet(&mut printer, STATUS, 0, &[]), BUSY);
+    }
+    assert_eq!(send_packet(&mut printer, STATUS, 0, &[]), 0x00);
+    drop(printer);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn strips_printed_without_margin_make_one_page() {
+    let dir = output_dir("strips");
+    let mut printer = Printer::new(dir.clone());
+    send_packet(&mut printer, DATA, 0, &black_then_white_rows());
+    send_packet(&mut printer, PRINT, 0, &[0x01, 0x00, 0xe4, 0x40]);
+    assert!(get_pages(&dir).is_empty());
+
+    send_packet(&mut printer, DATA, 0, &black_then_white_rows());
+    send_packet(&mut printer, PRINT, 0, &[0x01, 0x03, 0xe4, 0x40]);
+    let pages = get_pages(&dir);
+    assert_eq!(pages.len(), 1);
+    let (_, height, _) = read_page(&pages[0]);
+    assert_eq!(height, 32);
+    drop(printer);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn unsaved_page_saved_when_dropped() {
+    let dir = output_dir("dropped");
+    let mut printer = Printer::new(dir.clone());
+    send_packet(&mut printer, DATA, 0, &black_then_white_rows());
+    send_packet(&mut printer, PRINT, 0, &[0x01, 0x00, 0xe4, 0x40]);
+    assert!(get_pages(&dir).is_empty());
+    drop(printer);
+    let pages = get_pages(&dir);
+    assert_eq!(pages.len(), 1);
+    assert_black_then_white_page(&pages[0]);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
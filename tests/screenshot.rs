@@ -0,0 +1,73 @@
+//! Screenshots of a headless Game Boy
+
+use gb_emu::gameboy::palette::{GRAYSCALE, PEA_SOUP};
+use gb_emu::gameboy::ppu::{SCREEN_HEIGHT, SCREEN_WIDTH};
+use gb_emu::gameboy::{GameBoy, ScreenshotOptions};
+
+#[test]
+fn scaled_screenshot_with_the_dmg_palette() {
+    let mut gameboy = GameBoy::headless();
+    gameboy.run_frame();
+    let image = gameboy.get_screenshot(ScreenshotOptions::default());
+    assert_eq!((image.width, image.height), (SCREEN_WIDTH, SCREEN_HEIGHT));
+    assert_eq!(image.get_pixel(0, 0), GRAYSCALE[0]);
+
+    gameboy.set_dmg_palette(PEA_SOUP);
+    // The border is only there with a SGB
+    let options = ScreenshotOptions {
+        border: true,
+        scale: 3,
+    };
+    let image = gameboy.get_screenshot(options);
+    assert_eq!(
+        (image.width, image.height),
+        (SCREEN_WIDTH * 3, SCREEN_HEIGHT * 3)
+    );
+    assert_eq!(
+        image.get_pixel(SCREEN_WIDTH * 3 - 1, SCREEN_HEIGHT * 3 - 1),
+        PEA_SOUP[0]
+    );
+}
+
+#[test]
+fn save_screenshot_with_a_timestamped_name() {
+    let mut gameboy = GameBoy::headless();
+    gameboy.run_frame();
+    let output_dir = std::env::temp_dir().join(format!("gb_emu_screenshot_{}", std::process::id()));
+    std::fs::create_dir_all(&output_dir).unwrap();
+    let options = ScreenshotOptions {
+        border: false,
+        scale: 2,
+    };
+    let path = gameboy.save_screenshot(&output_dir, options).unwrap();
+    let name = path.file_name().unwrap().to_string_lossy().to_string();
+    assert!(name.starts_with("screenshot_") && name.ends_with(".png"));
+
+    let decoder = png::Decoder::new(std::fs::File::open(&path).unwrap());
+    let reader = decoder.read_info().unwrap();
+    let (width, height) = (reader.info().width, reader.info().height);
+    std::fs::remove_dir_all(&output_dir).unwrap();
+    assert_eq!(
+        (width as usize, height as usize),
+        (SCREEN_WIDTH * 2, SCREEN_HEIGHT * 2)
+    );
+}
+
+#[test]
+fn dump_every_other_frame() {
+    let mut gameboy = GameBoy::headless();
+    let output_dir = std::env::temp_dir().join(format!("gb_emu_dump_{}", std::process::id()));
+    std::fs::create_dir_all(&output_dir).unwrap();
+    let saved = gameboy
+        .dump_frames(&output_dir, 2, 5, ScreenshotOptions::default())
+        .unwrap();
+
+    let mut names: Vec<String> = std::fs::read_dir(&output_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+    std::fs::remove_dir_all(&output_dir).unwrap();
+    assert_eq!(saved, 2);
+    assert_eq!(names, vec!["frame_000002.png", "frame_000004.png"]);
+}
@@ -0,0 +1,179 @@
+//! Super Game Boy packets sent through P1, seen from the memory bus
+
+mod common;
+
+use common::{run_until_line, GREEN, RED};
+use gb_emu::gameboy::bus::sgb::{Mask, BORDER_WIDTH, PACKET_SIZE, SCREEN_LEFT, SCREEN_TOP};
+use gb_emu::gameboy::bus::{Bus, MemoryBus};
+use gb_emu::gameboy::ppu::SCREEN_WIDTH;
+
+const WHITE: u16 = 0x7fff;
+
+const PAL01: u8 = 0x00;
+const ATTR_BLK: u8 = 0x04;
+const ATTR_DIV: u8 = 0x06;
+const MLT_REQ: u8 = 0x11;
+const CHR_TRN: u8 = 0x13;
+const PCT_TRN: u8 = 0x14;
+const MASK_EN: u8 = 0x17;
+
+fn sgb_bus() -> Bus {
+    let mut bus = Bus::new();
+    bus.enable_sgb();
+    bus
+}
+
+/// Reset pulse, 128 bits LSB first and the stop bit, both lines high between pulses
+fn send_bits(bus: &mut Bus, packet: &[u8; PACKET_SIZE], stop_bit: bool) {
+    bus.write_8(0xff00, 0x00);
+    bus.write_8(0xff00, 0x30);
+    let bits = (0..PACKET_SIZE * 8).map(|bit| packet[bit / 8] >> (bit % 8) & 0x1 == 1);
+    for one in bits.chain([stop_bit]) {
+        bus.write_8(0xff00, if one { 0x10 } else { 0x20 });
+        bus.write_8(0xff00, 0x30);
+    }
+}
+
+/// Packets end with a 0 stop bit
+fn send_packet(bus: &mut Bus, packet: &[u8; PACKET_SIZE]) {
+    send_bits(bus, packet, false);
+}
+
+/// Single packet command
+fn send_command(bus: &mut Bus, command: u8, data: &[u8]) {
+    let mut packet = [0; PACKET_SIZE];
+    packet[0] = command << 3 | 1;
+    packet[1..=data.len()].copy_from_slice(data);
+    send_packet(bus, &packet);
+}
+
+/// Up to the start of the next VBlank, the frame is then seen by the SGB
+fn run_frame(bus: &mut Bus) {
+    run_until_line(bus, 0);
+    run_until_line(bus, 144);
+}
+
+/// Screen of shade 1, the LCD on past its blank first frame
+fn start_lcd(bus: &mut Bus) {
+    for line in 0..8 {
+        bus.write_8(0x8000 + line * 2, 0xff);
+    }
+    bus.write_8(0xff47, 0b11_10_01_00);
+    bus.write_8(0xff40, 0x91);
+    run_frame(bus);
+}
+
+fn get_color(bus: &Bus, x: usize, y: usize) -> u16 {
+    bus.get_sgb().unwrap().get_colors()[y * SCREEN_WIDTH + x]
+}
+
+#[test]
+fn palettes_and_attributes() {
+    let mut bus = sgb_bus();
+    start_lcd(&mut bus);
+    let colors = [WHITE, RED, 0, 0, GREEN, 0, 0];
+    let data: Vec<u8> = colors
+        .iter()
+        .flat_map(|color| color.to_le_bytes())
+        .collect();
+    send_command(&mut bus, PAL01, &data);
+    // Inside only: the edge takes the inside palette
+    send_command(&mut bus, ATTR_BLK, &[1, 0x01, 0x01, 2, 2, 4, 4]);
+    run_frame(&mut bus);
+
+    assert_eq!(bus.get_sgb().unwrap().get_palettes()[1][0], WHITE);
+    assert_eq!(get_color(&bus, 0, 0), RED);
+    assert_eq!(get_color(&bus, 2 * 8, 2 * 8), GREEN);
+    assert_eq!(get_color(&bus, 3 * 8 + 4, 3 * 8 + 4), GREEN);
+    assert_eq!(get_color(&bus, 5 * 8, 3 * 8), RED);
+
+    // Split below row 9, row 9 itself in palette 1
+    send_command(&mut bus, ATTR_DIV, &[0x40 | 0x01 << 4, 9]);
+    run_frame(&mut bus);
+    assert_eq!(get_color(&bus, 0, 8 * 8), RED);
+    assert_eq!(get_color(&bus, 0, 9 * 8), GREEN);
+    assert_eq!(get_color(&bus, 0, 10 * 8), RED);
+}
+
+#[test]
+fn mask() {
+    let mut bus = sgb_bus();
+    start_lcd(&mut bus);
+    send_command(&mut bus, MASK_EN, &[2]);
+    run_frame(&mut bus);
+    assert_eq!(bus.get_sgb().unwrap().get_mask(), Mask::Black);
+    assert_eq!(get_color(&bus, 80, 72), 0x0000);
+
+    // Cancelling the mask with a 1 stop bit does nothing
+    let mut packet = [0; PACKET_SIZE];
+    packet[0] = MASK_EN << 3 | 1;
+    send_bits(&mut bus, &packet, true);
+    run_frame(&mut bus);
+    assert_eq!(bus.get_sgb().unwrap().get_mask(), Mask::Black);
+}
+
+#[test]
+fn multiplayer_joypad_ids() {
+    let mut bus = sgb_bus();
+    bus.write_8(0xff00, 0x30);
+    let single = bus.read_8(0xff00);
+    send_command(&mut bus, MLT_REQ, &[0x01]);
+    assert_eq!(bus.read_8(0xff00) & 0x0f, 0x0f);
+    // P14 pulses select the next joypad
+    bus.write_8(0xff00, 0x20);
+    bus.write_8(0xff00, 0x30);
+    assert_eq!(bus.read_8(0xff00) & 0x0f, 0x0e);
+    bus.write_8(0xff00, 0x20);
+    bus.write_8(0xff00, 0x30);
+    assert_eq!(bus.read_8(0xff00) & 0x0f, 0x0f);
+
+    send_command(&mut bus, MLT_REQ, &[0x00]);
+    assert_eq!(bus.read_8(0xff00), single);
+}
+
+#[test]
+fn border_from_vram_transfers() {
+    let mut bus = sgb_bus();
+    // Tiles 0-255 shown 20 per row, the data sent is the tile data as is
+    for tile in 0..256_u16 {
+        bus.write_8(0x9800 + tile / 20 * 32 + tile % 20, tile as u8);
+    }
+    // Border map entry 0: tile 1 with palette 4
+    bus.write_8(0x8000, 0x01);
+    bus.write_8(0x8001, 0x10);
+    // Border tile 1, top left pixel of color 1
+    bus.write_8(0x8020, 0x80);
+    // Color 1 of palette 4
+    bus.write_8(0x8802, RED as u8);
+    bus.write_8(0x8803, (RED >> 8) as u8);
+    bus.write_8(0xff47, 0b11_10_01_00);
+    bus.write_8(0xff40, 0x91);
+    run_frame(&mut bus);
+
+    send_command(&mut bus, CHR_TRN, &[0x00]);
+    run_frame(&mut bus);
+    send_command(&mut bus, PCT_TRN, &[]);
+    run_frame(&mut bus);
+
+    let sgb = bus.get_sgb().unwrap();
+    let frame = sgb.get_border_frame();
+    let backdrop = sgb.get_palettes()[0][0];
+    assert_eq!(frame[0], RED);
+    assert_eq!(frame[1], backdrop);
+    assert_eq!(
+        frame[SCREEN_TOP * BORDER_WIDTH + SCREEN_LEFT],
+        sgb.get_colors()[0]
+    );
+}
+
+#[test]
+fn packets_are_ignored_without_sgb() {
+    let mut bus = Bus::new();
+    bus.write_8(0xff00, 0x30);
+    let single = bus.read_8(0xff00);
+    send_command(&mut bus, MLT_REQ, &[0x01]);
+    bus.write_8(0xff00, 0x20);
+    bus.write_8(0xff00, 0x30);
+    assert_eq!(bus.read_8(0xff00), single);
+    assert!(bus.get_sgb().is_none());
+}
@@ -0,0 +1,293 @@
+//! Runs every opcode against single step test vectors in the community JSON format
+//! (https://github.com/SingleStepTests/sm83), one file per opcode: "00.json" to "ff.json"
+//! and "cb 00.json" to "cb ff.json".
+//!
+//! tests/sm83 holds a few hand-written vectors in that format, point GB_SM83_TESTS to the
+//! directory of the full set to run it instead. Run with
+//! `cargo test --test single_step -- --nocapture` to see the summary table, which is also
+//! saved in single_step.md under the cargo target directory.
+
+use gb_emu::gameboy::bus::{BusActivity, FlatBus, MemoryBus, RecordingBus};
+use gb_emu::gameboy::cpu::Cpu;
+use serde_json::Value;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+/// Opcodes (test file names without extension) passing all their vectors, that must not regress
+const KNOWN_PASSING: &[&str] = &[
+    "00", "03", "08", "18", "20", "2b", "c1", "c5", "c9", "cb 37", "cd", "e9", "f9",
+];
+
+type TestBus = RecordingBus<FlatBus>;
+
+struct CpuState {
+    a: u8,
+    b: u8,
+    c: u8,
+    d: u8,
+    e: u8,
+    f: u8,
+    h: u8,
+    l: u8,
+    pc: u16,
+    sp: u16,
+    ime: bool,
+    ram: Vec<(u16, u8)>,
+}
+
+impl CpuState {
+    fn parse(value: &Value) -> Result<CpuState, String> {
+        let field = |name: &str| -> Result<u64, String> {
+            value[name]
+                .as_u64()
+                .ok_or_else(|| format!("missing field {}", name))
+        };
+        let mut ram = vec![];
+        for entry in value["ram"].as_array().ok_or("missing field ram")? {
+            match (entry[0].as_u64(), entry[1].as_u64()) {
+                (Some(address), Some(value)) => ram.push((address as u16, value as u8)),
+                _ => return Err(format!("invalid ram entry {}", entry)),
+            }
+        }
+        // IE lives in memory for the CPU
+        if let Some(ie) = value["ie"].as_u64() {
+            ram.push((0xffff, ie as u8));
+        }
+        Ok(CpuState {
+            a: field("a")? as u8,
+            b: field("b")? as u8,
+            c: field("c")? as u8,
+            d: field("d")? as u8,
+            e: field("e")? as u8,
+            f: field("f")? as u8,
+            h: field("h")? as u8,
+            l: field("l")? as u8,
+            pc: field("pc")? as u16,
+            sp: field("sp")? as u16,
+            ime: value["ime"].as_u64().unwrap_or(0) != 0,
+            ram,
+        })
+    }
+
+    fn load(&self, cpu: &mut Cpu<TestBus>) {
+        cpu.registers.a = self.a;
+        cpu.registers.b = self.b;
+        cpu.registers.c = self.c;
+        cpu.registers.d = self.d;
+        cpu.registers.e = self.e;
+        cpu.registers.h = self.h;
+        cpu.registers.l = self.l;
+        cpu.registers.pc = self.pc;
+        cpu.registers.sp = self.sp;
+        cpu.flags.set_flags(self.f);
+        cpu.set_interrupt_master_enabled(self.ime);
+        for (address, value) in &self.ram {
+            cpu.bus.inner.write_8(*address, *value);
+        }
+    }
+
+    /// Description of the first difference with the CPU state, if any
+    fn compare(&self, cpu: &mut Cpu<TestBus>) -> Option<String> {
+        let registers = [
+            ("A", self.a as u16, cpu.registers.a as u16),
+            ("B", self.b as u16, cpu.registers.b as u16),
+            ("C", self.c as u16, cpu.registers.c as u16),
+            ("D", self.d as u16, cpu.registers.d as u16),
+            ("E", self.e as u16, cpu.registers.e as u16),
+            ("F", self.f as u16, cpu.flags.get_flags() as u16),
+            ("H", self.h as u16, cpu.registers.h as u16),
+            ("L", self.l as u16, cpu.registers.l as u16),
+            ("PC", self.pc, cpu.registers.pc),
+            ("SP", self.sp, cpu.registers.sp),
+            (
+                "IME",
+                self.ime as u16,
+                cpu.is_interrupt_master_enabled() as u16,
+            ),
+        ];
+        for (name, expected, actual) in registers {
+            if expected != actual {
+                return Some(format!(
+                    "{} expected {:02x} got {:02x}",
+                    name, expected, actual
+                ));
+            }
+        }
+        for (address, expected) in &self.ram {
+            let actual = cpu.bus.inner.read_8(*address);
+            if *expected != actual {
+                return Some(format!(
+                    "[{:04x}] expected {:02x} got {:02x}",
+                    address, expected, actual
+                ));
+            }
+        }
+        None
+    }
+}
+
+/// Bus activity of each M-cycle
+fn parse_cycles(value: &Value) -> Result<Vec<BusActivity>, String> {
+    let mut cycles = vec![];
+    for cycle in value.as_array().ok_or("missing field cycles")? {
+        let (Some(address), Some(value), Some(pins)) =
+            (cycle[0].as_u64(), cycle[1].as_u64(), cycle[2].as_str())
+        else {
+            cycles.push(BusActivity::Idle);
+            continue;
+        };
+        let (address, value) = (address as u16, value as u8);
+        cycles.push(if pins.contains('w') {
+            BusActivity::Write { address, value }
+        } else if pins.contains('r') {
+            BusActivity::Read { address, value }
+        } else {
+            BusActivity::Idle
+        });
+    }
+    Ok(cycles)
+}
+
+/// Runs a single test vector, returns the failure description if any
+fn run_test(test: &Value) -> Result<(), String> {
+    let initial = CpuState::parse(&test["initial"])?;
+    let expected = CpuState::parse(&test["final"])?;
+    let cycles = parse_cycles(&test["cycles"])?;
+
+    let mut cpu = Cpu::new(TestBus::new(FlatBus::new()));
+    initial.load(&mut cpu);
+
+    let clock_cycles = panic::catch_unwind(AssertUnwindSafe(|| cpu.execute()))
+        .map_err(|_| String::from("panic"))?;
+
+    if let Some(difference) = expected.compare(&mut cpu) {
+        return Err(difference);
+    }
+
+    for index in 0..cycles.len().max(cpu.bus.activity.len()) {
+        let (expected, actual) = (cycles.get(index), cpu.bus.activity.get(index));
+        if expected != actual {
+            return Err(format!(
+                "M-cycle {} expected {:?} got {:?}",
+                index, expected, actual
+            ));
+        }
+    }
+
+    if clock_cycles as usize != cycles.len() * 4 {
+        return Err(format!(
+            "cycles expected {} got {}",
+            cycles.len() * 4,
+            clock_cycles
+        ));
+    }
+    Ok(())
+}
+
+struct OpcodeResult {
+    opcode: String,
+    passed: usize,
+    total: usize,
+    first_failure: Option<String>,
+}
+
+fn run_test_file(path: &Path) -> OpcodeResult {
+    let opcode = path.file_stem().unwrap().to_string_lossy().to_string();
+    let tests: Vec<Value> = match std::fs::read_to_string(path)
+        .map_err(|error| error.to_string())
+        .and_then(|content| serde_json::from_str(&content).map_err(|error| error.to_string()))
+    {
+        Ok(tests) => tests,
+        Err(error) => {
+            return OpcodeResult {
+                opcode,
+                passed: 0,
+                total: 0,
+                first_failure: Some(error),
+            }
+        }
+    };
+
+    let mut result = OpcodeResult {
+        opcode,
+        passed: 0,
+        total: tests.len(),
+        first_failure: None,
+    };
+    for test in &tests {
+        match run_test(test) {
+            Ok(()) => result.passed += 1,
+            Err(error) if result.first_failure.is_none() => {
+                let name = test["name"].as_str().unwrap_or("?");
+                result.first_failure = Some(format!("{}: {}", name, error));
+            }
+            Err(_) => (),
+        }
+    }
+    result
+}
+
+fn test_directory() -> PathBuf {
+    match std::env::var("GB_SM83_TESTS") {
+        Ok(directory) => PathBuf::from(directory),
+        Err(_) => Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("sm83"),
+    }
+}
+
+#[test]
+fn single_step() {
+    let directory = test_directory();
+    let mut files: Vec<PathBuf> = match std::fs::read_dir(&directory) {
+        Ok(entries) => entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|extension| extension == "json")
+            })
+            .collect(),
+        Err(_) => vec![],
+    };
+    assert!(
+        !files.is_empty(),
+        "No test vector found in {}",
+        directory.display()
+    );
+    files.sort();
+
+    // Failures are reported in the table, keep the output readable
+    panic::set_hook(Box::new(|_| ()));
+    let results: Vec<OpcodeResult> = files.iter().map(|path| run_test_file(path)).collect();
+    let _ = panic::take_hook();
+
+    let mut table = String::from("| Opcode | Passed | First failure |\n|---|---|---|\n");
+    for result in &results {
+        table += &format!(
+            "| {} | {}/{} | {} |\n",
+            result.opcode,
+            result.passed,
+            result.total,
+            result.first_failure.as_deref().unwrap_or("")
+        );
+    }
+    let fully_passing = results
+        .iter()
+        .filter(|result| result.total > 0 && result.passed == result.total)
+        .count();
+    table += &format!("\n{}/{} opcodes passing\n", fully_passing, results.len());
+    println!("{}", table);
+    let report = Path::new(env!("CARGO_TARGET_TMPDIR")).join("single_step.md");
+    std::fs::write(&report, &table).unwrap();
+    println!("Summary saved in {}", report.display());
+
+    let regressions: Vec<&str> = results
+        .iter()
+        .filter(|result| {
+            KNOWN_PASSING.contains(&result.opcode.as_str()) && result.passed != result.total
+        })
+        .map(|result| result.opcode.as_str())
+        .collect();
+    assert!(regressions.is_empty(), "Regressions: {:?}", regressions);
+}
@@ -0,0 +1,195 @@
+use gb_emu::gameboy::bus::FlatBus;
+use gb_emu::gameboy::cpu::Cpu;
+use gb_emu::gameboy::trace::{
+    TraceBuffer, TraceComparison, TraceFields, TraceFilter, TraceFormat, Tracer,
+};
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+const START_ADDRESS: u16 = 0x0100;
+
+/// Trace output kept in memory
+#[derive(Clone, Default)]
+struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(data);
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Run the program and return the trace lines
+fn trace(program: &[u8], format: TraceFormat, filter: TraceFilter) -> Vec<String> {
+    let mut bus = FlatBus::new();
+    bus.load(START_ADDRESS, program);
+    let mut cpu = Cpu::new(bus);
+    cpu.registers.pc = START_ADDRESS;
+    cpu.registers.sp = 0xfffe;
+    let output = SharedBuffer::default();
+    cpu.tracer = Some(Tracer::new(Box::new(output.clone()), format, filter));
+    while cpu.registers.pc < START_ADDRESS + program.len() as u16 {
+        cpu.execute();
+    }
+    let content = String::from_utf8(output.0.borrow().clone()).unwrap();
+    content.lines().map(String::from).collect()
+}
+
+// ld a,$12 ; ld b,a ; inc b ; ld hl,$c000
+const PROGRAM: [u8; 7] = [0x3e, 0x12, 0x47, 0x04, 0x21, 0x00, 0xc0];
+
+#[test]
+fn doctor_format() {
+    let lines = trace(&PROGRAM, TraceFormat::Doctor, TraceFilter::default());
+    assert_eq!(
+        lines,
+        vec![
+            "A:00 F:00 B:00 C:00 D:00 E:00 H:00 L:00 SP:FFFE PC:0100 PCMEM:3E,12,47,04",
+            "A:12 F:00 B:00 C:00 D:00 E:00 H:00 L:00 SP:FFFE PC:0102 PCMEM:47,04,21,00",
+            "A:12 F:00 B:12 C:00 D:00 E:00 H:00 L:00 SP:FFFE PC:0103 PCMEM:04,21,00,C0",
+            "A:12 F:00 B:13 C:00 D:00 E:00 H:00 L:00 SP:FFFE PC:0104 PCMEM:21,00,C0,00",
+        ]
+    );
+}
+
+#[test]
+fn detailed_format() {
+    let lines = trace(&PROGRAM, TraceFormat::Detailed, TraceFilter::default());
+    assert!(
+        lines[0].ends_with(" BANK:00 CY:0 | ld a,$12"),
+        "{}",
+        lines[0]
+    );
+    assert!(lines[1].ends_with(" BANK:00 CY:8 | ld b,a"), "{}", lines[1]);
+}
+
+#[test]
+fn filters() {
+    let pc_range = trace(
+        &PROGRAM,
+        TraceFormat::Doctor,
+        TraceFilter {
+            pc_range: Some(0x0102..=0x0103),
+            ..TraceFilter::default()
+        },
+    );
+    assert_eq!(pc_range.len(), 2);
+    assert!(pc_range[0].contains("PC:0102"));
+
+    let skip_and_limit = trace(
+        &PROGRAM,
+        TraceFormat::Doctor,
+        TraceFilter {
+            skip: 1,
+            limit: Some(2),
+            ..TraceFilter::default()
+        },
+    );
+    assert_eq!(skip_and_limit.len(), 2);
+    assert!(skip_and_limit[0].contains("PC:0102"));
+    assert!(skip_and_limit[1].contains("PC:0103"));
+
+    let other_bank = trace(
+        &PROGRAM,
+        TraceFormat::Doctor,
+        TraceFilter {
+            rom_bank: Some(1),
+            ..TraceFilter::default()
+        },
+    );
+    assert!(other_bank.is_empty());
+}
+
+#[test]
+fn compare_fields() {
+    let expected = TraceFields::parse(
+        "A:01 F:B0 B:00 C:13 D:00 E:D8 H:01 L:4D SP:FFFE PC:0100 PCMEM:00,C3,13,02",
+    );
+    let same = TraceFields::parse(
+        "A:01 F:B0 B:00 C:13 D:00 E:D8 H:01 L:4D SP:FFFE PC:0100 PCMEM:00,C3,13,02 \
+         BANK:00 CY:1234 | nop",
+    );
+    assert!(expected.compare(&same).is_empty());
+    assert_eq!(same.get("CY"), Some("1234"));
+
+    let different = TraceFields::parse(
+        "A:01 F:80 B:00 C:13 D:00 E:D8 H:01 L:4D SP:FFFE PC:0100 PCMEM:00,C3,13,03",
+    );
+    let differences = expected.compare(&different);
+    assert_eq!(differences.len(), 2);
+    assert_eq!(
+        differences[0].to_string(),
+        "F expected B0 got 80 (Z-HC / Z---)"
+    );
+    assert_eq!(
+        differences[1].to_string(),
+        "PCMEM expected 00,C3,13,02 got 00,C3,13,03"
+    );
+}
+
+#[test]
+fn buffer_lines() {
+    let mut buffer = TraceBuffer::default();
+    write!(buffer, "first\nsec").unwrap();
+    assert_eq!(buffer.take_lines(), vec!["first"]);
+    assert!(buffer.take_lines().is_empty());
+    writeln!(buffer, "ond").unwrap();
+    assert_eq!(buffer.take_lines(), vec!["second"]);
+}
+
+/// Reference trace of PROGRAM from its second instruction
+const REFERENCE: [&str; 3] = [
+    "A:12 F:00 B:00 C:00 D:00 E:00 H:00 L:00 SP:FFFE PC:0102 PCMEM:47,04,21,00",
+    "A:12 F:00 B:12 C:00 D:00 E:00 H:00 L:00 SP:FFFE PC:0103 PCMEM:04,21,00,C0",
+    "A:12 F:00 B:13 C:00 D:00 E:00 H:00 L:00 SP:FFFE PC:0104 PCMEM:21,00,C0,00",
+];
+
+#[test]
+fn matching_reference() {
+    let reference = REFERENCE.iter().map(|line| line.to_string()).collect();
+    let mut comparison = TraceComparison::new(reference, 2).unwrap();
+    for line in trace(&PROGRAM, TraceFormat::Detailed, TraceFilter::default()) {
+        assert!(comparison.check(line).is_none());
+    }
+    // The first line is skipped until PC reaches the one of the reference
+    assert!(comparison.is_complete());
+    assert_eq!(comparison.get_matched(), 3);
+}
+
+#[test]
+fn first_divergence() {
+    let mut reference: Vec<String> = REFERENCE.iter().map(|line| line.to_string()).collect();
+    reference[2] = reference[2].replace("B:13", "B:14");
+    let mut comparison = TraceComparison::new(reference, 1).unwrap();
+    let divergence = trace(&PROGRAM, TraceFormat::Detailed, TraceFilter::default())
+        .into_iter()
+        .find_map(|line| comparison.check(line))
+        .unwrap();
+    assert_eq!(divergence.line_index, 2);
+    assert_eq!(divergence.expected, REFERENCE[2].replace("B:13", "B:14"));
+    assert!(
+        divergence.actual.contains("PC:0104"),
+        "{}",
+        divergence.actual
+    );
+    assert_eq!(divergence.differences.len(), 1);
+    assert_eq!(
+        divergence.differences[0].to_string(),
+        "B expected 14 got 13"
+    );
+    // Only the last matching line is kept with a context of 1
+    assert_eq!(divergence.history.len(), 1);
+    assert!(divergence.history[0].contains("PC:0103"));
+    assert!(!comparison.is_complete());
+}
+
+#[test]
+fn reference_without_pc() {
+    assert!(TraceComparison::new(vec!["A:00 F:00".to_string()], 10).is_none());
+    assert!(TraceComparison::new(vec![], 10).is_none());
+}